@@ -0,0 +1,138 @@
+//! This module benchmarks the throughput of the cache under concurrent searches, the lookups of
+//! the shared cache are compared with the same lookups serialized by a global mutex (which is how
+//! the cache used to be shared between the actix workers). When the `disk-cache` feature is
+//! enabled the lookups of the pooled connections to the SQLite file are measured as well.
+
+use std::sync::OnceLock;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::{runtime::Runtime, sync::Mutex};
+#[cfg(feature = "disk-cache")]
+use websurfx::cache::{cacher::Cacher, disk_cacher::DiskCache};
+use websurfx::{
+    cache::cacher::{create_cache, SharedCache},
+    config::parser::Config,
+    models::aggregation_models::{SearchResult, SearchResults},
+};
+
+/// A constant holding the number of cache lookups done by every concurrent search task.
+const LOOKUPS_PER_TASK: usize = 100;
+
+/// A constant holding the number of search result pages stored in the cache.
+const CACHED_PAGES: usize = 64;
+
+/// A static variable holding the cache shared by the benchmarks.
+static CACHE: OnceLock<SharedCache> = OnceLock::new();
+
+#[cfg(feature = "disk-cache")]
+/// A static variable holding the disk cache measured by the benchmarks.
+static DISK_CACHE: OnceLock<SharedCache> = OnceLock::new();
+
+/// A static variable holding the urls of the cached search result pages.
+static URLS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// A static variable holding the global mutex used to serialize the lookups of the baseline.
+static LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A helper function which builds a page of search results like the ones cached by a search.
+fn search_results() -> SearchResults {
+    let results: Vec<SearchResult> = (0..10)
+        .map(|index| {
+            SearchResult::new(
+                &format!("Result {index} about rust"),
+                &format!("https://www.example.com/rust/{index}"),
+                "A description of the result which is about as long as the usual descriptions.",
+                &["duckduckgo", "brave"],
+            )
+        })
+        .collect();
+    SearchResults::new(results.into_boxed_slice(), Box::new([]))
+}
+
+/// A helper function which runs the concurrent search tasks, every one of them looking up the
+/// cached pages in turn.
+///
+/// # Arguments
+///
+/// * `cache` - It takes the cache to look up as an argument.
+/// * `tasks` - It takes the number of concurrent search tasks as an argument.
+/// * `serialized` - It takes whether the lookups should be serialized by the global mutex as an
+///   argument.
+async fn searches(cache: &'static SharedCache, tasks: usize, serialized: bool) {
+    let urls = URLS.get().unwrap();
+    let handles: Vec<_> = (0..tasks)
+        .map(|task| {
+            tokio::spawn(async move {
+                for lookup in 0..LOOKUPS_PER_TASK {
+                    let url = &urls[(task + lookup) % urls.len()];
+                    let _guard = match serialized {
+                        true => Some(LOCK.lock().await),
+                        false => None,
+                    };
+                    assert!(cache.cached_results(url).await.is_ok());
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+/// A benchmark function which measures the throughput of the cache lookups for an increasing
+/// number of concurrent searches.
+fn concurrent_searches(c: &mut Criterion) {
+    let runtime: Runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let config = Config::parse(false).unwrap();
+    let cache = CACHE.get_or_init(|| SharedCache::new(runtime.block_on(create_cache(&config))));
+    let urls = URLS.get_or_init(|| {
+        (0..CACHED_PAGES)
+            .map(|page| format!("http://127.0.0.1:8080/search?q=rust&page={page}"))
+            .collect()
+    });
+    runtime
+        .block_on(cache.cache_results(&vec![search_results(); CACHED_PAGES], urls))
+        .unwrap();
+
+    // The SQLite file is removed once the benchmarks are done.
+    #[cfg(feature = "disk-cache")]
+    let directory = tempfile::tempdir().unwrap();
+    #[cfg(feature = "disk-cache")]
+    let disk_cache = {
+        let mut config = config.clone();
+        config.disk_cache.path = directory
+            .path()
+            .join("cache.db")
+            .to_string_lossy()
+            .into_owned();
+        let disk_cache = DISK_CACHE.get_or_init(|| {
+            SharedCache::new(runtime.block_on(<DiskCache as Cacher>::build(&config)))
+        });
+        runtime
+            .block_on(disk_cache.cache_results(&vec![search_results(); CACHED_PAGES], urls))
+            .unwrap();
+        disk_cache
+    };
+
+    let mut group = c.benchmark_group("concurrent_searches");
+    for tasks in [1, 8, 64] {
+        group.throughput(Throughput::Elements((tasks * LOOKUPS_PER_TASK) as u64));
+        group.bench_with_input(BenchmarkId::new("shared", tasks), &tasks, |b, &tasks| {
+            b.iter(|| runtime.block_on(searches(cache, tasks, false)))
+        });
+        group.bench_with_input(BenchmarkId::new("mutex", tasks), &tasks, |b, &tasks| {
+            b.iter(|| runtime.block_on(searches(cache, tasks, true)))
+        });
+        #[cfg(feature = "disk-cache")]
+        group.bench_with_input(BenchmarkId::new("disk", tasks), &tasks, |b, &tasks| {
+            b.iter(|| runtime.block_on(searches(disk_cache, tasks, false)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, concurrent_searches);
+criterion_main!(benches);
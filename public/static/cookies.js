@@ -27,11 +27,18 @@ function setClientSettingsOnPage(cookie) {
       case 'animations':
         select_tag.value = cookie_value['animation']
         break
+      case 'languages':
+        select_tag.value = cookie_value['language'] ?? ''
+        break
       case 'safe_search_levels':
         select_tag.value = cookie_value['safe_search_level']
         break
     }
   })
+  // The highlighting is enabled when it is missing from the older cookies.
+  document.querySelector('.highlight_query').checked =
+    cookie_value['highlight_query'] ?? true
+
   let engines = document.querySelectorAll('.engine')
   let engines_cookie = cookie_value['engines']
 
@@ -70,24 +77,25 @@ function setClientSettingsOnPage(cookie) {
 document.addEventListener(
   'DOMContentLoaded',
   () => {
+    // The messages are provided by the page as they are translated.
+    let cookie_input = document.querySelector('.cookies input')
     try {
       // Decode the cookie value
       let cookie = decodeURIComponent(document.cookie)
       // Set the value of the input field to the decoded cookie value if it is not empty
       // Otherwise, display a message indicating that no cookies have been saved on the user's system
       if (cookie.length) {
-        document.querySelector('.cookies input').value = cookie
+        cookie_input.value = cookie
         // This function displays the user provided settings on the settings page.
         setClientSettingsOnPage(cookie)
       } else {
-        document.querySelector('.cookies input').value =
-          'No cookies have been saved on your system'
+        cookie_input.value = cookie_input.dataset.noCookies
       }
     } catch (error) {
       // If there is an error decoding the cookie, log the error to the console
       // and display an error message in the input field
       console.error('Error decoding cookie:', error)
-      document.querySelector('.cookies input').value = 'Error decoding cookie'
+      cookie_input.value = cookie_input.dataset.cookieError
     }
   },
   false,
@@ -5,3 +5,15 @@
 function toggleErrorBox() {
     document.querySelector('.dropdown_error_box').classList.toggle('show')
 }
+
+/**
+ * This function binds the buttons toggling the error-box once the page has been loaded, as the
+ * content security policy does not allow inline event handlers.
+ *
+ * @listens DOMContentLoaded
+ */
+document.addEventListener('DOMContentLoaded', () => {
+    document
+        .querySelectorAll('.error_box_toggle_button')
+        .forEach((button) => button.addEventListener('click', toggleErrorBox))
+})
@@ -4,3 +4,15 @@
 function clearSearchText() {
     document.querySelector('.search_bar > input').value = ''
 }
+
+/**
+ * This function binds the clear button of the search bar once the page has been loaded, as the
+ * content security policy does not allow inline event handlers.
+ *
+ * @listens DOMContentLoaded
+ */
+document.addEventListener('DOMContentLoaded', () => {
+    document
+        .querySelector('.search_bar > .clear_button')
+        ?.addEventListener('click', clearSearchText)
+})
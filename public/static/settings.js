@@ -26,10 +26,11 @@ function setActiveTab(current_tab) {
     .querySelectorAll('.btn')
     .forEach((tab) => tab.classList.remove('active'))
 
-  // Add the active class to the current tab and its corresponding settings
+  // Add the active class to the current tab and its corresponding settings, the tab is
+  // matched by its `data-tab` attribute as the text of the button is translated.
   current_tab.classList.add('active')
   document
-    .querySelector(`.${current_tab.innerText.toLowerCase().replace(' ', '_')}`)
+    .querySelector(`.${current_tab.dataset.tab}`)
     .classList.add('active')
 }
 
@@ -53,6 +54,9 @@ function setClientSettings() {
       case 'animations':
         cookie_dictionary['animation'] = select_tag.value || null
         break
+      case 'languages':
+        cookie_dictionary['language'] = select_tag.value || null
+        break
       case 'safe_search_levels':
         cookie_dictionary['safe_search_level'] = Number(select_tag.value)
         break
@@ -70,6 +74,10 @@ function setClientSettings() {
 
   cookie_dictionary['engines'] = engines
 
+  // Save whether the words of the query should be highlighted in the results
+  cookie_dictionary['highlight_query'] =
+    document.querySelector('.highlight_query').checked
+
   // Set the expiration date for the cookie to 1 year from the current date
   let expiration_date = new Date()
   expiration_date.setFullYear(expiration_date.getFullYear() + 1)
@@ -80,8 +88,8 @@ function setClientSettings() {
   )}; expires=${expiration_date.toUTCString()}`
 
   // Display a success message to the user
-  document.querySelector('.message').innerText =
-    '✅ The settings have been saved sucessfully!!'
+  let message = document.querySelector('.message')
+  message.innerText = message.dataset.saved
 
   // Clear the success message after 10 seconds
   setTimeout(() => {
@@ -89,6 +97,56 @@ function setClientSettings() {
   }, 10000)
 }
 
+/**
+ * This function copies the url of the search page carrying the preferences selected in the
+ * settings page as url parameters, which can be used by the clients which do not keep cookies
+ * (the search query is added to it as the `q` parameter).
+ */
+function copySettingsUrl() {
+  let params = new URLSearchParams()
+
+  document.querySelectorAll('select').forEach((select_tag) => {
+    switch (select_tag.name) {
+      case 'themes':
+        params.set('theme', select_tag.value)
+        break
+      case 'colorschemes':
+        params.set('colorscheme', select_tag.value)
+        break
+      case 'animations':
+        params.set('animation', select_tag.value)
+        break
+      case 'languages':
+        if (select_tag.value) {
+          params.set('language', select_tag.value)
+        }
+        break
+      case 'safe_search_levels':
+        params.set('safesearch', select_tag.value)
+        break
+    }
+  })
+
+  let engines = []
+  document.querySelectorAll('.engine').forEach((engine_checkbox) => {
+    if (engine_checkbox.checked) {
+      engines.push(engine_checkbox.parentNode.parentNode.innerText.trim())
+    }
+  })
+  params.set('engines', engines.join(','))
+  params.set(
+    'highlight_query',
+    document.querySelector('.highlight_query').checked,
+  )
+
+  let url = new URL(`search?${params}`, document.baseURI).href
+  let message = document.querySelector('.message')
+  navigator.clipboard
+    .writeText(url)
+    .then(() => (message.innerText = message.dataset.copiedUrl))
+    .catch(() => (message.innerText = url))
+}
+
 /**
  * This functions gets the saved cookies if it is present on the user's machine If it
  * is available then it is parsed and converted to an object which is then used to
@@ -99,6 +157,13 @@ function getClientSettings() {
   // Get the appCookie from the user's machine
   let cookie = decodeURIComponent(document.cookie)
 
+  // The styles passed as url parameters are applied by the server and take precedence over
+  // the cookie.
+  let params = new URLSearchParams(window.location.search)
+  if (['theme', 'colorscheme', 'animation'].some((name) => params.has(name))) {
+    return
+  }
+
   // If the cookie is not empty, parse it and use it to set the user's preferences
   if (cookie.length) {
     let cookie_value = cookie
@@ -153,3 +218,37 @@ function getClientSettings() {
     }
   }
 }
+
+/**
+ * This function loads the user preferences and binds the controls of the settings page once the
+ * page has been loaded, as the content security policy does not allow inline event handlers.
+ *
+ * @listens DOMContentLoaded
+ */
+document.addEventListener('DOMContentLoaded', () => {
+  getClientSettings()
+
+  document
+    .querySelectorAll('.settings_container .sidebar .btn')
+    .forEach((tab) => tab.addEventListener('click', () => setActiveTab(tab)))
+  document
+    .querySelector('.select_all')
+    ?.addEventListener('change', toggleAllSelection)
+  document
+    .querySelector('.save_button')
+    ?.addEventListener('click', setClientSettings)
+  document
+    .querySelector('.copy_settings_url_button')
+    ?.addEventListener('click', copySettingsUrl)
+  // Reload the page once the restored preferences have been submitted.
+  document
+    .querySelector('.restore_form')
+    ?.addEventListener('submit', () =>
+      setTimeout(() => window.location.reload(), 10),
+    )
+  // Show the full link of the profile so that it can be shared as is.
+  const profileLink = document.querySelector('.profile_link')
+  if (profileLink) {
+    profileLink.value = new URL(profileLink.value, window.location.origin).href
+  }
+})
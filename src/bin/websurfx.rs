@@ -5,8 +5,21 @@
 #[cfg(not(feature = "dhat-heap"))]
 use mimalloc::MiMalloc;
 
+use clap::{Parser, Subcommand, ValueEnum};
 use std::{net::TcpListener, sync::OnceLock};
-use websurfx::{cache::cacher::create_cache, config::parser::Config, run};
+use websurfx::{
+    cache::cacher::create_cache,
+    config::{
+        format::convert_to_toml,
+        parser::{set_overrides, Config},
+        reload::{self, SharedConfig},
+        validation::Severity,
+    },
+    handler::{file_path, set_config_file_path, FileType},
+    models::{engine_models::EngineHandler, parser_models::ConfigOverrides},
+    results::aggregator::aggregate,
+    run, run_metrics,
+};
 
 /// A dhat heap memory profiler
 #[cfg(feature = "dhat-heap")]
@@ -18,7 +31,76 @@ static ALLOC: dhat::Alloc = dhat::Alloc;
 static GLOBAL: MiMalloc = MiMalloc;
 
 /// A static constant for holding the parsed config.
-static CONFIG: OnceLock<Config> = OnceLock::new();
+static CONFIG: OnceLock<SharedConfig> = OnceLock::new();
+
+/// A constant holding the default config file shipped with the app.
+const DEFAULT_CONFIG: &str = include_str!("../../websurfx/config.lua");
+
+/// An open-source meta search engine which aggregates the results of the upstream search engines.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Path of the config file to use instead of searching the default config paths.
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<String>,
+    /// Port on which the server should be launched (overrides the `port` config option).
+    #[arg(long)]
+    port: Option<u16>,
+    /// IP address on which the server should be launched (overrides the `binding_ip` config
+    /// option).
+    #[arg(long, value_name = "IP")]
+    bind: Option<String>,
+    /// Print the default config file and exit.
+    #[arg(long)]
+    print_default_config: bool,
+    /// Validate the config file, report all of its issues and exit.
+    #[arg(long)]
+    check_config: bool,
+    /// The command to run instead of launching the server.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// An enum type which provides the different commands of the app.
+#[derive(Subcommand)]
+enum Command {
+    /// Search the upstream search engines once and print the results without launching the
+    /// server.
+    Search {
+        /// The search query.
+        query: String,
+        /// The page of the search results to fetch.
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+        /// The output format of the search results.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// Comma separated names of the upstream search engines to use instead of the ones
+        /// enabled in the config file.
+        #[arg(long, value_delimiter = ',')]
+        engines: Vec<String>,
+        /// The safe search level to use instead of the one set in the config file.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=4))]
+        safe_search: Option<u8>,
+    },
+    /// Probe the readiness route of a running server and exit with a non zero code if it is not
+    /// ready, this allows the container health checks to work without shipping any external
+    /// tools (like curl) in the docker image.
+    Healthcheck,
+    /// Convert the lua config file into the toml format and print it, the output can be saved as
+    /// `config.toml` in place of the `config.lua` file (which takes precedence when both of them
+    /// are in the same directory).
+    ConvertConfig,
+}
+
+/// An enum type which provides the different output formats of the `search` command.
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Human readable text.
+    Text,
+    /// A JSON object holding the search results and the engine errors.
+    Json,
+}
 
 /// The function that launches the main server and registers all the routes of the website.
 ///
@@ -32,10 +114,59 @@ async fn main() -> std::io::Result<()> {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
 
+    let cli = Cli::parse();
+
+    if cli.print_default_config {
+        print!("{DEFAULT_CONFIG}");
+        return Ok(());
+    }
+
+    if let Some(config_path) = cli.config {
+        set_config_file_path(config_path);
+    }
+    set_overrides(ConfigOverrides {
+        port: cli.port,
+        binding_ip: cli.bind,
+    });
+
+    // Validate the config file and report all of its issues without launching the server.
+    if cli.check_config {
+        std::process::exit(check_config());
+    }
+
+    match cli.command {
+        Some(Command::Search {
+            query,
+            page,
+            format,
+            engines,
+            safe_search,
+        }) => {
+            let exit_code = search(&query, page, format, &engines, safe_search)
+                .await
+                .unwrap_or_else(|error| {
+                    eprintln!("error: {error}");
+                    1
+                });
+            std::process::exit(exit_code);
+        }
+        Some(Command::Healthcheck) => std::process::exit(healthcheck().await),
+        Some(Command::ConvertConfig) => std::process::exit(convert_config()),
+        None => (),
+    }
+
     // Initialize the parsed config globally.
-    let config = CONFIG.get_or_init(|| Config::parse(false).unwrap());
+    let shared_config = CONFIG.get_or_init(|| match Config::parse(false) {
+        Ok(config) => SharedConfig::new(config),
+        Err(error) => {
+            eprintln!("Config Error: {error}");
+            eprintln!("Run `websurfx --check-config` to list all the issues of the config file");
+            std::process::exit(1);
+        }
+    });
+    let config = shared_config.load();
 
-    let cache = create_cache(config).await;
+    let cache = create_cache(&config).await;
 
     log::info!(
         "started server on port {} and IP {}",
@@ -48,7 +179,164 @@ async fn main() -> std::io::Result<()> {
         config.port,
     );
 
+    if let (true, Some(metrics_port)) = (config.metrics.enabled, config.metrics.port) {
+        log::info!(
+            "serving metrics on http://{}:{}/metrics",
+            config.binding_ip,
+            metrics_port
+        );
+        let metrics_listener = TcpListener::bind((config.binding_ip.as_str(), metrics_port))?;
+        actix_web::rt::spawn(run_metrics(metrics_listener)?);
+    }
+
     let listener = TcpListener::bind((config.binding_ip.as_str(), config.port))?;
 
-    run(listener, config, cache)?.await
+    // Reload the config on `SIGHUP` or when the config file is modified.
+    reload::watch(shared_config);
+
+    run(listener, shared_config, cache)?.await
+}
+
+/// A helper function which validates the config file and prints all the issues found in it.
+///
+/// # Returns
+///
+/// It returns the exit code of the app which is non zero if the config file could not be loaded
+/// or has any errors.
+fn check_config() -> i32 {
+    match Config::check() {
+        Ok(issues) => {
+            for issue in &issues {
+                eprintln!("{issue}");
+            }
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.severity == Severity::Error)
+                .count();
+            println!(
+                "config check finished with {} error(s) and {} warning(s)",
+                errors,
+                issues.len() - errors
+            );
+            i32::from(errors > 0)
+        }
+        Err(error) => {
+            eprintln!("error: failed to load the config file: {error}");
+            1
+        }
+    }
+}
+
+/// A helper function which converts the lua config file into the toml format and prints it.
+///
+/// # Returns
+///
+/// It returns the exit code of the app which is non zero if the config file could not be found
+/// or converted.
+fn convert_config() -> i32 {
+    match file_path(FileType::Config)
+        .map_err(Box::from)
+        .and_then(convert_to_toml)
+    {
+        Ok(document) => {
+            print!("{document}");
+            0
+        }
+        Err(error) => {
+            eprintln!("error: failed to convert the config file: {error}");
+            1
+        }
+    }
+}
+
+/// A helper function which probes the readiness route of an already running server.
+///
+/// # Returns
+///
+/// It returns the exit code of the app which is non zero if the config file could not be loaded
+/// or the server is not ready.
+async fn healthcheck() -> i32 {
+    let Ok(config) = Config::parse(true) else {
+        return 1;
+    };
+    // The readiness route is served under the path prefix like the other routes.
+    let ready = reqwest::get(format!(
+        "http://{}:{}{}/readyz",
+        config.binding_ip, config.port, config.base_path
+    ))
+    .await
+    .is_ok_and(|res| res.status().is_success());
+    i32::from(!ready)
+}
+
+/// A helper function which fetches the search results of the provided query from the upstream
+/// search engines once and prints them on the stdout. The errors of the engines are printed on
+/// the stderr.
+///
+/// # Arguments
+///
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number (starting from 1) of the search results as an argument.
+/// * `format` - It takes the output format of the search results as an argument.
+/// * `engines` - It takes the names of the upstream search engines to use as an argument, the
+///   ones enabled in the config file are used when it is empty.
+/// * `safe_search` - It takes the optional safe search level to use as an argument.
+///
+/// # Error
+///
+/// Returns an error if the config file could not be loaded, an engine is unknown or the results
+/// could not be aggregated otherwise it returns the exit code of the app, which is non zero if
+/// no results were found because of the errors of the engines.
+async fn search(
+    query: &str,
+    page: u32,
+    format: OutputFormat,
+    engines: &[String],
+    safe_search: Option<u8>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    // The logging is not initialized so that only the search results are printed.
+    let config = Config::parse(true)?;
+
+    let engines = match engines.is_empty() {
+        true => config
+            .upstream_search_engines
+            .iter()
+            .filter_map(|(engine, enabled)| enabled.then(|| EngineHandler::new(engine)))
+            .collect::<Result<Vec<EngineHandler>, _>>(),
+        false => engines
+            .iter()
+            .map(|engine| EngineHandler::new(engine))
+            .collect(),
+    }
+    .map_err(|error| error.to_string())?;
+
+    let results = aggregate(
+        query,
+        page.max(1) - 1,
+        &config,
+        &engines,
+        safe_search.unwrap_or(config.safe_search),
+    )
+    .await?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&results)?),
+        OutputFormat::Text => {
+            for (index, result) in results.results.iter().enumerate() {
+                println!("{}. {}", index + 1, result.title);
+                println!("   {}", result.url);
+                println!("   {}", result.description);
+                println!("   [{}]", result.engine.join(", "));
+                println!();
+            }
+        }
+    }
+
+    for error in results.engine_errors_info.iter() {
+        eprintln!("engine error: {}: {}", error.engine, error.error);
+    }
+
+    Ok(i32::from(
+        results.results.is_empty() && !results.engine_errors_info.is_empty(),
+    ))
 }
@@ -6,12 +6,25 @@ use futures::future::join_all;
 #[cfg(feature = "memory-cache")]
 use moka::future::Cache as MokaCache;
 
-#[cfg(feature = "memory-cache")]
-use std::time::Duration;
-use tokio::sync::Mutex;
-
-use crate::{config::parser::Config, models::aggregation_models::SearchResults};
-
+use serde::{Deserialize, Serialize};
+#[cfg(all(
+    feature = "redis-cache",
+    any(feature = "memory-cache", feature = "disk-cache")
+))]
+use std::sync::OnceLock;
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::{Mutex as StdMutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config::parser::Config, metrics::registry::registry, models::aggregation_models::SearchResults,
+};
+
+#[cfg(feature = "disk-cache")]
+use super::disk_cacher::DiskCache;
 use super::error::CacheError;
 #[cfg(feature = "redis-cache")]
 use super::redis_cacher::RedisCache;
@@ -19,7 +32,40 @@ use super::redis_cacher::RedisCache;
 #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
 use super::encryption::*;
 
-/// Abstraction trait for common methods provided by a cache backend.
+/// A named struct which stores the search results fetched from the cache with the time at which
+/// they have been cached.
+#[derive(Deserialize, Clone)]
+pub struct CachedResults {
+    /// It stores the unix time in seconds at which the results have been cached.
+    pub cached_at: u64,
+    /// It stores the cached search results.
+    pub results: SearchResults,
+}
+
+/// An enum type which provides the outcome of a lookup of the search results in the cache.
+pub enum CacheLookup {
+    /// The results have been cached within the expiry time.
+    Fresh(SearchResults),
+    /// The results are past the expiry time but can still be served while they are refreshed.
+    Stale(SearchResults),
+}
+
+/// A named struct which stores the number of search results cached in a cache backend and the
+/// number of bytes they take up.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct CacheStats {
+    /// It stores the name of the cache backend.
+    pub backend: &'static str,
+    /// It stores the number of cached search results.
+    pub entries: u64,
+    /// It stores the number of bytes taken up by the cached search results (as stored in the
+    /// backend, after the compression and the encryption).
+    pub bytes: u64,
+}
+
+/// Abstraction trait for common methods provided by a cache backend. The methods take a shared
+/// reference so that the backends, which are internally concurrent, can serve many requests at
+/// the same time.
 #[async_trait::async_trait]
 pub trait Cacher: Send + Sync {
     // A function that builds the cache from the given configuration.
@@ -43,9 +89,9 @@ pub trait Cacher: Send + Sync {
     ///
     /// # Error
     ///
-    /// Returns the `SearchResults` from the cache if the program executes normally otherwise
+    /// Returns the `CachedResults` from the cache if the program executes normally otherwise
     /// returns a `CacheError` if the results cannot be retrieved from the cache.
-    async fn cached_results(&mut self, url: &str) -> Result<SearchResults, Report<CacheError>>;
+    async fn cached_results(&self, url: &str) -> Result<CachedResults, Report<CacheError>>;
 
     /// A function which caches the results by using the `url` as the key and
     /// `json results` as the value and stores it in the cache
@@ -61,11 +107,106 @@ pub trait Cacher: Send + Sync {
     /// otherwise it returns a `CacheError` if the search results cannot be cached due to a
     /// failure.
     async fn cache_results(
-        &mut self,
+        &self,
         search_results: &[SearchResults],
         urls: &[String],
     ) -> Result<(), Report<CacheError>>;
 
+    /// A function which checks whether the cache backend is usable or not.
+    ///
+    /// # Error
+    ///
+    /// Returns a unit type if the cache backend is able to serve requests otherwise returns a
+    /// `CacheError` describing why it is unusable.
+    async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        Ok(())
+    }
+
+    /// A function which fetches the preferences saved in the profile stored under the provided
+    /// key. The backends which can not store the profiles never find any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the preferences as a json string on success otherwise returns a `CacheError` if
+    /// the profile is missing or can not be retrieved.
+    async fn cached_profile(&self, _key: &str) -> Result<String, Report<CacheError>> {
+        Err(Report::new(CacheError::MissingValue))
+    }
+
+    /// A function which stores the preferences in the profile under the provided key, unlike
+    /// the search results the profiles do not expire.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    /// * `preferences` - It takes the preferences as a json string as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns a unit type if the profile is stored successfully otherwise returns a
+    /// `CacheError` on a failure or if the backend can not store the profiles.
+    async fn cache_profile(
+        &self,
+        _key: &str,
+        _preferences: &str,
+    ) -> Result<(), Report<CacheError>> {
+        Err(Report::new(CacheError::UnsupportedOperation))
+    }
+
+    /// A function which removes the cached search results stored under the provided urls.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - It takes the urls used as the keys of the cached results as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError` on a
+    /// failure or if the backend can not remove the results.
+    async fn delete_results(&self, _urls: &[String]) -> Result<u64, Report<CacheError>> {
+        Err(Report::new(CacheError::UnsupportedOperation))
+    }
+
+    /// A function which removes all the cached search results whose url starts with the provided
+    /// prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - It takes the prefix of the urls of the results to remove as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError` on a
+    /// failure or if the backend can not remove the results.
+    async fn delete_results_with_prefix(&self, _prefix: &str) -> Result<u64, Report<CacheError>> {
+        Err(Report::new(CacheError::UnsupportedOperation))
+    }
+
+    /// A function which removes all the cached search results, the profiles are kept.
+    ///
+    /// # Error
+    ///
+    /// Returns a unit type if the results are removed successfully otherwise returns a
+    /// `CacheError` on a failure or if the backend can not remove the results.
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        Err(Report::new(CacheError::UnsupportedOperation))
+    }
+
+    /// A function which counts the cached search results and the bytes they take up in each of
+    /// the backends.
+    ///
+    /// # Error
+    ///
+    /// Returns the statistics of the backends on success otherwise returns a `CacheError` on a
+    /// failure or if the backend can not count the results.
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        Err(Report::new(CacheError::UnsupportedOperation))
+    }
+
     /// A helper function which computes the hash of the url and formats and returns it as string.
     ///
     /// # Arguments
@@ -93,32 +234,16 @@ pub trait Cacher: Send + Sync {
         feature = "cec-cache-results"
     ))]
     async fn encrypt_or_decrypt_results(
-        &mut self,
+        &self,
         mut bytes: Vec<u8>,
         encrypt: bool,
     ) -> Result<Vec<u8>, Report<CacheError>> {
-        use chacha20poly1305::{
-            aead::{Aead, AeadCore, KeyInit, OsRng},
-            ChaCha20Poly1305,
-        };
-
-        let cipher = CIPHER.get_or_init(|| {
-            let key = ChaCha20Poly1305::generate_key(&mut OsRng);
-            ChaCha20Poly1305::new(&key)
-        });
-
-        let encryption_key = ENCRYPTION_KEY.get_or_init(
-            || ChaCha20Poly1305::generate_nonce(&mut OsRng), // 96-bits; unique per message
-        );
+        let keyring = KEYRING.get_or_init(|| Keyring::new(&[]));
 
         bytes = if encrypt {
-            cipher
-                .encrypt(encryption_key, bytes.as_ref())
-                .map_err(|_| CacheError::EncryptionError)?
+            keyring.encrypt(&bytes)?
         } else {
-            cipher
-                .decrypt(encryption_key, bytes.as_ref())
-                .map_err(|_| CacheError::EncryptionError)?
+            keyring.decrypt(&bytes)?
         };
 
         Ok(bytes)
@@ -136,10 +261,7 @@ pub trait Cacher: Send + Sync {
     /// Returns the compressed bytes on success otherwise it returns a CacheError
     /// on failure.
     #[cfg(any(feature = "compress-cache-results", feature = "cec-cache-results"))]
-    async fn compress_results(
-        &mut self,
-        mut bytes: Vec<u8>,
-    ) -> Result<Vec<u8>, Report<CacheError>> {
+    async fn compress_results(&self, mut bytes: Vec<u8>) -> Result<Vec<u8>, Report<CacheError>> {
         use tokio::io::AsyncWriteExt;
         let mut writer = async_compression::tokio::write::BrotliEncoder::new(Vec::new());
         writer
@@ -167,7 +289,7 @@ pub trait Cacher: Send + Sync {
     /// on failure.
     #[cfg(feature = "cec-cache-results")]
     async fn compress_encrypt_compress_results(
-        &mut self,
+        &self,
         mut bytes: Vec<u8>,
     ) -> Result<Vec<u8>, Report<CacheError>> {
         // compress first
@@ -194,7 +316,7 @@ pub trait Cacher: Send + Sync {
     /// on failure.
 
     #[cfg(any(feature = "compress-cache-results", feature = "cec-cache-results"))]
-    async fn decompress_results(&mut self, bytes: &[u8]) -> Result<Vec<u8>, Report<CacheError>> {
+    async fn decompress_results(&self, bytes: &[u8]) -> Result<Vec<u8>, Report<CacheError>> {
         cfg_if::cfg_if! {
              if #[cfg(feature = "compress-cache-results")]
             {
@@ -222,11 +344,14 @@ pub trait Cacher: Send + Sync {
     /// Returns a Vec of compressed or encrypted bytes on success otherwise it returns a CacheError
     /// on failure.
     async fn pre_process_search_results(
-        &mut self,
+        &self,
         search_results: &SearchResults,
     ) -> Result<Vec<u8>, Report<CacheError>> {
+        // The results are stored with the time at which they are cached in the same layout as
+        // the `CachedResults` struct, so that the stale results can be told apart.
         #[allow(unused_mut)] // needs to be mutable when any of the features is enabled
-        let mut bytes: Vec<u8> = search_results.try_into()?;
+        let mut bytes: Vec<u8> = bincode::serialize(&(unix_time(), search_results))
+            .map_err(|_| CacheError::SerializationError)?;
         #[cfg(feature = "compress-cache-results")]
         {
             let compressed = self.compress_results(bytes).await?;
@@ -258,14 +383,14 @@ pub trait Cacher: Send + Sync {
 
     ///
     /// # Error
-    /// Returns the SearchResults struct on success otherwise it returns a CacheError
+    /// Returns the CachedResults struct on success otherwise it returns a CacheError
     /// on failure.
 
     #[allow(unused_mut)] // needs to be mutable when any of the features is enabled
     async fn post_process_search_results(
-        &mut self,
+        &self,
         mut bytes: Vec<u8>,
-    ) -> Result<SearchResults, Report<CacheError>> {
+    ) -> Result<CachedResults, Report<CacheError>> {
         #[cfg(feature = "compress-cache-results")]
         {
             let decompressed = self.decompress_results(&bytes).await?;
@@ -325,12 +450,12 @@ impl Cacher for RedisCache {
             "Initialising redis cache. Listening to {}",
             &config.redis_url
         );
-        RedisCache::new(&config.redis_url, 5, config.cache_expiry_time)
+        RedisCache::new(&config.redis_url, &config.redis, cache_ttl(config))
             .await
-            .expect("Redis cache configured")
+            .unwrap_or_else(|error| panic!("Failed to connect to the redis cache: {error}"))
     }
 
-    async fn cached_results(&mut self, url: &str) -> Result<SearchResults, Report<CacheError>> {
+    async fn cached_results(&self, url: &str) -> Result<CachedResults, Report<CacheError>> {
         use base64::Engine;
         let hashed_url_string: &str = &self.hash_url(url);
         let base64_string = self.cached_json(hashed_url_string).await?;
@@ -342,7 +467,7 @@ impl Cacher for RedisCache {
     }
 
     async fn cache_results(
-        &mut self,
+        &self,
         search_results: &[SearchResults],
         urls: &[String],
     ) -> Result<(), Report<CacheError>> {
@@ -368,34 +493,101 @@ impl Cacher for RedisCache {
             let hash = self.hash_url(url);
             hashed_url_strings.push(hash);
         }
-        self.cache_json(base64_strings, hashed_url_strings.into_iter())
-            .await
+        self.cache_json(
+            base64_strings,
+            urls.iter().cloned(),
+            hashed_url_strings.into_iter(),
+        )
+        .await
+    }
+
+    async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        self.ping().await
+    }
+
+    async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        self.fetch_json(key)
+            .await?
+            .ok_or_else(|| Report::new(CacheError::MissingValue))
+    }
+
+    async fn cache_profile(&self, key: &str, preferences: &str) -> Result<(), Report<CacheError>> {
+        self.store_json(key, preferences).await
+    }
+
+    async fn delete_results(&self, urls: &[String]) -> Result<u64, Report<CacheError>> {
+        let keys: Vec<String> = urls.iter().map(|url| self.hash_url(url)).collect();
+        self.delete_json(&keys).await
+    }
+
+    async fn delete_results_with_prefix(&self, prefix: &str) -> Result<u64, Report<CacheError>> {
+        self.delete_json_with_prefix(prefix).await
+    }
+
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        self.delete_all_json().await.map(|_| ())
+    }
+
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        let (entries, bytes) = self.usage().await?;
+        Ok(vec![CacheStats {
+            backend: "redis",
+            entries,
+            bytes,
+        }])
     }
 }
-/// TryInto implementation for SearchResults from Vec<u8>
+/// TryInto implementation for CachedResults from Vec<u8>
 use std::{convert::TryInto, sync::Arc};
 
-impl TryInto<SearchResults> for Vec<u8> {
+impl TryInto<CachedResults> for Vec<u8> {
     type Error = CacheError;
 
-    fn try_into(self) -> Result<SearchResults, Self::Error> {
+    fn try_into(self) -> Result<CachedResults, Self::Error> {
         bincode::deserialize_from(self.as_slice()).map_err(|_| CacheError::SerializationError)
     }
 }
 
-impl TryInto<Vec<u8>> for &SearchResults {
-    type Error = CacheError;
+/// A helper function which provides the current unix time in seconds.
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
 
-    fn try_into(self) -> Result<Vec<u8>, Self::Error> {
-        bincode::serialize(self).map_err(|_| CacheError::SerializationError)
-    }
+/// A helper function which provides the time to live of the cached results in the backends, the
+/// results are kept past the expiry time for the stale time so that they can be served while
+/// they are refreshed.
+///
+/// # Arguments
+///
+/// * `config` - It takes the config struct as an argument.
+#[cfg(any(
+    feature = "memory-cache",
+    feature = "redis-cache",
+    feature = "disk-cache"
+))]
+fn cache_ttl(config: &Config) -> u16 {
+    config
+        .cache_expiry_time
+        .saturating_add(config.cache_stale_time)
 }
 
+/// A constant holding the maximum number of profiles stored by the in-memory cache, the ones
+/// beyond it are evicted.
+#[cfg(feature = "memory-cache")]
+pub const MAX_IN_MEMORY_PROFILES: u64 = 10_000;
+
 /// Memory based cache backend.
 #[cfg(feature = "memory-cache")]
 pub struct InMemoryCache {
-    /// The backend cache which stores data.
-    cache: Arc<MokaCache<String, Vec<u8>>>,
+    /// The backend cache which stores the url of the cached results with their bytes under the
+    /// hash of the url, the url is kept so that the results can be removed by its prefix.
+    cache: Arc<MokaCache<String, (String, Vec<u8>)>>,
+    /// The backend cache which stores the profiles, they do not expire but are lost when the
+    /// server is restarted.
+    profiles: Arc<MokaCache<String, String>>,
 }
 
 #[cfg(feature = "memory-cache")]
@@ -403,6 +595,7 @@ impl Clone for InMemoryCache {
     fn clone(&self) -> Self {
         Self {
             cache: self.cache.clone(),
+            profiles: self.profiles.clone(),
         }
     }
 }
@@ -416,22 +609,23 @@ impl Cacher for InMemoryCache {
         InMemoryCache {
             cache: Arc::new(
                 MokaCache::builder()
-                    .time_to_live(Duration::from_secs(config.cache_expiry_time.into()))
+                    .time_to_live(Duration::from_secs(cache_ttl(config).into()))
                     .build(),
             ),
+            profiles: Arc::new(MokaCache::new(MAX_IN_MEMORY_PROFILES)),
         }
     }
 
-    async fn cached_results(&mut self, url: &str) -> Result<SearchResults, Report<CacheError>> {
+    async fn cached_results(&self, url: &str) -> Result<CachedResults, Report<CacheError>> {
         let hashed_url_string = self.hash_url(url);
         match self.cache.get(&hashed_url_string).await {
-            Some(res) => self.post_process_search_results(res).await,
+            Some((_, res)) => self.post_process_search_results(res).await,
             None => Err(Report::new(CacheError::MissingValue)),
         }
     }
 
     async fn cache_results(
-        &mut self,
+        &self,
         search_results: &[SearchResults],
         urls: &[String],
     ) -> Result<(), Report<CacheError>> {
@@ -440,8 +634,9 @@ impl Cacher for InMemoryCache {
             let hashed_url_string = self.hash_url(url);
             let bytes = self.pre_process_search_results(search_result).await?;
             let new_self = self.clone();
+            let url = url.clone();
             tasks.push(tokio::spawn(async move {
-                new_self.cache.insert(hashed_url_string, bytes).await
+                new_self.cache.insert(hashed_url_string, (url, bytes)).await
             }));
         }
 
@@ -449,51 +644,400 @@ impl Cacher for InMemoryCache {
 
         Ok(())
     }
+
+    async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        // The moka cache lives in the process memory and can't become unavailable, running the
+        // pending maintenance tasks ensures that it is still being serviced.
+        self.cache.run_pending_tasks().await;
+        Ok(())
+    }
+
+    async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        self.profiles
+            .get(key)
+            .await
+            .ok_or_else(|| Report::new(CacheError::MissingValue))
+    }
+
+    async fn cache_profile(&self, key: &str, preferences: &str) -> Result<(), Report<CacheError>> {
+        self.profiles
+            .insert(key.to_owned(), preferences.to_owned())
+            .await;
+        Ok(())
+    }
+
+    async fn delete_results(&self, urls: &[String]) -> Result<u64, Report<CacheError>> {
+        let mut deleted = 0;
+        for url in urls {
+            if self.cache.remove(&self.hash_url(url)).await.is_some() {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_results_with_prefix(&self, prefix: &str) -> Result<u64, Report<CacheError>> {
+        let keys: Vec<_> = self
+            .cache
+            .iter()
+            .filter(|(_, (url, _))| url.starts_with(prefix))
+            .map(|(key, _)| key)
+            .collect();
+        let mut deleted = 0;
+        for key in keys {
+            if self.cache.remove(key.as_str()).await.is_some() {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        self.cache.invalidate_all();
+        self.cache.run_pending_tasks().await;
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        // The entry count is only updated by the pending maintenance tasks.
+        self.cache.run_pending_tasks().await;
+        Ok(vec![CacheStats {
+            backend: "memory",
+            entries: self.cache.entry_count(),
+            bytes: self
+                .cache
+                .iter()
+                .map(|(_, (_, bytes))| bytes.len() as u64)
+                .sum(),
+        }])
+    }
 }
 
-/// Cache backend which utilises both memory and redis based caches.
+#[cfg(feature = "disk-cache")]
+#[async_trait::async_trait]
+impl Cacher for DiskCache {
+    async fn build(config: &Config) -> Self {
+        log::info!(
+            "Initialising disk cache. Storing in {}",
+            &config.disk_cache.path
+        );
+        let cache = DiskCache::open(
+            &config.disk_cache.path,
+            cache_ttl(config),
+            config.disk_cache.max_size,
+        )
+        .expect("Disk cache configured");
+        cache.spawn_compaction(Duration::from_secs(config.disk_cache.compaction_interval));
+        cache
+    }
+
+    async fn cached_results(&self, url: &str) -> Result<CachedResults, Report<CacheError>> {
+        let bytes = self.cached_bytes(&self.hash_url(url)).await?;
+        self.post_process_search_results(bytes).await
+    }
+
+    async fn cache_results(
+        &self,
+        search_results: &[SearchResults],
+        urls: &[String],
+    ) -> Result<(), Report<CacheError>> {
+        let mut entries = Vec::with_capacity(urls.len());
+        for (url, search_result) in urls.iter().zip(search_results.iter()) {
+            let bytes = self.pre_process_search_results(search_result).await?;
+            entries.push((self.hash_url(url), url.clone(), bytes));
+        }
+        self.cache_bytes(entries).await
+    }
+
+    async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        self.ping().await
+    }
+
+    async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        DiskCache::cached_profile(self, key).await
+    }
+
+    async fn cache_profile(&self, key: &str, preferences: &str) -> Result<(), Report<CacheError>> {
+        DiskCache::cache_profile(self, key, preferences).await
+    }
+
+    async fn delete_results(&self, urls: &[String]) -> Result<u64, Report<CacheError>> {
+        let keys = urls.iter().map(|url| self.hash_url(url)).collect();
+        Ok(self.delete_bytes(keys).await? as u64)
+    }
+
+    async fn delete_results_with_prefix(&self, prefix: &str) -> Result<u64, Report<CacheError>> {
+        Ok(self.delete_bytes_with_prefix(prefix).await? as u64)
+    }
+
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        self.delete_all_bytes().await.map(|_| ())
+    }
+
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        let (entries, bytes) = self.usage().await?;
+        Ok(vec![CacheStats {
+            backend: "disk",
+            entries,
+            bytes,
+        }])
+    }
+}
+
+/// Cache backend which utilises the memory, redis and disk based caches which are enabled.
 ///
-/// The hybrid cache system uses both the types of cache to ensure maximum availability.
-/// The set method sets the key, value pair in both the caches. Therefore in a case where redis
-/// cache becomes unavailable, the backend will retreive the value from in-memory cache.
-#[cfg(all(feature = "memory-cache", feature = "redis-cache"))]
+/// The hybrid cache system uses all the enabled types of cache to ensure maximum availability.
+/// The set method sets the key, value pair in all the caches. Therefore in a case where redis
+/// cache becomes unavailable, the backend will retreive the value from in-memory cache and then
+/// from the disk cache, which also keeps the values across the restarts of the server. When the
+/// redis server is unreachable at startup, the other caches are used until the connection retried
+/// in the background succeeds.
+#[cfg(any(
+    all(feature = "memory-cache", feature = "redis-cache"),
+    all(feature = "memory-cache", feature = "disk-cache"),
+    all(feature = "redis-cache", feature = "disk-cache")
+))]
 pub struct HybridCache {
+    #[cfg(feature = "memory-cache")]
     /// The in-memory backend cache which stores data.
     memory_cache: InMemoryCache,
-    /// The redis backend cache which stores data.
-    redis_cache: RedisCache,
+    #[cfg(feature = "redis-cache")]
+    /// The redis backend cache which stores data, it is only set once connected to the redis
+    /// server.
+    redis_cache: Arc<OnceLock<RedisCache>>,
+    #[cfg(feature = "disk-cache")]
+    /// The disk backend cache which stores data.
+    disk_cache: DiskCache,
 }
 
-#[cfg(all(feature = "memory-cache", feature = "redis-cache"))]
+#[cfg(any(
+    all(feature = "memory-cache", feature = "redis-cache"),
+    all(feature = "memory-cache", feature = "disk-cache"),
+    all(feature = "redis-cache", feature = "disk-cache")
+))]
 #[async_trait::async_trait]
 impl Cacher for HybridCache {
     async fn build(config: &Config) -> Self {
         log::info!("Initialising hybrid cache");
         HybridCache {
+            #[cfg(feature = "memory-cache")]
             memory_cache: InMemoryCache::build(config).await,
-            redis_cache: RedisCache::build(config).await,
+            #[cfg(feature = "redis-cache")]
+            redis_cache: connect_redis_cache(config).await,
+            #[cfg(feature = "disk-cache")]
+            disk_cache: DiskCache::build(config).await,
         }
     }
 
-    async fn cached_results(&mut self, url: &str) -> Result<SearchResults, Report<CacheError>> {
-        match self.redis_cache.cached_results(url).await {
-            Ok(res) => Ok(res),
-            Err(_) => self.memory_cache.cached_results(url).await,
+    async fn cached_results(&self, url: &str) -> Result<CachedResults, Report<CacheError>> {
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            if let Ok(res) = redis_cache.cached_results(url).await {
+                return Ok(res);
+            }
+        }
+        #[cfg(feature = "memory-cache")]
+        if let Ok(res) = self.memory_cache.cached_results(url).await {
+            return Ok(res);
+        }
+        #[cfg(feature = "disk-cache")]
+        if let Ok(res) = self.disk_cache.cached_results(url).await {
+            return Ok(res);
         }
+        Err(Report::new(CacheError::MissingValue))
     }
 
     async fn cache_results(
-        &mut self,
+        &self,
         search_results: &[SearchResults],
         urls: &[String],
     ) -> Result<(), Report<CacheError>> {
-        self.redis_cache.cache_results(search_results, urls).await?;
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            redis_cache.cache_results(search_results, urls).await?;
+        }
+        #[cfg(feature = "memory-cache")]
         self.memory_cache
             .cache_results(search_results, urls)
             .await?;
+        #[cfg(feature = "disk-cache")]
+        self.disk_cache.cache_results(search_results, urls).await?;
 
         Ok(())
     }
+
+    async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        // The redis cache is not checked until it is connected, the other caches are used
+        // meanwhile.
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            redis_cache.health_check().await?;
+        }
+        #[cfg(feature = "memory-cache")]
+        self.memory_cache.health_check().await?;
+        #[cfg(feature = "disk-cache")]
+        self.disk_cache.health_check().await?;
+        Ok(())
+    }
+
+    async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            if let Ok(preferences) = redis_cache.cached_profile(key).await {
+                return Ok(preferences);
+            }
+        }
+        #[cfg(feature = "memory-cache")]
+        if let Ok(preferences) = self.memory_cache.cached_profile(key).await {
+            return Ok(preferences);
+        }
+        #[cfg(feature = "disk-cache")]
+        if let Ok(preferences) = Cacher::cached_profile(&self.disk_cache, key).await {
+            return Ok(preferences);
+        }
+        Err(Report::new(CacheError::MissingValue))
+    }
+
+    async fn cache_profile(&self, key: &str, preferences: &str) -> Result<(), Report<CacheError>> {
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            redis_cache.cache_profile(key, preferences).await?;
+        }
+        #[cfg(feature = "memory-cache")]
+        self.memory_cache.cache_profile(key, preferences).await?;
+        #[cfg(feature = "disk-cache")]
+        Cacher::cache_profile(&self.disk_cache, key, preferences).await?;
+        Ok(())
+    }
+
+    // The same results are stored in every backend so the highest number of removed results is
+    // reported.
+    async fn delete_results(&self, urls: &[String]) -> Result<u64, Report<CacheError>> {
+        let mut deleted = 0;
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            deleted = deleted.max(redis_cache.delete_results(urls).await?);
+        }
+        #[cfg(feature = "memory-cache")]
+        {
+            deleted = deleted.max(self.memory_cache.delete_results(urls).await?);
+        }
+        #[cfg(feature = "disk-cache")]
+        {
+            deleted = deleted.max(self.disk_cache.delete_results(urls).await?);
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_results_with_prefix(&self, prefix: &str) -> Result<u64, Report<CacheError>> {
+        let mut deleted = 0;
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            deleted = deleted.max(redis_cache.delete_results_with_prefix(prefix).await?);
+        }
+        #[cfg(feature = "memory-cache")]
+        {
+            deleted = deleted.max(self.memory_cache.delete_results_with_prefix(prefix).await?);
+        }
+        #[cfg(feature = "disk-cache")]
+        {
+            deleted = deleted.max(self.disk_cache.delete_results_with_prefix(prefix).await?);
+        }
+        Ok(deleted)
+    }
+
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            redis_cache.flush_results().await?;
+        }
+        #[cfg(feature = "memory-cache")]
+        self.memory_cache.flush_results().await?;
+        #[cfg(feature = "disk-cache")]
+        self.disk_cache.flush_results().await?;
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        let mut stats = Vec::new();
+        #[cfg(feature = "redis-cache")]
+        if let Some(redis_cache) = self.redis_cache.get() {
+            stats.extend(redis_cache.stats().await?);
+        }
+        #[cfg(feature = "memory-cache")]
+        stats.extend(self.memory_cache.stats().await?);
+        #[cfg(feature = "disk-cache")]
+        stats.extend(self.disk_cache.stats().await?);
+        Ok(stats)
+    }
+}
+
+/// A constant holding the time in seconds before the hybrid cache tries again to connect to the
+/// redis server, it is doubled after every failed attempt.
+#[cfg(all(
+    feature = "redis-cache",
+    any(feature = "memory-cache", feature = "disk-cache")
+))]
+const REDIS_RETRY_INTERVAL: u64 = 5;
+
+/// A constant holding the maximum time in seconds between two attempts of the hybrid cache to
+/// connect to the redis server.
+#[cfg(all(
+    feature = "redis-cache",
+    any(feature = "memory-cache", feature = "disk-cache")
+))]
+const MAX_REDIS_RETRY_INTERVAL: u64 = 300;
+
+/// A helper function which connects the hybrid cache to the redis server. If the redis server is
+/// unreachable a warning is logged and the connection is retried in the background, so that the
+/// hybrid cache degrades to its other backends instead of failing to start.
+///
+/// # Arguments
+///
+/// * `config` - It takes the config struct as an argument.
+///
+/// # Returns
+///
+/// It returns the cell which holds the redis cache once it is connected.
+#[cfg(all(
+    feature = "redis-cache",
+    any(feature = "memory-cache", feature = "disk-cache")
+))]
+async fn connect_redis_cache(config: &Config) -> Arc<OnceLock<RedisCache>> {
+    log::info!(
+        "Initialising redis cache. Listening to {}",
+        &config.redis_url
+    );
+    let redis_cache = Arc::new(OnceLock::new());
+    let cache_ttl = cache_ttl(config);
+    match RedisCache::new(&config.redis_url, &config.redis, cache_ttl).await {
+        Ok(connected) => {
+            let _ = redis_cache.set(connected);
+            return redis_cache;
+        }
+        Err(error) => log::warn!(
+            "Failed to connect to the redis cache, the other caches are used until it is reachable: {error}"
+        ),
+    }
+
+    let (redis_url, options) = (config.redis_url.clone(), config.redis.clone());
+    let cell = Arc::clone(&redis_cache);
+    tokio::spawn(async move {
+        let mut interval = REDIS_RETRY_INTERVAL;
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            match RedisCache::new(&redis_url, &options, cache_ttl).await {
+                Ok(connected) => {
+                    let _ = cell.set(connected);
+                    log::info!("Connected to the redis cache");
+                    return;
+                }
+                Err(error) => log::warn!("Failed to connect to the redis cache: {error}"),
+            }
+            interval = (interval * 2).min(MAX_REDIS_RETRY_INTERVAL);
+        }
+    });
+    redis_cache
 }
 
 /// Dummy cache backend
@@ -506,23 +1050,64 @@ impl Cacher for DisabledCache {
         DisabledCache
     }
 
-    async fn cached_results(&mut self, _url: &str) -> Result<SearchResults, Report<CacheError>> {
+    async fn cached_results(&self, _url: &str) -> Result<CachedResults, Report<CacheError>> {
         Err(Report::new(CacheError::MissingValue))
     }
 
     async fn cache_results(
-        &mut self,
+        &self,
         _search_results: &[SearchResults],
         _urls: &[String],
     ) -> Result<(), Report<CacheError>> {
         Ok(())
     }
+
+    async fn delete_results(&self, _urls: &[String]) -> Result<u64, Report<CacheError>> {
+        Ok(0)
+    }
+
+    async fn delete_results_with_prefix(&self, _prefix: &str) -> Result<u64, Report<CacheError>> {
+        Ok(0)
+    }
+
+    async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        Ok(Vec::new())
+    }
 }
 
-/// A structure to efficiently share the cache between threads - as it is protected by a Mutex.
+/// A structure to efficiently share the cache between threads, the requests are served
+/// concurrently by the internal cache backend without any locking.
 pub struct SharedCache {
-    /// The internal cache protected from concurrent access by a mutex
-    cache: Mutex<Box<dyn Cacher>>,
+    /// The internal cache backend.
+    cache: Box<dyn Cacher>,
+    /// It stores the age after which the cached results are stale and refreshed in the
+    /// background, it is `None` when the stale-while-revalidate mode is disabled.
+    revalidate_after: Option<Duration>,
+    /// It stores the keys of the cached results which are being refreshed, so that only one
+    /// refresh per key runs at a time.
+    refreshing: StdMutex<HashSet<String>>,
+}
+
+/// A named struct which removes the key of the refreshed results from the keys being refreshed
+/// when it is dropped, even if the refresh panics.
+struct RefreshGuard {
+    /// It stores the keys of the cached results which are being refreshed.
+    refreshing: &'static StdMutex<HashSet<String>>,
+    /// It stores the key of the refreshed results.
+    key: String,
+}
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        self.refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.key);
+    }
 }
 
 impl SharedCache {
@@ -535,10 +1120,25 @@ impl SharedCache {
     /// Returns a newly constructed `SharedCache` struct.
     pub fn new(cache: impl Cacher + 'static) -> Self {
         Self {
-            cache: Mutex::new(Box::new(cache)),
+            cache: Box::new(cache),
+            revalidate_after: None,
+            refreshing: StdMutex::new(HashSet::new()),
         }
     }
 
+    /// A function which enables the stale-while-revalidate mode, in which the cached results
+    /// older than the provided age are still served but reported as stale so that they are
+    /// refreshed in the background.
+    ///
+    /// # Arguments
+    ///
+    /// * `revalidate_after` - It takes the age after which the cached results are stale as an
+    ///   argument.
+    pub fn with_stale_while_revalidate(mut self, revalidate_after: Duration) -> Self {
+        self.revalidate_after = Some(revalidate_after);
+        self
+    }
+
     /// A getter function which retrieves the cached SearchResulsts from the internal cache.
     ///
     /// # Arguments
@@ -551,8 +1151,84 @@ impl SharedCache {
     /// Returns a `SearchResults` struct containing the search results from the cache if nothing
     /// goes wrong otherwise returns a `CacheError`.
     pub async fn cached_results(&self, url: &str) -> Result<SearchResults, Report<CacheError>> {
-        let mut mut_cache = self.cache.lock().await;
-        mut_cache.cached_results(url).await
+        match self.lookup(url).await? {
+            CacheLookup::Fresh(results) | CacheLookup::Stale(results) => Ok(results),
+        }
+    }
+
+    /// A function which retrieves the cached search results from the internal cache and tells
+    /// whether they are stale and should be refreshed.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - It takes the search url as an argument which will be used as the key to fetch the
+    ///   cached results from the cache.
+    ///
+    /// # Error
+    ///
+    /// Returns the fresh or stale search results from the cache if nothing goes wrong otherwise
+    /// returns a `CacheError`.
+    pub async fn lookup(&self, url: &str) -> Result<CacheLookup, Report<CacheError>> {
+        let cached_results = self.cache.cached_results(url).await;
+        match cached_results {
+            Ok(_) => registry().record_cache_hit(),
+            Err(_) => registry().record_cache_miss(),
+        }
+        let cached_results = cached_results?;
+        let age = Duration::from_secs(unix_time().saturating_sub(cached_results.cached_at));
+        Ok(match self.revalidate_after {
+            Some(revalidate_after) if age >= revalidate_after => {
+                CacheLookup::Stale(cached_results.results)
+            }
+            _ => CacheLookup::Fresh(cached_results.results),
+        })
+    }
+
+    /// A function which refreshes the stale search results in the background and caches the
+    /// refreshed results, nothing is done if the results are already being refreshed. The
+    /// refresh runs on the current thread so it must be called from an actix worker (or any
+    /// other local task set).
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - It takes the search url used as the key of the cached results as an argument.
+    /// * `refresh` - It takes the future fetching the fresh search results as an argument, it
+    ///   resolves to `None` if they could not be fetched.
+    ///
+    /// # Returns
+    ///
+    /// It returns whether a refresh has been started.
+    pub fn revalidate(
+        &'static self,
+        url: String,
+        refresh: impl Future<Output = Option<SearchResults>> + 'static,
+    ) -> bool {
+        let newly_refreshing = self
+            .refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(url.clone());
+        if !newly_refreshing {
+            return false;
+        }
+
+        let guard = RefreshGuard {
+            refreshing: &self.refreshing,
+            key: url,
+        };
+        tokio::task::spawn_local(async move {
+            if let Some(results) = refresh.await {
+                if let Err(error) = self
+                    .cache
+                    .cache_results(&[results], &[guard.key.clone()])
+                    .await
+                {
+                    log::error!("Failed to cache the refreshed results: {error:?}");
+                }
+            }
+            drop(guard);
+        });
+        true
     }
 
     /// A setter function which caches the results by using the `url` as the key and
@@ -574,24 +1250,218 @@ impl SharedCache {
         search_results: &[SearchResults],
         urls: &[String],
     ) -> Result<(), Report<CacheError>> {
-        let mut mut_cache = self.cache.lock().await;
-        mut_cache.cache_results(search_results, urls).await
+        self.cache.cache_results(search_results, urls).await
+    }
+
+    /// A function which checks whether the internal cache backend is usable or not.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the cache backend is usable otherwise returns a `CacheError`.
+    pub async fn health_check(&self) -> Result<(), Report<CacheError>> {
+        self.cache.health_check().await
+    }
+
+    /// A getter function which retrieves the preferences saved in a profile from the internal
+    /// cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the preferences as a json string if nothing goes wrong otherwise returns a
+    /// `CacheError`.
+    pub async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        self.cache.cached_profile(key).await
+    }
+
+    /// A setter function which stores the preferences in a profile in the internal cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    /// * `preferences` - It takes the preferences as a json string as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the profile is stored succesfully otherwise returns a `CacheError`
+    /// on a failure.
+    pub async fn cache_profile(
+        &self,
+        key: &str,
+        preferences: &str,
+    ) -> Result<(), Report<CacheError>> {
+        self.cache.cache_profile(key, preferences).await
+    }
+
+    /// A getter function which retrieves the cached search results stored under the provided
+    /// url with the time at which they have been cached, unlike `lookup` it is not counted as a
+    /// cache hit or miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - It takes the url used as the key of the cached results as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the cached results if they exist otherwise returns a `CacheError`.
+    pub async fn entry(&self, url: &str) -> Result<CachedResults, Report<CacheError>> {
+        self.cache.cached_results(url).await
+    }
+
+    /// A function which removes the cached search results stored under the provided urls from
+    /// the internal cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - It takes the urls used as the keys of the cached results as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn delete_results(&self, urls: &[String]) -> Result<u64, Report<CacheError>> {
+        self.cache.delete_results(urls).await
+    }
+
+    /// A function which removes the cached search results whose url starts with the provided
+    /// prefix from the internal cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - It takes the prefix of the urls of the results to remove as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn delete_results_with_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<u64, Report<CacheError>> {
+        self.cache.delete_results_with_prefix(prefix).await
+    }
+
+    /// A function which removes all the cached search results from the internal cache.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the results are removed succesfully otherwise returns a
+    /// `CacheError`.
+    pub async fn flush_results(&self) -> Result<(), Report<CacheError>> {
+        self.cache.flush_results().await
+    }
+
+    /// A function which counts the cached search results and the bytes they take up in the
+    /// backends of the internal cache.
+    ///
+    /// # Error
+    ///
+    /// Returns the statistics of the backends on success otherwise returns a `CacheError`.
+    pub async fn stats(&self) -> Result<Vec<CacheStats>, Report<CacheError>> {
+        self.cache.stats().await
     }
 }
 
 /// A function to initialise the cache backend.
 pub async fn create_cache(config: &Config) -> impl Cacher {
-    #[cfg(all(feature = "redis-cache", feature = "memory-cache"))]
+    #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+    KEYRING.get_or_init(|| Keyring::new(&config.cache_encryption.keys));
+
+    #[cfg(any(
+        all(feature = "memory-cache", feature = "redis-cache"),
+        all(feature = "memory-cache", feature = "disk-cache"),
+        all(feature = "redis-cache", feature = "disk-cache")
+    ))]
     return HybridCache::build(config).await;
 
-    #[cfg(all(feature = "memory-cache", not(feature = "redis-cache")))]
+    #[cfg(all(
+        feature = "memory-cache",
+        not(any(feature = "redis-cache", feature = "disk-cache"))
+    ))]
     return InMemoryCache::build(config).await;
 
-    #[cfg(all(feature = "redis-cache", not(feature = "memory-cache")))]
+    #[cfg(all(
+        feature = "redis-cache",
+        not(any(feature = "memory-cache", feature = "disk-cache"))
+    ))]
     return RedisCache::build(config).await;
 
-    #[cfg(not(any(feature = "memory-cache", feature = "redis-cache")))]
+    #[cfg(all(
+        feature = "disk-cache",
+        not(any(feature = "memory-cache", feature = "redis-cache"))
+    ))]
+    return DiskCache::build(config).await;
+
+    #[cfg(not(any(
+        feature = "memory-cache",
+        feature = "redis-cache",
+        feature = "disk-cache"
+    )))]
     return DisabledCache::build(config).await;
 }
 
 //#[cfg(feature = "Compress-cache-results")]
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    /// A test function to test whether only one refresh of the stale results runs at a time for
+    /// the same key.
+    async fn test_revalidate_deduplication() {
+        let cache: &'static SharedCache = Box::leak(Box::new(SharedCache::new(DisabledCache)));
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        tokio::task::LocalSet::new()
+            .run_until(async move {
+                assert!(cache.revalidate("key".to_owned(), async move {
+                    receiver.await.ok().map(|_| SearchResults::default())
+                }));
+                assert!(!cache.revalidate("key".to_owned(), async { None }));
+                assert!(cache.revalidate("other".to_owned(), async { None }));
+
+                sender.send(()).unwrap();
+                while !cache.refreshing.lock().unwrap().is_empty() {
+                    tokio::task::yield_now().await;
+                }
+                assert!(cache.revalidate("key".to_owned(), async { None }));
+            })
+            .await;
+    }
+    #[cfg(feature = "memory-cache")]
+    #[tokio::test]
+    /// A test function to test whether the results cached in memory are removed by their url, by
+    /// the prefix of their url or all at once and whether they are counted in the statistics.
+    async fn test_in_memory_cache_administration() {
+        let cache = InMemoryCache {
+            cache: Arc::new(MokaCache::new(100)),
+            profiles: Arc::new(MokaCache::new(100)),
+        };
+        let urls = ["https://a.com/1", "https://a.com/2", "https://b.com/1"].map(str::to_owned);
+        cache
+            .cache_results(&vec![SearchResults::default(); urls.len()], &urls)
+            .await
+            .unwrap();
+        let stats = cache.stats().await.unwrap();
+        assert_eq!(stats[0].entries, 3);
+        assert!(stats[0].bytes > 0);
+
+        assert_eq!(cache.delete_results(&urls[2..]).await.unwrap(), 1);
+        assert_eq!(
+            cache
+                .delete_results_with_prefix("https://a.com/")
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(cache.stats().await.unwrap()[0].entries, 0);
+
+        cache
+            .cache_results(&[SearchResults::default()], &urls[..1])
+            .await
+            .unwrap();
+        cache.flush_results().await.unwrap();
+        assert!(cache.cached_results(&urls[0]).await.is_err());
+    }
+}
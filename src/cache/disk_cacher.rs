@@ -0,0 +1,600 @@
+//! This module provides the functionality to cache the aggregated results in a local SQLite file,
+//! so that the cache outlives the restarts of the server without requiring a redis server.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, TryLockError,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use error_stack::Report;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::error::CacheError;
+
+/// A constant holding the number of bytes in a megabyte.
+const MEGABYTE: u64 = 1024 * 1024;
+
+/// A constant holding the version of the layout of the tables, the cached results stored with an
+/// older layout are dropped when the file is opened.
+const SCHEMA_VERSION: i64 = 1;
+
+/// A constant holding the number of connections opened to the SQLite file, the WAL journal lets
+/// the queries of the readers run concurrently with each other and with the query of a writer.
+const POOL_SIZE: usize = 4;
+
+/// A constant holding how long a connection waits for the write lock held by another one before
+/// its query fails.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A helper function which provides the current unix time in seconds.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// A named struct which stores a small pool of connections to the SQLite file.
+struct ConnectionPool {
+    /// It stores the connections, every one of them runs a single query at a time.
+    connections: Vec<Mutex<Connection>>,
+    /// It stores the index of the next connection to wait for when all of them are in use.
+    next: AtomicUsize,
+}
+
+impl ConnectionPool {
+    /// A function which takes an idle connection from the pool, or waits for the connections in
+    /// turn when all of them are in use.
+    fn get(&self) -> MutexGuard<'_, Connection> {
+        // A poisoned lock still holds a usable connection as every query is atomic.
+        for connection in &self.connections {
+            match connection.try_lock() {
+                Ok(connection) => return connection,
+                Err(TryLockError::Poisoned(poisoned)) => return poisoned.into_inner(),
+                Err(TryLockError::WouldBlock) => (),
+            }
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        self.connections[index]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A helper function which opens a connection to the SQLite file which waits for the write lock
+/// held by the other connections.
+///
+/// # Arguments
+///
+/// * `path` - It takes the path to the SQLite file as an argument.
+///
+/// # Error
+///
+/// Returns the newly opened connection on success otherwise returns a `CacheError`.
+fn connect(path: &str) -> Result<Connection, Report<CacheError>> {
+    let connection =
+        Connection::open(path).map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+    connection
+        .busy_timeout(BUSY_TIMEOUT)
+        .map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+    Ok(connection)
+}
+
+/// A named struct which stores the connections to the SQLite file storing the cached results.
+#[derive(Clone)]
+pub struct DiskCache {
+    /// It stores the pool of connections to the SQLite file, the blocking calls on them are run
+    /// on the blocking thread pool of tokio.
+    pool: Arc<ConnectionPool>,
+    /// It stores the max TTL of the cached results in seconds.
+    cache_expiry_time: i64,
+    /// It stores the maximum size of the cached results in bytes, the least recently used
+    /// results are evicted once it is exceeded.
+    max_size: u64,
+}
+
+impl DiskCache {
+    /// A function which opens the pool of connections to the SQLite file (creating it if needed)
+    /// and creates the tables storing the cached results and the profiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - It takes the path to the SQLite file as an argument.
+    /// * `cache_expiry_time` - It takes the max TTL of the cached results in seconds as an
+    ///   argument.
+    /// * `max_size` - It takes the maximum size of the cached results in megabytes as an
+    ///   argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the newly opened disk cache on success otherwise returns a `CacheError`.
+    pub fn open(
+        path: &str,
+        cache_expiry_time: u16,
+        max_size: u64,
+    ) -> Result<Self, Report<CacheError>> {
+        let connection = connect(path)?;
+        let schema_version: i64 = connection
+            .query_row("PRAGMA user_version", (), |row| row.get(0))
+            .map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+        if schema_version < SCHEMA_VERSION {
+            // The cached results can always be fetched again so they are dropped instead of
+            // being migrated, the profiles are kept.
+            connection
+                .execute_batch(&format!(
+                    "DROP TABLE IF EXISTS results;
+                    PRAGMA user_version = {SCHEMA_VERSION};"
+                ))
+                .map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+        }
+        // The incremental auto vacuum has to be enabled before the tables are created so that
+        // the compaction can give back the pages of the evicted results to the file system.
+        connection
+            .execute_batch(
+                "PRAGMA auto_vacuum = INCREMENTAL;
+                PRAGMA journal_mode = WAL;
+                CREATE TABLE IF NOT EXISTS results (
+                    key TEXT PRIMARY KEY,
+                    url TEXT NOT NULL,
+                    value BLOB NOT NULL,
+                    size INTEGER NOT NULL,
+                    expires_at INTEGER NOT NULL,
+                    accessed_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS results_accessed_at ON results (accessed_at);
+                CREATE TABLE IF NOT EXISTS profiles (
+                    key TEXT PRIMARY KEY,
+                    preferences TEXT NOT NULL
+                );",
+            )
+            .map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+
+        // Every connection to an in-memory database opens its own database, so it can not be
+        // shared by a pool.
+        let pool_size = match path {
+            "" | ":memory:" => 1,
+            _ => POOL_SIZE,
+        };
+        let mut connections = vec![Mutex::new(connection)];
+        for _ in 1..pool_size {
+            connections.push(Mutex::new(connect(path)?));
+        }
+        Ok(Self {
+            pool: Arc::new(ConnectionPool {
+                connections,
+                next: AtomicUsize::new(0),
+            }),
+            cache_expiry_time: cache_expiry_time.into(),
+            max_size: max_size.saturating_mul(MEGABYTE),
+        })
+    }
+
+    /// A helper function which runs the provided closure with a connection of the pool on the
+    /// blocking thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - It takes the closure running the query as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the value returned by the closure on success otherwise returns a `CacheError`.
+    async fn run<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    ) -> Result<T, Report<CacheError>> {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || query(&mut pool.get()))
+            .await
+            .map_err(|_| Report::new(CacheError::UnsupportedOperation))?
+            .map_err(|error| Report::new(CacheError::SqliteError(error)))
+    }
+
+    /// A function which fetches the cached bytes stored under the provided key and marks them as
+    /// recently used.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the cached results as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the cached bytes if they exist and have not expired otherwise returns a
+    /// `CacheError`.
+    pub async fn cached_bytes(&self, key: &str) -> Result<Vec<u8>, Report<CacheError>> {
+        let key = key.to_owned();
+        self.run(move |connection| {
+            let now = now();
+            let value = connection
+                .query_row(
+                    "SELECT value FROM results WHERE key = ?1 AND expires_at > ?2",
+                    params![key, now],
+                    |row| row.get::<_, Vec<u8>>(0),
+                )
+                .optional()?;
+            if value.is_some() {
+                connection.execute(
+                    "UPDATE results SET accessed_at = ?2 WHERE key = ?1",
+                    params![key, now],
+                )?;
+            }
+            Ok(value)
+        })
+        .await?
+        .ok_or_else(|| Report::new(CacheError::MissingValue))
+    }
+
+    /// A function which stores the provided bytes under the provided keys and evicts the least
+    /// recently used results if the maximum size is exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - It takes the keys, the urls of the results and the bytes to store as an
+    ///   argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the bytes are stored succesfully otherwise returns a `CacheError`.
+    pub async fn cache_bytes(
+        &self,
+        entries: Vec<(String, String, Vec<u8>)>,
+    ) -> Result<(), Report<CacheError>> {
+        let (cache_expiry_time, max_size) = (self.cache_expiry_time, self.max_size);
+        self.run(move |connection| {
+            let now = now();
+            let transaction = connection.transaction()?;
+            for (key, url, value) in entries {
+                transaction.execute(
+                    "INSERT OR REPLACE INTO results (key, url, value, size, expires_at, accessed_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        key,
+                        url,
+                        value,
+                        value.len() as i64,
+                        now + cache_expiry_time,
+                        now
+                    ],
+                )?;
+            }
+            evict(&transaction, max_size)?;
+            transaction.commit()
+        })
+        .await
+    }
+
+    /// A function which removes the results stored under the provided keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - It takes the keys of the results to remove as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn delete_bytes(&self, keys: Vec<String>) -> Result<usize, Report<CacheError>> {
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let mut deleted = 0;
+            for key in keys {
+                deleted += transaction.execute("DELETE FROM results WHERE key = ?1", [key])?;
+            }
+            transaction.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    /// A function which removes the results whose url starts with the provided prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - It takes the prefix of the urls of the results to remove as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn delete_bytes_with_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<usize, Report<CacheError>> {
+        let prefix = prefix.to_owned();
+        self.run(move |connection| {
+            // The prefix is compared as is instead of with `LIKE` so that the `%` and `_`
+            // characters in the urls need no escaping.
+            connection.execute(
+                "DELETE FROM results WHERE substr(url, 1, length(?1)) = ?1",
+                [prefix],
+            )
+        })
+        .await
+    }
+
+    /// A function which removes all the results, the profiles are kept.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn delete_all_bytes(&self) -> Result<usize, Report<CacheError>> {
+        self.run(|connection| connection.execute("DELETE FROM results", ()))
+            .await
+    }
+
+    /// A function which counts the results which have not expired and the bytes they take up.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of results and their size in bytes on success otherwise returns a
+    /// `CacheError`.
+    pub async fn usage(&self) -> Result<(u64, u64), Report<CacheError>> {
+        self.run(|connection| {
+            connection.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM results WHERE expires_at > ?1",
+                [now()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+        })
+        .await
+    }
+
+    /// A function which retrieves the preferences saved in a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the preferences as a json string if the profile exists otherwise returns a
+    /// `CacheError`.
+    pub async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        let key = key.to_owned();
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT preferences FROM profiles WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+        })
+        .await?
+        .ok_or_else(|| Report::new(CacheError::MissingValue))
+    }
+
+    /// A function which creates or replaces the preferences saved in a profile, unlike the
+    /// search results the profiles neither expire nor are evicted.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    /// * `preferences` - It takes the preferences as a json string as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the profile is stored succesfully otherwise returns a `CacheError`.
+    pub async fn cache_profile(
+        &self,
+        key: &str,
+        preferences: &str,
+    ) -> Result<(), Report<CacheError>> {
+        let (key, preferences) = (key.to_owned(), preferences.to_owned());
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO profiles (key, preferences) VALUES (?1, ?2)",
+                params![key, preferences],
+            )
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// A function which checks whether the SQLite file can still be queried.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the file is usable otherwise returns a `CacheError`.
+    pub async fn ping(&self) -> Result<(), Report<CacheError>> {
+        self.run(|connection| connection.query_row("SELECT 1", (), |_| Ok(())))
+            .await
+    }
+
+    /// A function which compacts the SQLite file by removing the expired results, evicting the
+    /// least recently used results above the maximum size and giving back the freed pages to the
+    /// file system.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed results on success otherwise returns a `CacheError`.
+    pub async fn compact(&self) -> Result<usize, Report<CacheError>> {
+        let max_size = self.max_size;
+        self.run(move |connection| {
+            let transaction = connection.transaction()?;
+            let expired =
+                transaction.execute("DELETE FROM results WHERE expires_at <= ?1", [now()])?;
+            let evicted = evict(&transaction, max_size)?;
+            transaction.commit()?;
+            connection.execute_batch("PRAGMA incremental_vacuum;")?;
+            Ok(expired + evicted)
+        })
+        .await
+    }
+
+    /// A function which spawns the background task compacting the SQLite file periodically.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - It takes the time between two compactions as an argument.
+    pub fn spawn_compaction(&self, interval: Duration) {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match cache.compact().await {
+                    Ok(removed) => log::debug!("Compacted the disk cache ({removed} removed)"),
+                    Err(error) => log::error!("Failed to compact the disk cache: {error:?}"),
+                }
+            }
+        });
+    }
+}
+
+/// A helper function which evicts the least recently used results until their total size fits
+/// in the maximum size.
+///
+/// # Arguments
+///
+/// * `connection` - It takes the connection to the SQLite file as an argument.
+/// * `max_size` - It takes the maximum size of the cached results in bytes as an argument.
+///
+/// # Error
+///
+/// Returns the number of evicted results on success otherwise returns the SQLite error.
+fn evict(connection: &Connection, max_size: u64) -> rusqlite::Result<usize> {
+    let total_size: i64 =
+        connection.query_row("SELECT COALESCE(SUM(size), 0) FROM results", (), |row| {
+            row.get(0)
+        })?;
+    let excess = total_size.saturating_sub(i64::try_from(max_size).unwrap_or(i64::MAX));
+    if excess <= 0 {
+        return Ok(0);
+    }
+    // The window sums the sizes from the least recently used result, so the results are
+    // deleted until the freed size covers the excess.
+    connection.execute(
+        "DELETE FROM results WHERE key IN (
+            SELECT key FROM (
+                SELECT key, SUM(size) OVER (ORDER BY accessed_at, key) - size AS freed
+                FROM results
+            ) WHERE freed < ?1
+        )",
+        [excess],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    /// A test function to test whether the results are stored and retrieved from the SQLite file
+    /// and whether the least recently used results are evicted above the maximum size.
+    async fn test_disk_cache_eviction() {
+        let mut cache = DiskCache::open(":memory:", 60, 1).unwrap();
+        cache.max_size = 10;
+        assert!(cache.cached_bytes("missing").await.is_err());
+
+        cache
+            .cache_bytes(vec![
+                ("first".to_owned(), "https://a.com/1".to_owned(), vec![1; 4]),
+                (
+                    "second".to_owned(),
+                    "https://a.com/2".to_owned(),
+                    vec![2; 4],
+                ),
+            ])
+            .await
+            .unwrap();
+        // The first results have been used more recently than the second ones.
+        cache
+            .pool
+            .get()
+            .execute_batch(
+                "UPDATE results SET accessed_at = accessed_at - 5 WHERE key = 'first';
+                UPDATE results SET accessed_at = accessed_at - 10 WHERE key = 'second';",
+            )
+            .unwrap();
+
+        cache
+            .cache_bytes(vec![(
+                "third".to_owned(),
+                "https://b.com/1".to_owned(),
+                vec![3; 4],
+            )])
+            .await
+            .unwrap();
+        assert!(cache.cached_bytes("second").await.is_err());
+        assert_eq!(cache.cached_bytes("first").await.unwrap(), vec![1; 4]);
+        assert_eq!(cache.cached_bytes("third").await.unwrap(), vec![3; 4]);
+    }
+
+    #[tokio::test]
+    /// A test function to test whether the results are removed by their key, by the prefix of
+    /// their url or all at once and whether the usage is counted.
+    async fn test_disk_cache_deletion() {
+        let cache = DiskCache::open(":memory:", 60, 1).unwrap();
+        cache
+            .cache_bytes(vec![
+                ("first".to_owned(), "https://a.com/1".to_owned(), vec![1; 4]),
+                (
+                    "second".to_owned(),
+                    "https://a.com/2".to_owned(),
+                    vec![2; 4],
+                ),
+                ("third".to_owned(), "https://b.com/1".to_owned(), vec![3; 8]),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(cache.usage().await.unwrap(), (3, 16));
+
+        assert_eq!(
+            cache.delete_bytes(vec!["third".to_owned()]).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            cache
+                .delete_bytes_with_prefix("https://a.com/")
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(cache.usage().await.unwrap(), (0, 0));
+
+        cache
+            .cache_bytes(vec![(
+                "first".to_owned(),
+                "https://a.com/1".to_owned(),
+                vec![1; 4],
+            )])
+            .await
+            .unwrap();
+        assert_eq!(cache.delete_all_bytes().await.unwrap(), 1);
+        assert!(cache.cached_bytes("first").await.is_err());
+    }
+
+    #[tokio::test]
+    /// A test function to test whether the connections of the pool share the SQLite file and
+    /// whether the concurrent lookups and writes all succeed.
+    async fn test_disk_cache_pool() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("cache.db");
+        let cache = DiskCache::open(path.to_str().unwrap(), 60, 1).unwrap();
+        assert_eq!(cache.pool.connections.len(), POOL_SIZE);
+
+        let writes = (0..16).map(|index| {
+            cache.cache_bytes(vec![(
+                index.to_string(),
+                format!("https://a.com/{index}"),
+                vec![index; 4],
+            )])
+        });
+        assert!(futures::future::join_all(writes)
+            .await
+            .into_iter()
+            .all(|result| result.is_ok()));
+
+        let lookups = (0..16).map(|index| {
+            let cache = cache.clone();
+            async move { cache.cached_bytes(&index.to_string()).await.unwrap() == vec![index; 4] }
+        });
+        assert!(futures::future::join_all(lookups)
+            .await
+            .into_iter()
+            .all(|found| found));
+        assert_eq!(cache.usage().await.unwrap(), (16, 64));
+    }
+}
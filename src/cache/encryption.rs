@@ -1,25 +1,170 @@
+//! This module provides the keys and the ChaCha20-Poly1305 ciphers encrypting the cached search
+//! results. Every encrypted blob is prefixed with the id of the key which encrypted it followed
+//! by its nonce, so that the keys can be rotated: the newest key encrypts the results while all
+//! the configured keys decrypt them.
+
+use super::error::CacheError;
 use chacha20poly1305::{
-    consts::{B0, B1},
-    ChaChaPoly1305,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
 };
 use std::sync::OnceLock;
 
-use chacha20::{
-    cipher::{
-        generic_array::GenericArray,
-        typenum::{UInt, UTerm},
-        StreamCipherCoreWrapper,
-    },
-    ChaChaCore,
-};
+/// A constant holding the length in bytes of the encryption keys.
+pub const KEY_LENGTH: usize = 32;
+
+/// A constant holding the length in bytes of the key id prefixed to every encrypted blob.
+const KEY_ID_LENGTH: usize = 4;
+
+/// A constant holding the length in bytes of the nonce stored after the key id.
+const NONCE_LENGTH: usize = 12;
+
+/// The keyring encrypting and decrypting the cached results, it is initialised with the
+/// configured keys when the cache is created.
+pub static KEYRING: OnceLock<Keyring> = OnceLock::new();
+
+/// A named struct which stores the ciphers of the encryption keys along with their ids, the first
+/// one encrypts the results.
+pub struct Keyring {
+    /// It stores the ciphers of the keys by the id of their key, from the newest to the oldest.
+    ciphers: Vec<([u8; KEY_ID_LENGTH], ChaCha20Poly1305)>,
+}
+
+impl Keyring {
+    /// Constructs a new `Keyring` from the provided keys. When no key is provided a random key is
+    /// generated, so the encrypted results can neither be read after a restart nor shared with
+    /// the other servers.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - It takes the keys from the newest to the oldest as an argument.
+    pub fn new(keys: &[[u8; KEY_LENGTH]]) -> Self {
+        let mut ciphers: Vec<([u8; KEY_ID_LENGTH], ChaCha20Poly1305)> = keys
+            .iter()
+            .map(|key| (key_id(key), ChaCha20Poly1305::new(Key::from_slice(key))))
+            .collect();
+        if ciphers.is_empty() {
+            log::warn!(
+                "No cache encryption key is configured, the encrypted results are only readable until the server is restarted"
+            );
+            let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+            ciphers.push((key_id(key.as_ref()), ChaCha20Poly1305::new(&key)));
+        }
+        Keyring { ciphers }
+    }
+
+    /// A function which encrypts the provided bytes with the newest key and a random nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - It takes the bytes to encrypt as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the id of the key, the nonce and the encrypted bytes on success otherwise returns
+    /// a `CacheError` on a failure.
+    pub fn encrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, CacheError> {
+        let (id, cipher) = &self.ciphers[0];
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = cipher
+            .encrypt(&nonce, bytes)
+            .map_err(|_| CacheError::EncryptionError)?;
+
+        let mut blob = Vec::with_capacity(KEY_ID_LENGTH + NONCE_LENGTH + encrypted.len());
+        blob.extend_from_slice(id);
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&encrypted);
+        Ok(blob)
+    }
+
+    /// A function which decrypts the provided blob with the key whose id prefixes it.
+    ///
+    /// # Arguments
+    ///
+    /// * `blob` - It takes the blob produced by the `encrypt` function as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the decrypted bytes on success otherwise returns a `CacheError` if the blob is
+    /// malformed, if its key is not in the keyring or if it has been tampered with.
+    pub fn decrypt(&self, blob: &[u8]) -> Result<Vec<u8>, CacheError> {
+        if blob.len() < KEY_ID_LENGTH + NONCE_LENGTH {
+            return Err(CacheError::EncryptionError);
+        }
+        let (id, rest) = blob.split_at(KEY_ID_LENGTH);
+        let (nonce, encrypted) = rest.split_at(NONCE_LENGTH);
+        let (_, cipher) = self
+            .ciphers
+            .iter()
+            .find(|(key_id, _)| key_id == id)
+            .ok_or(CacheError::EncryptionError)?;
+        cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|_| CacheError::EncryptionError)
+    }
+}
+
+/// A helper function which computes the id of the provided key, which is the beginning of its
+/// hash so that the key itself can not be recovered from it.
+///
+/// # Arguments
+///
+/// * `key` - It takes the key as an argument.
+fn key_id(key: &[u8]) -> [u8; KEY_ID_LENGTH] {
+    let mut id = [0; KEY_ID_LENGTH];
+    id.copy_from_slice(&blake3::hash(key).as_bytes()[..KEY_ID_LENGTH]);
+    id
+}
+
+/// A function which decodes an encryption key written as 64 hexadecimal characters (like the
+/// output of `openssl rand -hex 32`).
+///
+/// # Arguments
+///
+/// * `encoded` - It takes the hexadecimal key as an argument.
+///
+/// # Error
+///
+/// Returns the key on success otherwise returns a description of the problem, which does not
+/// include the key.
+pub fn decode_key(encoded: &str) -> Result<[u8; KEY_LENGTH], String> {
+    let encoded = encoded.trim();
+    if encoded.len() != KEY_LENGTH * 2 || !encoded.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(format!(
+            "the cache encryption keys should be made of {} hexadecimal characters",
+            KEY_LENGTH * 2
+        ));
+    }
+    let mut key = [0; KEY_LENGTH];
+    for (index, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&encoded[index * 2..index * 2 + 2], 16)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the results encrypted with an older key can still be
+    /// decrypted after a new key is added while the keys removed from the keyring can not
+    /// decrypt them anymore.
+    fn test_keyring_rotation() {
+        let old_key = decode_key(&"ab".repeat(KEY_LENGTH)).unwrap();
+        let new_key = decode_key(&"cd".repeat(KEY_LENGTH)).unwrap();
 
-/// The ChaCha20 core wrapped in a stream cipher for use in ChaCha20-Poly1305 authenticated encryption.
-type StreamCipherCoreWrapperType =
-    StreamCipherCoreWrapper<ChaChaCore<UInt<UInt<UInt<UInt<UTerm, B1>, B0>, B1>, B0>>>;
-/// Our ChaCha20-Poly1305 cipher instance, lazily initialized.
-pub static CIPHER: OnceLock<ChaChaPoly1305<StreamCipherCoreWrapperType>> = OnceLock::new();
+        let blob = Keyring::new(&[old_key]).encrypt(b"results").unwrap();
+        assert_eq!(blob[..KEY_ID_LENGTH], key_id(&old_key));
 
-/// The type alias for our encryption key, a 32-byte array.
-type GenericArrayType = GenericArray<u8, UInt<UInt<UInt<UInt<UTerm, B1>, B1>, B0>, B0>>;
-/// Our encryption key, lazily initialized.
-pub static ENCRYPTION_KEY: OnceLock<GenericArrayType> = OnceLock::new();
+        let rotated = Keyring::new(&[new_key, old_key]);
+        assert_eq!(rotated.decrypt(&blob).unwrap(), b"results");
+        assert_eq!(
+            rotated.encrypt(b"results").unwrap()[..KEY_ID_LENGTH],
+            key_id(&new_key)
+        );
+        assert!(Keyring::new(&[new_key]).decrypt(&blob).is_err());
+        assert!(decode_key("not a key").is_err());
+    }
+}
@@ -4,6 +4,8 @@ use std::fmt;
 
 #[cfg(feature = "redis-cache")]
 use redis::RedisError;
+#[cfg(any(feature = "sqlite-profiles", feature = "disk-cache"))]
+use rusqlite::Error as SqliteError;
 
 /// A custom error type used for handling redis async pool associated errors.
 #[derive(Debug)]
@@ -24,6 +26,12 @@ pub enum CacheError {
     CompressionError,
     /// Whenever base64 decoding failed
     Base64DecodingOrEncodingError,
+    /// Returned when the cache backend does not support the requested operation.
+    UnsupportedOperation,
+    /// This variant handles all errors related to the SQLite files storing the profiles or the
+    /// disk cache.
+    #[cfg(any(feature = "sqlite-profiles", feature = "disk-cache"))]
+    SqliteError(SqliteError),
 }
 
 impl fmt::Display for CacheError {
@@ -61,6 +69,13 @@ impl fmt::Display for CacheError {
             CacheError::Base64DecodingOrEncodingError => {
                 write!(f, "base64 encoding or decoding failed")
             }
+
+            CacheError::UnsupportedOperation => {
+                write!(f, "The cache backend does not support this operation")
+            }
+
+            #[cfg(any(feature = "sqlite-profiles", feature = "disk-cache"))]
+            CacheError::SqliteError(sqlite_error) => write!(f, "{}", sqlite_error),
         }
     }
 }
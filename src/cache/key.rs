@@ -0,0 +1,98 @@
+//! This module provides the structured key under which the results of the upstream search engines
+//! are cached. It only depends on the parameters which affect the results (and not on the address
+//! the server is bound to), so that the replicas sharing a cache backend share the cached results.
+
+use std::fmt;
+
+/// A constant holding the version of the layout of the cache keys, it has to be bumped whenever
+/// the layout or the cached data changes so that the entries cached by the older versions are
+/// never read (they expire on their own).
+pub const CACHE_KEY_VERSION: u8 = 1;
+
+/// A named struct which stores the normalized parameters identifying the cached results of an
+/// upstream search engine. It is formatted as
+/// `v{version}/{engine}/{safe_search_level}/{language}/{page}/{query}` and hashed with the
+/// `hash_url` function of the cache backends to get the key actually stored, so the cached
+/// results of an engine can be removed by the prefix of the formatted key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    /// It stores the lowercase name of the upstream search engine.
+    engine: String,
+    /// It stores the safe search level.
+    safe_search_level: u8,
+    /// It stores the lowercase code of the locale of the pages.
+    language: String,
+    /// It stores the page number (starting from 0).
+    page: u32,
+    /// It stores the search query in lowercase with its whitespace collapsed.
+    query: String,
+}
+
+impl CacheKey {
+    /// Constructs a new `CacheKey` from the parameters of the search, they are normalized so that
+    /// the variants of the same search (in casing or whitespace) share the cached results.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - It takes the search query as an argument.
+    /// * `page` - It takes the page number (starting from 0) as an argument.
+    /// * `safe_search_level` - It takes the safe search level as an argument.
+    /// * `language` - It takes the code of the locale of the pages as an argument.
+    /// * `engine` - It takes the name of the upstream search engine as an argument.
+    pub fn new(
+        query: &str,
+        page: u32,
+        safe_search_level: u8,
+        language: &str,
+        engine: &str,
+    ) -> Self {
+        Self {
+            engine: engine.to_lowercase(),
+            safe_search_level,
+            language: language.to_lowercase(),
+            page,
+            query: query
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ")
+                .to_lowercase(),
+        }
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The query is written last as it is the only part which can contain the separator.
+        write!(
+            f,
+            "v{}/{}/{}/{}/{}/{}",
+            CACHE_KEY_VERSION,
+            self.engine,
+            self.safe_search_level,
+            self.language,
+            self.page,
+            self.query
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the variants of the same search share the same key and
+    /// whether the key is prefixed with its version.
+    fn test_cache_key_normalization() {
+        let key = CacheKey::new("  Rust   Language ", 1, 2, "EN", "DuckDuckGo");
+        assert_eq!(
+            key,
+            CacheKey::new("rust language", 1, 2, "en", "duckduckgo")
+        );
+        assert_eq!(key.to_string(), "v1/duckduckgo/2/en/1/rust language");
+        assert_ne!(
+            key,
+            CacheKey::new("rust language", 2, 2, "en", "duckduckgo")
+        );
+    }
+}
@@ -6,6 +6,13 @@ pub mod cacher;
 /// encryption module contains encryption utils such the cipher and key
 pub mod encryption;
 pub mod error;
+pub mod key;
+
+#[cfg(feature = "disk-cache")]
+pub mod disk_cacher;
 
 #[cfg(feature = "redis-cache")]
 pub mod redis_cacher;
+
+#[cfg(feature = "sqlite-profiles")]
+pub mod sqlite_profiles;
@@ -1,41 +1,218 @@
 //! This module provides the functionality to cache the aggregated results fetched and aggregated
 //! from the upstream search engines in a json format.
 
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+};
+
 use super::error::CacheError;
+use crate::models::parser_models::{RedisConfig, RedisMode};
 use error_stack::Report;
-use futures::stream::FuturesUnordered;
+use futures::{future::try_join_all, stream::FuturesUnordered};
 use redis::{
-    aio::ConnectionManager, AsyncCommands, Client, ExistenceCheck, RedisError, SetExpiry,
-    SetOptions,
+    aio::{ConnectionLike, ConnectionManager},
+    cluster::ClusterClientBuilder,
+    cluster_async::ClusterConnection,
+    sentinel::{Sentinel, SentinelNodeConnectionInfo},
+    AsyncCommands, Client, ConnectionAddr, ConnectionInfo, ErrorKind, IntoConnectionInfo,
+    RedisError, RedisFuture, RedisResult, TlsMode,
 };
+use tokio::sync::Mutex;
 
 /// A constant holding the redis pipeline size.
 const REDIS_PIPELINE_SIZE: usize = 3;
 
+/// A constant holding the number of keys requested per `SCAN` command and sent per pipeline when
+/// going through all the cached results.
+const SCAN_BATCH_SIZE: usize = 1000;
+
+/// A constant holding the namespace (after the configured prefix) of the keys of the cached
+/// results, only the keys of this namespace are scanned so that the keys of the other
+/// applications sharing the redis database are never counted nor removed.
+const RESULTS_NAMESPACE: &str = "websurfx:results:";
+
+/// A constant holding the field of the redis hashes storing the url of the cached results.
+const URL_FIELD: &str = "url";
+
+/// A constant holding the field of the redis hashes storing the cached results.
+const RESULTS_FIELD: &str = "results";
+
+/// An enum type which provides the connections to the different deployments of the redis
+/// servers, so that the commands are run the same way whatever the deployment.
+#[derive(Clone)]
+enum RedisConnection {
+    /// A connection to a single redis server (a standalone server or the master provided by the
+    /// sentinels), which reconnects on its own when it is dropped.
+    Node(ConnectionManager),
+    /// A connection to a redis cluster, which routes the commands to the nodes holding their
+    /// keys.
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> RedisFuture<'a, redis::Value> {
+        match self {
+            RedisConnection::Node(connection) => connection.req_packed_command(cmd),
+            RedisConnection::Cluster(connection) => connection.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<redis::Value>> {
+        match self {
+            RedisConnection::Node(connection) => connection.req_packed_commands(cmd, offset, count),
+            RedisConnection::Cluster(connection) => {
+                connection.req_packed_commands(cmd, offset, count)
+            }
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Node(connection) => connection.get_db(),
+            RedisConnection::Cluster(connection) => connection.get_db(),
+        }
+    }
+}
+
+/// A named struct which stores the sentinels monitoring the redis master, so that the address
+/// of the new master can be fetched after a failover.
+struct SentinelMaster {
+    /// It stores the connections to the sentinels, it is locked while the master is looked up
+    /// so that a single reconnection runs at a time.
+    sentinel: Mutex<Sentinel>,
+    /// It stores the name of the master monitored by the sentinels.
+    service_name: String,
+    /// It stores the credentials and the TLS mode used to connect to the master.
+    node_connection_info: SentinelNodeConnectionInfo,
+}
+
+impl SentinelMaster {
+    /// A function which fetches the address of the current master from the sentinels.
+    ///
+    /// # Error
+    ///
+    /// Returns a client connecting to the current master on success otherwise returns a
+    /// `RedisError` if none of the sentinels provided it.
+    async fn client(&self) -> RedisResult<Client> {
+        self.sentinel
+            .lock()
+            .await
+            .async_master_for(&self.service_name, Some(&self.node_connection_info))
+            .await
+    }
+}
+
 /// A named struct which stores the redis Connection url address to which the client will
 /// connect to.
 pub struct RedisCache {
-    /// It stores a pool of connections ready to be used.
-    connection_pool: Box<[ConnectionManager]>,
-    /// It stores the size of the connection pool (in other words the number of
-    /// connections that should be stored in the pool).
-    pool_size: u8,
-    /// It stores the index of which connection is being used at the moment.
-    current_connection: u8,
+    /// It stores a pool of connections ready to be used, the connections are multiplexed so they
+    /// are cloned to run the commands concurrently. It is replaced when the sentinels provide a
+    /// new master.
+    connection_pool: RwLock<Arc<[RedisConnection]>>,
+    /// It stores the index of the connection from which the next command starts, it is rotated
+    /// to spread the commands over the pool.
+    next_connection: AtomicUsize,
+    /// It stores the number of times the pool has been replaced, so that the commands failing
+    /// concurrently after a failover only replace it once.
+    generation: AtomicUsize,
+    /// It stores the sentinels monitoring the master when the redis servers are deployed with
+    /// sentinels.
+    sentinel: Option<SentinelMaster>,
+    /// It stores whether the connections are made to a redis cluster.
+    cluster: bool,
+    /// It stores the prefix added to all the keys.
+    key_prefix: String,
     /// It stores the max TTL for keys.
     cache_ttl: u16,
-    /// It stores the redis pipeline struct of size 3.
-    pipeline: redis::Pipeline,
+}
+
+/// A helper function which builds the connection info of a redis server from its url, along with
+/// the credentials provided in the config (which take precedence over the ones of the url).
+///
+/// # Arguments
+///
+/// * `url` - It takes the url of the redis server as an argument (`rediss://` enables TLS).
+/// * `options` - It takes the configuration options of the redis cache as an argument.
+///
+/// # Error
+///
+/// Returns the connection info on success otherwise returns a `RedisError` if the url is not
+/// valid.
+fn connection_info(url: &str, options: &RedisConfig) -> RedisResult<ConnectionInfo> {
+    let mut info = url.into_connection_info()?;
+    if options.username.is_some() {
+        info.redis.username.clone_from(&options.username);
+    }
+    if options.password.is_some() {
+        info.redis.password.clone_from(&options.password);
+    }
+    Ok(info)
+}
+
+/// A helper function which opens the provided number of connections to a single redis server.
+///
+/// # Arguments
+///
+/// * `client` - It takes the client of the redis server as an argument.
+/// * `pool_size` - It takes the number of connections to open as an argument.
+///
+/// # Error
+///
+/// Returns the connections on success otherwise returns a standard error type.
+async fn node_connections(
+    client: Client,
+    pool_size: u8,
+) -> Result<Vec<RedisConnection>, Box<dyn std::error::Error + Send + Sync>> {
+    let tasks: FuturesUnordered<_> = FuturesUnordered::new();
+
+    for _ in 0..pool_size {
+        let client_partially_cloned = client.clone();
+        tasks.push(tokio::spawn(async move {
+            client_partially_cloned.get_connection_manager().await
+        }));
+    }
+
+    let mut outputs = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        outputs.push(RedisConnection::Node(task.await??));
+    }
+    Ok(outputs)
+}
+
+/// A helper function which escapes the special characters of the glob-style patterns of redis.
+///
+/// # Arguments
+///
+/// * `text` - It takes the text to match literally as an argument.
+fn escape_pattern(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
 }
 
 impl RedisCache {
-    /// A function which fetches the cached json results as json string.
+    /// A function which connects to the redis servers and builds the pool of connections.
     ///
     /// # Arguments
     ///
-    /// * `redis_connection_url` - It takes the redis Connection url address.
-    /// * `pool_size` - It takes the size of the connection pool (in other words the number of
-    /// connections that should be stored in the pool).
+    /// * `redis_connection_url` - It takes the redis Connection url address, it is used when no
+    /// nodes are provided in the options.
+    /// * `options` - It takes the configuration options of the redis cache (the deployment, the
+    /// credentials, the size of the connection pool and the prefix of the keys) as an argument.
     /// * `cache_ttl` - It takes the the time to live for cached results to live in the redis
     /// server.
     ///
@@ -45,35 +222,222 @@ impl RedisCache {
     /// error type.
     pub async fn new(
         redis_connection_url: &str,
-        pool_size: u8,
+        options: &RedisConfig,
         cache_ttl: u16,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        let client = Client::open(redis_connection_url)?;
-        let tasks: FuturesUnordered<_> = FuturesUnordered::new();
-
-        for _ in 0..pool_size {
-            let client_partially_cloned = client.clone();
-            tasks.push(tokio::spawn(async move {
-                client_partially_cloned.get_connection_manager().await
-            }));
-        }
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let nodes = match options.nodes.is_empty() {
+            true => vec![connection_info(redis_connection_url, options)?],
+            false => options
+                .nodes
+                .iter()
+                .map(|url| connection_info(url, options))
+                .collect::<RedisResult<Vec<ConnectionInfo>>>()?,
+        };
 
-        let mut outputs = Vec::with_capacity(tasks.len());
-        for task in tasks {
-            outputs.push(task.await??);
-        }
+        let mut sentinel = None;
+        let connections = match options.mode {
+            RedisMode::Standalone => {
+                node_connections(Client::open(nodes[0].clone())?, options.pool_size).await?
+            }
+            RedisMode::Sentinel => {
+                // The master uses TLS when the sentinels do.
+                let tls_mode = match &nodes[0].addr {
+                    ConnectionAddr::TcpTls { insecure: true, .. } => Some(TlsMode::Insecure),
+                    ConnectionAddr::TcpTls { .. } => Some(TlsMode::Secure),
+                    _ => None,
+                };
+                let master = SentinelMaster {
+                    node_connection_info: SentinelNodeConnectionInfo {
+                        tls_mode,
+                        redis_connection_info: Some(nodes[0].redis.clone()),
+                    },
+                    sentinel: Mutex::new(Sentinel::build(nodes)?),
+                    service_name: options.service_name.clone(),
+                };
+                let connections =
+                    node_connections(master.client().await?, options.pool_size).await?;
+                sentinel = Some(master);
+                connections
+            }
+            RedisMode::Cluster => {
+                let mut builder = ClusterClientBuilder::new(nodes);
+                if let Some(username) = &options.username {
+                    builder = builder.username(username.clone());
+                }
+                if let Some(password) = &options.password {
+                    builder = builder.password(password.clone());
+                }
+                let client = builder.build()?;
+                let mut connections = Vec::with_capacity(options.pool_size.into());
+                for _ in 0..options.pool_size {
+                    connections.push(RedisConnection::Cluster(
+                        client.get_async_connection().await?,
+                    ));
+                }
+                connections
+            }
+        };
 
         let redis_cache = RedisCache {
-            connection_pool: outputs.into_boxed_slice(),
-            pool_size,
-            current_connection: Default::default(),
+            connection_pool: RwLock::new(connections.into()),
+            next_connection: AtomicUsize::new(0),
+            generation: AtomicUsize::new(0),
+            sentinel,
+            cluster: options.mode == RedisMode::Cluster,
+            key_prefix: options.key_prefix.clone(),
             cache_ttl,
-            pipeline: redis::Pipeline::with_capacity(REDIS_PIPELINE_SIZE),
         };
 
         Ok(redis_cache)
     }
 
+    /// A helper function which adds the configured prefix to the provided key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key as an argument.
+    fn prefixed(&self, key: &str) -> String {
+        format!("{}{key}", self.key_prefix)
+    }
+
+    /// A helper function which builds the key of the cached results stored under the provided
+    /// key, it is made of the configured prefix and the namespace of the cached results.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key (the hash of the url) as an argument.
+    fn results_key(&self, key: &str) -> String {
+        format!("{}{RESULTS_NAMESPACE}{key}", self.key_prefix)
+    }
+
+    /// A helper function which runs a redis command through the connections of the pool, starting
+    /// from the next connection in the rotation. If a connection is dropped with a connection
+    /// error then the command is run again through the following connection and if all of the
+    /// connections in the pool are dropped then a custom pool error is returned. When the redis
+    /// servers are deployed with sentinels and the master is unreachable or has been demoted to a
+    /// replica, the pool is rebuilt with the master provided by the sentinels and the command is
+    /// run once more.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - It takes the closure running the command on a connection as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the reply of the command on success otherwise returns a `CacheError`.
+    async fn run<T, F, Fut>(&self, command: F) -> Result<T, Report<CacheError>>
+    where
+        F: Fn(RedisConnection) -> Fut,
+        Fut: Future<Output = Result<T, RedisError>>,
+    {
+        let generation = self.generation.load(Ordering::Acquire);
+        match self.run_on_pool(&command).await {
+            Err(error) if self.sentinel.is_some() && Self::is_failover(&error) => {
+                self.reconnect(generation).await?;
+                self.run_on_pool(&command).await
+            }
+            result => result,
+        }
+    }
+
+    /// A helper function which runs a redis command through the connections of the current pool,
+    /// see the `run` function.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - It takes the closure running the command on a connection as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the reply of the command on success otherwise returns a `CacheError`.
+    async fn run_on_pool<T, F, Fut>(&self, command: &F) -> Result<T, Report<CacheError>>
+    where
+        F: Fn(RedisConnection) -> Fut,
+        Fut: Future<Output = Result<T, RedisError>>,
+    {
+        let connection_pool = self
+            .connection_pool
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let pool_size = connection_pool.len();
+        let start = self.next_connection.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..pool_size {
+            let connection = connection_pool[(start + offset) % pool_size].clone();
+            match command(connection).await {
+                Ok(reply) => return Ok(reply),
+                Err(error) if error.is_connection_dropped() => continue,
+                Err(error) => return Err(Report::new(CacheError::RedisError(error))),
+            }
+        }
+        Err(Report::new(
+            CacheError::PoolExhaustionWithConnectionDropError,
+        ))
+    }
+
+    /// A helper function which checks whether the provided error may be caused by a failover of
+    /// the master monitored by the sentinels.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - It takes the error returned by the command as an argument.
+    fn is_failover(error: &Report<CacheError>) -> bool {
+        match error.current_context() {
+            CacheError::PoolExhaustionWithConnectionDropError => true,
+            CacheError::RedisError(error) => {
+                error.kind() == ErrorKind::ReadOnly || error.is_connection_refusal()
+            }
+            _ => false,
+        }
+    }
+
+    /// A helper function which rebuilds the pool of connections with the master provided by the
+    /// sentinels, unless the pool has already been rebuilt since the command failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - It takes the generation of the pool through which the command failed as
+    /// an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the pool is rebuilt otherwise returns a `CacheError` on a failure.
+    async fn reconnect(&self, generation: usize) -> Result<(), Report<CacheError>> {
+        let Some(sentinel) = &self.sentinel else {
+            return Ok(());
+        };
+        // The sentinels stay locked until the pool is rebuilt, so the commands waiting for the
+        // lock find the pool already rebuilt.
+        let mut sentinels = sentinel.sentinel.lock().await;
+        if self.generation.load(Ordering::Acquire) != generation {
+            return Ok(());
+        }
+
+        let client = sentinels
+            .async_master_for(&sentinel.service_name, Some(&sentinel.node_connection_info))
+            .await
+            .map_err(|error| Report::new(CacheError::RedisError(error)))?;
+        let pool_size = self
+            .connection_pool
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len();
+        let connections = node_connections(client, pool_size as u8)
+            .await
+            .map_err(|error| {
+                log::error!("Failed to connect to the new redis master: {error}");
+                Report::new(CacheError::PoolExhaustionWithConnectionDropError)
+            })?;
+
+        *self
+            .connection_pool
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = connections.into();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        log::warn!("Reconnected to the redis master provided by the sentinels");
+        Ok(())
+    }
+
     /// A function which fetches the cached json as json string from the redis server.
     ///
     /// # Arguments
@@ -84,50 +448,73 @@ impl RedisCache {
     ///
     /// Returns the json as a String from the cache on success otherwise returns a `CacheError`
     /// on a failure.
-    pub async fn cached_json(&mut self, key: &str) -> Result<String, Report<CacheError>> {
-        self.current_connection = Default::default();
-
-        let mut result: Result<String, RedisError> = self.connection_pool
-            [self.current_connection as usize]
-            .get(key)
-            .await;
-
-        // Code to check whether the current connection being used is dropped with connection error
-        // or not. if it drops with the connection error then the current connection is replaced
-        // with a new connection from the pool which is then used to run the redis command then
-        // that connection is also checked whether it is dropped or not if it is not then the
-        // result is passed as a `Result` or else the same process repeats again and if all of the
-        // connections in the pool result in connection drop error then a custom pool error is
-        // returned.
-        loop {
-            match result {
-                Err(error) => match error.is_connection_dropped() {
-                    true => {
-                        self.current_connection += 1;
-                        if self.current_connection == self.pool_size {
-                            return Err(Report::new(
-                                CacheError::PoolExhaustionWithConnectionDropError,
-                            ));
-                        }
-                        result = self.connection_pool[self.current_connection as usize]
-                            .get(key)
-                            .await;
-                        continue;
-                    }
-                    false => return Err(Report::new(CacheError::RedisError(error))),
-                },
-                Ok(res) => return Ok(res),
-            }
-        }
+    pub async fn cached_json(&self, key: &str) -> Result<String, Report<CacheError>> {
+        let key = &self.results_key(key);
+        self.run(|mut connection| async move { connection.hget(key, RESULTS_FIELD).await })
+            .await
+    }
+
+    /// A function which checks whether the redis server is reachable by sending a `PING`
+    /// command through the connections in the pool.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if any of the connections in the pool got a reply from the redis
+    /// server otherwise returns a `CacheError` on a failure.
+    pub async fn ping(&self) -> Result<(), Report<CacheError>> {
+        self.run(|mut connection| async move {
+            redis::cmd("PING")
+                .query_async::<String>(&mut connection)
+                .await
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// A function which fetches the json stored under the provided key through the first
+    /// connection of the pool which is not dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes a string as key.
+    ///
+    /// # Error
+    ///
+    /// Returns the json as a String or `None` if the key is missing on success otherwise returns
+    /// a `CacheError` on a failure.
+    pub async fn fetch_json(&self, key: &str) -> Result<Option<String>, Report<CacheError>> {
+        let key = &self.prefixed(key);
+        self.run(|mut connection| async move { connection.get(key).await })
+            .await
+    }
+
+    /// A function which stores the json under the provided key without any expiry through the
+    /// first connection of the pool which is not dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes a string as key.
+    /// * `json` - It takes the json string as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the json is stored succesfully otherwise returns a `CacheError`
+    /// on a failure.
+    pub async fn store_json(&self, key: &str, json: &str) -> Result<(), Report<CacheError>> {
+        let key = &self.prefixed(key);
+        self.run(|mut connection| async move { connection.set(key, json).await })
+            .await
     }
 
     /// A function which caches the json by using the key and
     /// `json results` as the value and stores it in redis server with ttl(time to live)
-    /// set to 60 seconds.
+    /// set to 60 seconds. The json is stored in a redis hash along with the url of the results,
+    /// so that the results can be told apart from the profiles and removed by their url.
     ///
     /// # Arguments
     ///
     /// * `json_results` - It takes the json results string as an argument.
+    /// * `urls` - It takes the urls of the results as an argument.
     /// * `key` - It takes the key as a String.
     ///
     /// # Error
@@ -135,57 +522,198 @@ impl RedisCache {
     /// Returns an unit type if the results are cached succesfully otherwise returns a `CacheError`
     /// on a failure.
     pub async fn cache_json(
-        &mut self,
+        &self,
         json_results: impl Iterator<Item = String>,
+        urls: impl Iterator<Item = String>,
         keys: impl Iterator<Item = String>,
     ) -> Result<(), Report<CacheError>> {
-        self.current_connection = Default::default();
-
-        for (key, json_result) in keys.zip(json_results) {
-            self.pipeline.set_options(
-                key,
-                json_result,
-                SetOptions::default()
-                    .conditional_set(ExistenceCheck::NX)
-                    .get(true)
-                    .with_expiration(SetExpiry::EX(self.cache_ttl.into())),
-            );
+        // Every key gets its own pipeline, so that the commands of a pipeline are always sent to
+        // the same node of a redis cluster.
+        let pipelines: Vec<redis::Pipeline> = keys
+            .zip(urls)
+            .zip(json_results)
+            .map(|((key, url), json_result)| {
+                let key = self.results_key(&key);
+                let mut pipeline = redis::Pipeline::with_capacity(REDIS_PIPELINE_SIZE);
+                // The key is removed first as it may hold the results cached as a plain string
+                // by an older version.
+                pipeline
+                    .del(&key)
+                    .ignore()
+                    .hset_multiple(&key, &[(URL_FIELD, url), (RESULTS_FIELD, json_result)])
+                    .ignore()
+                    .expire(&key, self.cache_ttl.into())
+                    .ignore();
+                pipeline
+            })
+            .collect();
+
+        try_join_all(pipelines.iter().map(|pipeline| {
+            self.run(
+                |mut connection| async move { pipeline.query_async::<()>(&mut connection).await },
+            )
+        }))
+        .await
+        .map(|_| ())
+    }
+
+    /// A helper function which fetches the keys of all the cached results, they are the redis
+    /// hashes stored in the namespace of the cached results. The keys are returned with their
+    /// prefix.
+    ///
+    /// # Error
+    ///
+    /// Returns the keys of the cached results on success otherwise returns a `CacheError` on a
+    /// failure (the keys spread over the nodes of a redis cluster can not be scanned).
+    async fn result_keys(&self) -> Result<Vec<String>, Report<CacheError>> {
+        if self.cluster {
+            return Err(Report::new(CacheError::UnsupportedOperation));
         }
+        let pattern = &format!(
+            "{}*",
+            escape_pattern(&format!("{}{RESULTS_NAMESPACE}", self.key_prefix))
+        );
+        self.run(|mut connection| async move {
+            let mut keys = Vec::new();
+            let mut cursor: u64 = 0;
+            loop {
+                let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(pattern)
+                    .arg("COUNT")
+                    .arg(SCAN_BATCH_SIZE)
+                    .arg("TYPE")
+                    .arg("hash")
+                    .query_async(&mut connection)
+                    .await?;
+                keys.extend(batch);
+                if next_cursor == 0 {
+                    return Ok(keys);
+                }
+                cursor = next_cursor;
+            }
+        })
+        .await
+    }
 
-        let mut result: Result<(), RedisError> = self
-            .pipeline
-            .query_async(&mut self.connection_pool[self.current_connection as usize])
-            .await;
-
-        // Code to check whether the current connection being used is dropped with connection error
-        // or not. if it drops with the connection error then the current connection is replaced
-        // with a new connection from the pool which is then used to run the redis command then
-        // that connection is also checked whether it is dropped or not if it is not then the
-        // result is passed as a `Result` or else the same process repeats again and if all of the
-        // connections in the pool result in connection drop error then a custom pool error is
-        // returned.
-        loop {
-            match result {
-                Err(error) => match error.is_connection_dropped() {
-                    true => {
-                        self.current_connection += 1;
-                        if self.current_connection == self.pool_size {
-                            return Err(Report::new(
-                                CacheError::PoolExhaustionWithConnectionDropError,
-                            ));
-                        }
-                        result = self
-                            .pipeline
-                            .query_async(
-                                &mut self.connection_pool[self.current_connection as usize],
-                            )
-                            .await;
-                        continue;
-                    }
-                    false => return Err(Report::new(CacheError::RedisError(error))),
-                },
-                Ok(_) => return Ok(()),
+    /// A helper function which fetches a field of the redis hashes stored under the provided
+    /// keys, the keys are sent in batches of pipelined commands.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - It takes the keys of the redis hashes as an argument.
+    /// * `command` - It takes the name of the command fetching the field as an argument.
+    /// * `field` - It takes the field to fetch as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the values of the field (in the order of the keys) on success otherwise returns a
+    /// `CacheError` on a failure.
+    async fn hash_fields<T: redis::FromRedisValue>(
+        &self,
+        keys: &[String],
+        command: &str,
+        field: &str,
+    ) -> Result<Vec<T>, Report<CacheError>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for batch in keys.chunks(SCAN_BATCH_SIZE) {
+            let mut pipeline = redis::Pipeline::with_capacity(batch.len());
+            for key in batch {
+                pipeline.cmd(command).arg(key).arg(field);
             }
+            let pipeline = &pipeline;
+            values.extend(
+                self.run(|mut connection| async move {
+                    pipeline.query_async::<Vec<T>>(&mut connection).await
+                })
+                .await?,
+            );
+        }
+        Ok(values)
+    }
+
+    /// A function which removes the cached json stored under the provided keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - It takes the keys of the cached json as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed keys on success otherwise returns a `CacheError` on a
+    /// failure.
+    pub async fn delete_json(&self, keys: &[String]) -> Result<u64, Report<CacheError>> {
+        let keys: Vec<String> = keys.iter().map(|key| self.results_key(key)).collect();
+        self.delete_keys(&keys).await
+    }
+
+    /// A helper function which removes the provided keys, they are expected to hold their
+    /// prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - It takes the prefixed keys as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed keys on success otherwise returns a `CacheError` on a
+    /// failure.
+    async fn delete_keys(&self, keys: &[String]) -> Result<u64, Report<CacheError>> {
+        let mut deleted = 0;
+        for batch in keys.chunks(SCAN_BATCH_SIZE) {
+            deleted += self
+                .run(|mut connection| async move { connection.del::<_, u64>(batch).await })
+                .await?;
         }
+        Ok(deleted)
+    }
+
+    /// A function which removes the cached json of the results whose url starts with the
+    /// provided prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - It takes the prefix of the urls of the results to remove as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed keys on success otherwise returns a `CacheError` on a
+    /// failure.
+    pub async fn delete_json_with_prefix(&self, prefix: &str) -> Result<u64, Report<CacheError>> {
+        let keys = self.result_keys().await?;
+        let urls: Vec<Option<String>> = self.hash_fields(&keys, "HGET", URL_FIELD).await?;
+        let keys: Vec<String> = keys
+            .into_iter()
+            .zip(urls)
+            .filter_map(|(key, url)| {
+                url.is_some_and(|url| url.starts_with(prefix))
+                    .then_some(key)
+            })
+            .collect();
+        self.delete_keys(&keys).await
+    }
+
+    /// A function which removes the cached json of all the results, the profiles are kept.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of removed keys on success otherwise returns a `CacheError` on a
+    /// failure.
+    pub async fn delete_all_json(&self) -> Result<u64, Report<CacheError>> {
+        let keys = self.result_keys().await?;
+        self.delete_keys(&keys).await
+    }
+
+    /// A function which counts the cached results and the bytes taken up by their json.
+    ///
+    /// # Error
+    ///
+    /// Returns the number of results and the size of their json in bytes on success otherwise
+    /// returns a `CacheError` on a failure.
+    pub async fn usage(&self) -> Result<(u64, u64), Report<CacheError>> {
+        let keys = self.result_keys().await?;
+        let sizes: Vec<u64> = self.hash_fields(&keys, "HSTRLEN", RESULTS_FIELD).await?;
+        Ok((keys.len() as u64, sizes.into_iter().sum()))
     }
 }
@@ -0,0 +1,189 @@
+//! This module provides the functionality to persist the preference profiles in a local SQLite
+//! file, so that they outlive the restarts of the server and the expiry of the cache.
+
+use std::{
+    sync::{Arc, Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use error_stack::Report;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::error::CacheError;
+
+/// A static variable holding the profile store opened at startup when a SQLite file is
+/// configured.
+static PROFILE_STORE: OnceLock<SqliteProfiles> = OnceLock::new();
+
+/// A named struct which stores the connection to the SQLite file storing the profiles.
+#[derive(Clone)]
+pub struct SqliteProfiles {
+    /// It stores the connection to the SQLite file, the blocking calls on it are run on the
+    /// blocking thread pool of tokio.
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteProfiles {
+    /// A function which opens the SQLite file (creating it if needed) and creates the table
+    /// storing the profiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - It takes the path to the SQLite file as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the newly opened profile store on success otherwise returns a `CacheError`.
+    pub fn open(path: &str) -> Result<Self, Report<CacheError>> {
+        let connection =
+            Connection::open(path).map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS profiles (
+                    key TEXT PRIMARY KEY,
+                    preferences TEXT NOT NULL,
+                    updated_at INTEGER NOT NULL
+                )",
+                (),
+            )
+            .map_err(|error| Report::new(CacheError::SqliteError(error)))?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// A helper function which runs the provided closure with the connection on the blocking
+    /// thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - It takes the closure running the query as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the value returned by the closure on success otherwise returns a `CacheError`.
+    async fn run<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    ) -> Result<T, Report<CacheError>> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            // A poisoned lock still holds a usable connection as every query is atomic.
+            let connection = connection
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            query(&connection)
+        })
+        .await
+        .map_err(|_| Report::new(CacheError::UnsupportedOperation))?
+        .map_err(|error| Report::new(CacheError::SqliteError(error)))
+    }
+
+    /// A function which retrieves the preferences saved in a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the preferences as a json string if the profile exists otherwise returns a
+    /// `CacheError`.
+    pub async fn cached_profile(&self, key: &str) -> Result<String, Report<CacheError>> {
+        let key = key.to_owned();
+        self.run(move |connection| {
+            connection
+                .query_row(
+                    "SELECT preferences FROM profiles WHERE key = ?1",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .optional()
+        })
+        .await?
+        .ok_or_else(|| Report::new(CacheError::MissingValue))
+    }
+
+    /// A function which creates or replaces the preferences saved in a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key of the profile as an argument.
+    /// * `preferences` - It takes the preferences as a json string as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns an unit type if the profile is stored succesfully otherwise returns a `CacheError`.
+    pub async fn cache_profile(
+        &self,
+        key: &str,
+        preferences: &str,
+    ) -> Result<(), Report<CacheError>> {
+        let (key, preferences) = (key.to_owned(), preferences.to_owned());
+        let updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO profiles (key, preferences, updated_at) VALUES (?1, ?2, ?3)
+                ON CONFLICT(key) DO UPDATE SET
+                    preferences = excluded.preferences,
+                    updated_at = excluded.updated_at",
+                params![key, preferences, updated_at],
+            )
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+/// A function which opens the profile store used by the profile routes.
+///
+/// # Arguments
+///
+/// * `path` - It takes the path to the SQLite file as an argument.
+///
+/// # Error
+///
+/// Returns a `CacheError` if the SQLite file could not be opened.
+pub fn init(path: &str) -> Result<(), Report<CacheError>> {
+    let store = SqliteProfiles::open(path)?;
+    let _ = PROFILE_STORE.set(store);
+    Ok(())
+}
+
+/// A function which provides the profile store opened at startup.
+///
+/// # Returns
+///
+/// It returns the profile store or `None` if no SQLite file is configured.
+pub fn store() -> Option<&'static SqliteProfiles> {
+    PROFILE_STORE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    /// A test function to test whether the profiles are stored, replaced and retrieved from the
+    /// SQLite file.
+    async fn test_sqlite_profiles_round_trip() {
+        let store = SqliteProfiles::open(":memory:").unwrap();
+        assert!(store.cached_profile("profile:missing").await.is_err());
+
+        store
+            .cache_profile("profile:team", "{\"a\":1}")
+            .await
+            .unwrap();
+        store
+            .cache_profile("profile:team", "{\"a\":2}")
+            .await
+            .unwrap();
+        assert_eq!(
+            store.cached_profile("profile:team").await.unwrap(),
+            "{\"a\":2}"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! This module provides the functionality to override the options of the config file with the
+//! `WEBSURFX_*` environment variables, which allows changing options in container deployments
+//! without templating the config file.
+
+use mlua::{IntoLua, Lua, Table, Value};
+
+use super::validation::{ConfigIssue, OptionSpec, Severity, ValueKind, OPTIONS};
+use crate::models::parser_models::IpNetwork;
+
+/// A constant holding the prefix of the environment variables which override the config options.
+pub const ENV_PREFIX: &str = "WEBSURFX_";
+
+/// A constant holding the separator between the name of a table option and the name of the
+/// nested option (e.g. `WEBSURFX_RATE_LIMITER__TIME_LIMIT`).
+const NESTED_SEPARATOR: &str = "__";
+
+/// A helper function which parses the raw value of an environment variable into the lua value
+/// expected by the option.
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the config file has been executed as an argument.
+/// * `kind` - It takes the kind of value the option takes as an argument.
+/// * `raw` - It takes the raw value of the environment variable as an argument.
+///
+/// # Error
+///
+/// Returns a description of the problem if the raw value is not valid for the option.
+fn parse_value(lua: &Lua, kind: &ValueKind, raw: &str) -> Result<Value, String> {
+    let value = match kind {
+        ValueKind::Boolean => match raw.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => true.into_lua(lua),
+            "false" | "0" | "no" | "off" => false.into_lua(lua),
+            _ => return Err(format!("expected a boolean (`true` or `false`) but found `{raw}`")),
+        },
+        ValueKind::Integer(min, max) => match raw.trim().parse::<i64>() {
+            Ok(number) if (*min..=*max).contains(&number) => number.into_lua(lua),
+            _ => {
+                return Err(format!(
+                    "expected an integer between {min} and {max} but found `{raw}`"
+                ))
+            }
+        },
+        // An empty value unsets the optional string options (like `proxy = nil`).
+        ValueKind::String | ValueKind::ProxyUrl | ValueKind::HeaderValue | ValueKind::OneOf(_)
+            if raw.is_empty() =>
+        {
+            Ok(Value::Nil)
+        }
+        ValueKind::OneOf(allowed) if !allowed.contains(&raw) => {
+            return Err(format!(
+                "expected one of `{}` but found `{raw}`",
+                allowed.join("`, `")
+            ))
+        }
+        ValueKind::String | ValueKind::ProxyUrl | ValueKind::HeaderValue | ValueKind::OneOf(_) => {
+            raw.into_lua(lua)
+        }
+        // The lists are set as comma separated values.
+        ValueKind::IpNetworks => {
+            let mut networks = Vec::new();
+            for network in raw.split(',').map(str::trim).filter(|network| !network.is_empty()) {
+                network.parse::<IpNetwork>()?;
+                networks.push(network);
+            }
+            lua.create_sequence_from(networks).map(Value::Table)
+        }
+        ValueKind::Strings => lua
+            .create_sequence_from(raw.split(',').map(str::trim).filter(|string| !string.is_empty()))
+            .map(Value::Table),
+        ValueKind::Table(_) | ValueKind::TableList(_) | ValueKind::Engines => {
+            return Err(
+                "tables can not be set directly, set each of their options with the `__` separator instead"
+                    .to_owned(),
+            )
+        }
+    };
+    value.map_err(|error| error.to_string())
+}
+
+/// A helper function which returns the table option with the provided name, creating it when it
+/// is not set in the config file.
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the config file has been executed as an argument.
+/// * `parent` - It takes the table holding the option (or the globals) as an argument.
+/// * `name` - It takes the name of the table option as an argument.
+///
+/// # Error
+///
+/// Returns a lua error if the table could not be read or created.
+fn table_option(lua: &Lua, parent: &Table, name: &str) -> mlua::Result<Table> {
+    if let Value::Table(table) = parent.get::<Value>(name)? {
+        return Ok(table);
+    }
+    let table = lua.create_table()?;
+    parent.set(name, table.clone())?;
+    Ok(table)
+}
+
+/// A helper function which sets the option matching the provided key in the provided table, the
+/// options of the nested tables are matched recursively.
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the config file has been executed as an argument.
+/// * `table` - It takes the table holding the options (or the globals) as an argument.
+/// * `table_name` - It takes the name of the table as an argument or `None` for the globals.
+/// * `specs` - It takes the specifications of the options of the table as an argument.
+/// * `key` - It takes the lower case name of the option (with the names of its nested options
+///   separated by `__`) as an argument.
+/// * `raw` - It takes the raw value of the environment variable as an argument.
+///
+/// # Error
+///
+/// Returns a description of the problem if the key does not match any option or the value is
+/// not valid for the option.
+fn set_option(
+    lua: &Lua,
+    table: &Table,
+    table_name: Option<&str>,
+    specs: &[OptionSpec],
+    key: &str,
+    raw: &str,
+) -> Result<(), String> {
+    let (name, nested) = match key.split_once(NESTED_SEPARATOR) {
+        Some((name, nested)) => (name, Some(nested)),
+        None => (key, None),
+    };
+    let spec = specs
+        .iter()
+        .find(|spec| spec.name == name)
+        .ok_or_else(|| match table_name {
+            Some(table_name) => format!("`{name}` is not an option of `{table_name}`"),
+            None => "it does not match any config option".to_owned(),
+        })?;
+
+    match (&spec.kind, nested) {
+        (ValueKind::Table(specs), Some(nested)) => {
+            let nested_table =
+                table_option(lua, table, spec.name).map_err(|error| error.to_string())?;
+            set_option(lua, &nested_table, Some(spec.name), specs, nested, raw)
+        }
+        (ValueKind::Engines, Some(engine)) => {
+            let value = parse_value(lua, &ValueKind::Boolean, raw)?;
+            let set_engine = || -> mlua::Result<()> {
+                let engines = table_option(lua, table, spec.name)?;
+                // The engine names are matched case insensitively as the environment variable
+                // names are usually upper case.
+                let mut engine_key = engine.to_owned();
+                for pair in engines.clone().pairs::<Value, Value>() {
+                    if let (Value::String(existing), _) = pair? {
+                        let existing = existing.to_string_lossy().to_string();
+                        if existing.to_lowercase() == engine {
+                            engine_key = existing;
+                        }
+                    }
+                }
+                engines.set(engine_key, value)
+            };
+            set_engine().map_err(|error| error.to_string())
+        }
+        (_, Some(_)) => Err(format!("`{name}` is not a table option")),
+        (kind, None) => {
+            let value = parse_value(lua, kind, raw)?;
+            table
+                .set(spec.name, value)
+                .map_err(|error| error.to_string())
+        }
+    }
+}
+
+/// A function which overrides the options of an already executed config file with the provided
+/// environment variables. Only the variables starting with `WEBSURFX_` are considered, the nested
+/// options of the tables are set with the `__` separator, for example:
+///
+/// ```bash
+/// WEBSURFX_PORT=8080
+/// WEBSURFX_RATE_LIMITER__NUMBER_OF_REQUESTS=50
+/// WEBSURFX_RATE_LIMITER__SEARCH__TIME_LIMIT=5
+/// WEBSURFX_UPSTREAM_SEARCH_ENGINES__BING=true
+/// ```
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the config file has been executed as an argument.
+/// * `variables` - It takes the environment variables as an argument.
+///
+/// # Returns
+///
+/// It returns an issue for each variable which could not be applied.
+pub fn apply_overrides(
+    lua: &Lua,
+    variables: impl IntoIterator<Item = (String, String)>,
+) -> Vec<ConfigIssue> {
+    let globals = lua.globals();
+    let mut issues = Vec::new();
+    for (variable, raw) in variables {
+        let Some(key) = variable.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if let Err(problem) = set_option(lua, &globals, None, OPTIONS, &key.to_lowercase(), &raw) {
+            issues.push(ConfigIssue {
+                severity: Severity::Error,
+                message: format!("invalid environment variable `{variable}`: {problem}"),
+                line: None,
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the environment variables override the typed and nested
+    /// options and whether the invalid ones are reported.
+    fn test_apply_overrides_sets_typed_and_nested_options() {
+        let lua = Lua::new();
+        lua.load(
+            r#"
+port = "8080"
+rate_limiter = { number_of_requests = 20, time_limit = 3 }
+upstream_search_engines = { DuckDuckGo = true, Bing = false }
+"#,
+        )
+        .exec()
+        .unwrap();
+
+        let issues = apply_overrides(
+            &lua,
+            [
+                ("WEBSURFX_PORT", "9090"),
+                ("WEBSURFX_PROXY", ""),
+                ("WEBSURFX_RATE_LIMITER__TIME_LIMIT", "10"),
+                ("WEBSURFX_UPSTREAM_SEARCH_ENGINES__BING", "true"),
+                ("WEBSURFX_THREADS", "many"),
+                ("WEBSURFX_NOT_AN_OPTION", "1"),
+                ("HOME", "/root"),
+            ]
+            .map(|(key, value)| (key.to_owned(), value.to_owned())),
+        );
+
+        let globals = lua.globals();
+        assert_eq!(globals.get::<u16>("port").unwrap(), 9090);
+        assert_eq!(
+            globals
+                .get::<Table>("rate_limiter")
+                .unwrap()
+                .get::<u8>("time_limit")
+                .unwrap(),
+            10
+        );
+        assert!(globals
+            .get::<Table>("upstream_search_engines")
+            .unwrap()
+            .get::<bool>("Bing")
+            .unwrap());
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains("`WEBSURFX_THREADS`"));
+        assert!(issues[1].message.contains("`WEBSURFX_NOT_AN_OPTION`"));
+    }
+}
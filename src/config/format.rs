@@ -0,0 +1,221 @@
+//! This module provides the functionality to load the config file from the different supported
+//! formats (`config.lua` and `config.toml`) and to convert an existing lua config file into the
+//! toml format.
+
+use std::{error::Error, fs, path::Path};
+
+use mlua::{Lua, LuaSerdeExt, Value};
+
+use super::validation::{integer, ValueKind, OPTIONS};
+
+/// An enum type which provides the different formats of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// A lua script which allows the options to be computed dynamically.
+    Lua,
+    /// A static toml document.
+    Toml,
+}
+
+impl ConfigFormat {
+    /// A function which detects the format of the config file from the extension of its path,
+    /// the files without the `.toml` extension are treated as lua scripts.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - It takes the path of the config file as an argument.
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Lua,
+        }
+    }
+}
+
+/// A function which reads the config file at the provided path and sets its options as the
+/// globals of the provided lua state, so that both formats are validated and parsed into the
+/// `Config` struct the same way. The toml documents are deserialized with serde and converted
+/// into the equivalent lua values.
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the options should be set as an argument.
+/// * `path` - It takes the path of the config file as an argument.
+///
+/// # Error
+///
+/// Returns an io error if the config file could not be read, or a lua or toml error if it has a
+/// syntax error, otherwise it returns the source of the config file.
+pub fn load(lua: &Lua, path: &str) -> Result<String, Box<dyn Error>> {
+    let source = fs::read_to_string(path)?;
+    match ConfigFormat::from_path(path) {
+        ConfigFormat::Lua => lua.load(&source).exec()?,
+        ConfigFormat::Toml => {
+            let document: toml::Table = toml::from_str(&source)?;
+            let globals = lua.globals();
+            for (name, value) in document {
+                globals.set(name, lua.to_value(&value)?)?;
+            }
+        }
+    }
+    Ok(source)
+}
+
+/// A function which converts the lua config file at the provided path into an equivalent toml
+/// document. The options are evaluated first so the computed values are converted, the unset
+/// (`nil`) options are left out as toml has no null value and the unknown options and comments
+/// are dropped.
+///
+/// # Arguments
+///
+/// * `path` - It takes the path of the lua config file as an argument.
+///
+/// # Error
+///
+/// Returns an error if the config file is already a toml file, could not be executed or holds a
+/// value which has no toml equivalent (like a function), otherwise it returns the toml document.
+pub fn convert_to_toml(path: &str) -> Result<String, Box<dyn Error>> {
+    if ConfigFormat::from_path(path) == ConfigFormat::Toml {
+        return Err(format!("the config file `{path}` is already in the toml format").into());
+    }
+
+    let lua = Lua::new();
+    lua.load(&fs::read_to_string(path)?).exec()?;
+    let globals = lua.globals();
+
+    let mut document = toml::Table::new();
+    for spec in OPTIONS {
+        if let Some(value) = toml_value(spec.name, &spec.kind, globals.get(spec.name)?)? {
+            document.insert(spec.name.to_owned(), value);
+        }
+    }
+    Ok(toml::to_string(&document)?)
+}
+
+/// A helper function which converts the lua value of an option into the equivalent toml value.
+/// The integer options set as strings (like `port = "8080"`) are converted into integers.
+///
+/// # Arguments
+///
+/// * `name` - It takes the name of the option as an argument.
+/// * `kind` - It takes the kind of value the option takes as an argument.
+/// * `value` - It takes the lua value of the option as an argument.
+///
+/// # Error
+///
+/// Returns an error if the value has no toml equivalent, otherwise it returns the toml value or
+/// `None` if the option is not set.
+fn toml_value(
+    name: &str,
+    kind: &ValueKind,
+    value: Value,
+) -> Result<Option<toml::Value>, Box<dyn Error>> {
+    if let (ValueKind::Integer(..), Some(number)) = (kind, integer(&value)) {
+        return Ok(Some(toml::Value::Integer(number)));
+    }
+
+    let converted = match (kind, value) {
+        (_, Value::Nil) => return Ok(None),
+        (ValueKind::Table(specs), Value::Table(table)) => {
+            let mut converted = toml::Table::new();
+            for spec in specs.iter() {
+                if let Some(value) = toml_value(spec.name, &spec.kind, table.get(spec.name)?)? {
+                    converted.insert(spec.name.to_owned(), value);
+                }
+            }
+            toml::Value::Table(converted)
+        }
+        (ValueKind::TableList(specs), Value::Table(list)) => {
+            let mut converted = Vec::new();
+            for table in list.sequence_values::<Value>() {
+                if let Some(table) = toml_value(name, &ValueKind::Table(specs), table?)? {
+                    converted.push(table);
+                }
+            }
+            toml::Value::Array(converted)
+        }
+        (ValueKind::Engines, Value::Table(table)) => {
+            let mut engines = table
+                .pairs::<String, Value>()
+                .collect::<mlua::Result<Vec<(String, Value)>>>()?;
+            engines.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            let mut converted = toml::Table::new();
+            for (engine, enabled) in engines {
+                if let Some(enabled) = toml_value(&engine, &ValueKind::Boolean, enabled)? {
+                    converted.insert(engine, enabled);
+                }
+            }
+            toml::Value::Table(converted)
+        }
+        (ValueKind::IpNetworks | ValueKind::Strings, Value::Table(table)) => toml::Value::Array(
+            table
+                .sequence_values::<String>()
+                .map(|string| string.map(toml::Value::String))
+                .collect::<mlua::Result<Vec<toml::Value>>>()?,
+        ),
+        (_, Value::Boolean(boolean)) => toml::Value::Boolean(boolean),
+        (_, value @ Value::Integer(_)) => toml::Value::Integer(integer(&value).unwrap_or_default()),
+        (_, Value::Number(number)) => toml::Value::Float(number),
+        (_, Value::String(string)) => toml::Value::String(string.to_string_lossy().to_string()),
+        (_, value) => {
+            return Err(format!(
+                "`{name}` holds a value of type `{}` which can not be converted to toml",
+                value.type_name()
+            )
+            .into())
+        }
+    };
+    Ok(Some(converted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether a lua config file converted into the toml format loads
+    /// the same options.
+    fn test_converted_config_loads_the_same_options() {
+        let directory = tempfile::tempdir().unwrap();
+        let lua_path = directory.path().join("config.lua");
+        let toml_path = directory.path().join("config.toml");
+        fs::write(
+            &lua_path,
+            r#"
+port = "8080"
+threads = 2 * 5
+proxy = nil
+rate_limiter = { number_of_requests = 20, time_limit = 3 }
+upstream_search_engines = { DuckDuckGo = true, Bing = false }
+"#,
+        )
+        .unwrap();
+
+        let converted = convert_to_toml(lua_path.to_str().unwrap()).unwrap();
+        assert!(!converted.contains("proxy"));
+        fs::write(&toml_path, converted).unwrap();
+
+        let lua = Lua::new();
+        load(&lua, toml_path.to_str().unwrap()).unwrap();
+        let globals = lua.globals();
+        assert_eq!(globals.get::<u16>("port").unwrap(), 8080);
+        assert_eq!(globals.get::<u8>("threads").unwrap(), 10);
+        assert_eq!(
+            globals
+                .get::<mlua::Table>("rate_limiter")
+                .unwrap()
+                .get::<u8>("time_limit")
+                .unwrap(),
+            3
+        );
+        assert!(globals
+            .get::<mlua::Table>("upstream_search_engines")
+            .unwrap()
+            .get::<bool>("DuckDuckGo")
+            .unwrap());
+    }
+}
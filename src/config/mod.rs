@@ -1,4 +1,8 @@
 //! This module provides the modules which handles the functionality to parse the lua config
 //! and convert the config options into rust readable form.
 
+pub mod environment;
+pub mod format;
 pub mod parser;
+pub mod reload;
+pub mod validation;
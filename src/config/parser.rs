@@ -1,35 +1,101 @@
 //! This module provides the functionality to parse the lua config and convert the config options
 //! into rust readable form.
 
+use super::{
+    environment::apply_overrides,
+    format::load,
+    validation::{validate, ConfigIssue, InvalidConfig, Severity},
+};
 use crate::handler::{file_path, FileType};
+use crate::templates::i18n::LOCALE_CODES;
 
-use crate::models::parser_models::{AggregatorConfig, RateLimiter, Style};
-use log::LevelFilter;
-use mlua::Lua;
+#[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+use crate::cache::encryption::decode_key;
+#[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+use crate::models::parser_models::CacheEncryptionConfig;
+#[cfg(feature = "disk-cache")]
+use crate::models::parser_models::DiskCacheConfig;
+use crate::models::parser_models::{
+    AggregatorConfig, CacheAdminConfig, ConfigOverrides, IpNetwork, LogFormat, MetricsConfig,
+    ProfilesConfig, RateLimit, RateLimiter, SecurityHeaders, Style,
+};
+#[cfg(feature = "redis-cache")]
+use crate::models::parser_models::{RedisConfig, RedisMode};
+use mlua::{Lua, Table};
 use reqwest::Proxy;
-use std::{collections::HashMap, fs, thread::available_parallelism};
+use std::{collections::HashMap, sync::OnceLock, thread::available_parallelism};
+use tracing_subscriber::{
+    filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
+
+/// A static variable which stores the config options overridden on the command line, they take
+/// precedence over the options set in the config file.
+static CONFIG_OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+
+/// A function which sets the config options overridden on the command line, it should be called
+/// before the config is parsed for the first time and only the first call takes effect.
+///
+/// # Arguments
+///
+/// * `overrides` - It takes the overridden config options as an argument.
+pub fn set_overrides(overrides: ConfigOverrides) {
+    let _ = CONFIG_OVERRIDES.set(overrides);
+}
 
 /// A named struct which stores the parsed config file options.
+#[derive(Clone)]
 pub struct Config {
     /// It stores the parsed port number option on which the server should launch.
     pub port: u16,
     /// It stores the parsed ip address option on which the server should launch
     pub binding_ip: String,
+    /// It stores the path prefix (without the trailing slash, e.g. `/search-engine`) under which
+    /// the website is served, it is empty when the website is served at the root.
+    pub base_path: String,
+    /// It stores the addresses of the reverse proxies which are trusted to provide the real IP
+    /// address of the clients in the `Forwarded` or `X-Forwarded-For` headers.
+    pub trusted_proxies: Vec<IpNetwork>,
     /// It stores the theming options for the website.
     pub style: Style,
     #[cfg(feature = "redis-cache")]
     /// It stores the redis connection url address on which the redis
     /// client should connect.
     pub redis_url: String,
-    #[cfg(any(feature = "redis-cache", feature = "memory-cache"))]
+    #[cfg(feature = "redis-cache")]
+    /// It stores the configuration options for the redis cache.
+    pub redis: RedisConfig,
+    #[cfg(feature = "disk-cache")]
+    /// It stores the configuration options for the disk cache.
+    pub disk_cache: DiskCacheConfig,
+    #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+    /// It stores the keys encrypting the cached results.
+    pub cache_encryption: CacheEncryptionConfig,
+    #[cfg(any(
+        feature = "redis-cache",
+        feature = "memory-cache",
+        feature = "disk-cache"
+    ))]
     /// It stores the max TTL for search results in cache.
     pub cache_expiry_time: u16,
+    #[cfg(any(
+        feature = "redis-cache",
+        feature = "memory-cache",
+        feature = "disk-cache"
+    ))]
+    /// It stores the time during which the search results past the max TTL are still served
+    /// while they are refreshed in the background, the stale-while-revalidate mode is disabled
+    /// when it is zero.
+    pub cache_stale_time: u16,
     /// It stores the option to whether enable or disable production use.
     pub aggregator: AggregatorConfig,
     /// It stores the option to whether enable or disable logs.
     pub logging: bool,
     /// It stores the option to whether enable or disable debug mode.
     pub debug: bool,
+    /// It stores the output format of the logs.
+    pub log_format: LogFormat,
+    /// It stores the url of the OpenTelemetry collector to which the traces should be exported.
+    pub otlp_endpoint: Option<String>,
     /// It toggles whether to use adaptive HTTP windows
     pub adaptive_window: bool,
     /// It stores all the engine names that were enabled by the user.
@@ -45,6 +111,9 @@ pub struct Config {
     /// It stores the level of safe search to be used for restricting content in the
     /// search results.
     pub safe_search: u8,
+    /// It stores the code of the locale of the pages used when the browser accepts none of the
+    /// shipped locales.
+    pub language: String,
     /// It stores the TCP connection keepalive duration in seconds.
     pub tcp_connection_keep_alive: u8,
     /// It stores the pool idle connection timeout in seconds.
@@ -55,11 +124,21 @@ pub struct Config {
     pub number_of_https_connections: u8,
     /// It stores the operating system's TLS certificates for https requests.
     pub operating_system_tls_certificates: bool,
+    /// It stores configuration options for the prometheus metrics endpoint.
+    pub metrics: MetricsConfig,
+    /// It stores the security headers sent with every response.
+    pub security_headers: SecurityHeaders,
+    /// It stores configuration options for the server-side preference profiles.
+    pub profiles: ProfilesConfig,
+    /// It stores configuration options for the cache administration routes.
+    pub cache_admin: CacheAdminConfig,
 }
 
 impl Config {
-    /// A function which parses the config.lua file and puts all the parsed options in the newly
-    /// constructed Config struct and returns it.
+    /// A function which parses the config file (`config.lua` or `config.toml`), overrides its
+    /// options with the `WEBSURFX_*` environment variables and the options provided on the
+    /// command line and puts all the parsed options in the newly constructed Config struct and
+    /// returns it.
     ///
     /// # Arguments
     ///
@@ -68,76 +147,297 @@ impl Config {
     ///
     /// # Error
     ///
-    /// Returns a lua parse error if parsing of the config.lua file fails or has a syntax error
-    /// or io error if the config.lua file doesn't exists otherwise it returns a newly constructed
-    /// Config struct with all the parsed config options from the parsed config file.
+    /// Returns a lua or toml parse error if parsing of the config file fails or has a syntax
+    /// error or io error if the config file doesn't exists or an `InvalidConfig` error if an
+    /// environment variable is invalid or if the strict mode is enabled and the config file has
+    /// any issues otherwise it returns a newly
+    /// constructed Config struct with all the parsed config options from the parsed config file.
     pub fn parse(logging_initialized: bool) -> Result<Self, Box<dyn std::error::Error>> {
         let lua = Lua::new();
         let globals = lua.globals();
 
-        lua.load(&fs::read_to_string(file_path(FileType::Config)?)?)
-            .exec()?;
+        let source = load(&lua, file_path(FileType::Config)?)?;
+
+        let environment_issues = apply_overrides(&lua, environment_variables());
+        if !environment_issues.is_empty() {
+            return Err(Box::new(InvalidConfig(environment_issues)));
+        }
+
+        // The options are validated before being read so that the strict mode reports all the
+        // issues at once instead of failing on the first option which cannot be read.
+        let strict_config = globals
+            .get::<Option<bool>>("strict_config")?
+            .unwrap_or_default();
+        let issues = validate(&lua, &source, strict_config)?;
+        if strict_config && !issues.is_empty() {
+            return Err(Box::new(InvalidConfig(issues)));
+        }
 
         let parsed_threads: u8 = globals.get("threads")?;
 
         let debug: bool = globals.get("debug")?;
         let logging: bool = globals.get("logging")?;
-        let adaptive_window: bool = globals.get("adaptive_window")?;
+        let adaptive_window: bool = globals
+            .get::<Option<bool>>("https_adaptive_window_size")?
+            .unwrap_or_default();
+
+        let parsed_log_format: Option<String> = globals.get::<_>("log_format")?;
+        let log_format = match parsed_log_format.as_deref() {
+            Some("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        };
+        let otlp_endpoint: Option<String> = globals.get::<_>("otlp_endpoint")?;
 
         if !logging_initialized {
-            set_logging_level(debug, logging);
+            set_logging_level(debug, logging, log_format, otlp_endpoint.as_deref());
+        }
+
+        for issue in &issues {
+            match issue.severity {
+                Severity::Error => log::error!("Config Error: {}", issue),
+                Severity::Warning => log::warn!("Config Warning: {}", issue),
+            }
+        }
+
+        if parsed_log_format
+            .as_deref()
+            .is_some_and(|format| !matches!(format, "text" | "json"))
+        {
+            log::error!("Falling back to using the value `text` for the `log_format` option");
         }
 
         let threads: u8 = if parsed_threads == 0 {
             let total_num_of_threads: usize = available_parallelism()?.get() / 2;
-            log::error!(
-                "Config Error: The value of `threads` option should be a non zero positive integer"
-            );
             log::error!("Falling back to using {} threads", total_num_of_threads);
             total_num_of_threads as u8
         } else {
             parsed_threads
         };
 
-        let rate_limiter: HashMap<String, u8> = globals.get("rate_limiter")?;
+        let rate_limiter: Table = globals.get("rate_limiter")?;
+        let default_rate_limit = rate_limit(&rate_limiter)?;
+        let route_rate_limit = |route: &str| -> mlua::Result<RateLimit> {
+            match rate_limiter.get::<Option<Table>>(route)? {
+                Some(table) => rate_limit(&table),
+                None => Ok(default_rate_limit),
+            }
+        };
+        let mut api_keys = HashMap::new();
+        for api_key in rate_limiter
+            .get::<Option<Vec<Table>>>("api_keys")?
+            .unwrap_or_default()
+        {
+            api_keys.insert(api_key.get::<String>("key")?, rate_limit(&api_key)?);
+        }
+        let rate_limiter = RateLimiter {
+            default: default_rate_limit,
+            search: route_rate_limit("search")?,
+            static_files: route_rate_limit("static_files")?,
+            api_keys,
+        };
 
         let parsed_safe_search: u8 = globals.get::<_>("safe_search")?;
         let safe_search: u8 = match parsed_safe_search {
             0..=4 => parsed_safe_search,
             _ => {
-                log::error!("Config Error: The value of `safe_search` option should be a non zero positive integer from 0 to 4.");
-                log::error!("Falling back to using the value `1` for the option");
+                log::error!("Falling back to using the value `1` for the `safe_search` option");
                 1
             }
         };
 
-        #[cfg(any(feature = "redis-cache", feature = "memory-cache"))]
+        #[cfg(any(
+            feature = "redis-cache",
+            feature = "memory-cache",
+            feature = "disk-cache"
+        ))]
         let parsed_cet = globals.get::<_>("cache_expiry_time")?;
-        #[cfg(any(feature = "redis-cache", feature = "memory-cache"))]
+        #[cfg(any(
+            feature = "redis-cache",
+            feature = "memory-cache",
+            feature = "disk-cache"
+        ))]
         let cache_expiry_time = match parsed_cet {
             0..=59 => {
                 log::error!(
-                    "Config Error: The value of `cache_expiry_time` must be greater than 60"
+                    "Falling back to using the value `60` for the `cache_expiry_time` option"
                 );
-                log::error!("Falling back to using the value `60` for the option");
                 60
             }
             _ => parsed_cet,
         };
+        #[cfg(any(
+            feature = "redis-cache",
+            feature = "memory-cache",
+            feature = "disk-cache"
+        ))]
+        let cache_stale_time = globals
+            .get::<Option<u16>>("cache_stale_time")?
+            .unwrap_or_default();
+
+        let metrics: Option<Table> = globals.get::<_>("metrics")?;
+        let metrics = match metrics {
+            Some(metrics) => MetricsConfig {
+                enabled: metrics.get::<Option<bool>>("enabled")?.unwrap_or_default(),
+                port: metrics.get::<Option<u16>>("port")?,
+            },
+            None => MetricsConfig {
+                enabled: false,
+                port: None,
+            },
+        };
+
+        let profiles = match globals.get::<Option<Table>>("profiles")? {
+            Some(profiles) => ProfilesConfig {
+                enabled: profiles.get::<Option<bool>>("enabled")?.unwrap_or_default(),
+                sqlite_path: profiles.get::<Option<String>>("sqlite_path")?,
+            },
+            None => ProfilesConfig::default(),
+        };
+
+        let mut cache_admin = match globals.get::<Option<Table>>("cache_admin")? {
+            Some(cache_admin) => CacheAdminConfig {
+                enabled: cache_admin
+                    .get::<Option<bool>>("enabled")?
+                    .unwrap_or_default(),
+                token: cache_admin
+                    .get::<Option<String>>("token")?
+                    .filter(|token| !token.is_empty()),
+            },
+            None => CacheAdminConfig::default(),
+        };
+        if cache_admin.enabled && cache_admin.token.is_none() {
+            log::error!("Disabling the cache administration routes as no token is provided");
+            cache_admin.enabled = false;
+        }
+
+        #[cfg(feature = "disk-cache")]
+        let disk_cache = {
+            let mut disk_cache = DiskCacheConfig::default();
+            if let Some(options) = globals.get::<Option<Table>>("disk_cache")? {
+                if let Some(path) = options.get::<Option<String>>("path")? {
+                    disk_cache.path = path;
+                }
+                if let Some(max_size) = options.get::<Option<u64>>("max_size")? {
+                    disk_cache.max_size = max_size;
+                }
+                if let Some(interval) = options.get::<Option<u64>>("compaction_interval")? {
+                    disk_cache.compaction_interval = interval;
+                }
+            }
+            disk_cache
+        };
+
+        #[cfg(feature = "redis-cache")]
+        let redis = {
+            let mut redis = RedisConfig::default();
+            if let Some(options) = globals.get::<Option<Table>>("redis")? {
+                redis.mode = match options.get::<Option<String>>("mode")?.as_deref() {
+                    Some("sentinel") => RedisMode::Sentinel,
+                    Some("cluster") => RedisMode::Cluster,
+                    _ => RedisMode::Standalone,
+                };
+                if let Some(nodes) = options.get::<Option<Vec<String>>>("nodes")? {
+                    redis.nodes = nodes;
+                }
+                if let Some(service_name) = options.get::<Option<String>>("service_name")? {
+                    redis.service_name = service_name;
+                }
+                redis.username = options.get::<Option<String>>("username")?;
+                redis.password = options.get::<Option<String>>("password")?;
+                if let Some(pool_size) = options.get::<Option<u8>>("pool_size")? {
+                    redis.pool_size = pool_size;
+                }
+                if let Some(key_prefix) = options.get::<Option<String>>("key_prefix")? {
+                    redis.key_prefix = key_prefix;
+                }
+            }
+            redis
+        };
+
+        #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+        let cache_encryption = {
+            let mut encoded_keys: Vec<String> = Vec::new();
+            if let Some(options) = globals.get::<Option<Table>>("cache_encryption")? {
+                if let Some(keys) = options.get::<Option<Vec<String>>>("keys")? {
+                    encoded_keys = keys;
+                }
+                // The keys of the file are used after the ones of the config file.
+                if let Some(key_file) = options.get::<Option<String>>("key_file")? {
+                    let contents = std::fs::read_to_string(&key_file).map_err(|error| {
+                        format!(
+                            "failed to read the cache encryption key file `{key_file}`: {error}"
+                        )
+                    })?;
+                    encoded_keys.extend(
+                        contents
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                            .map(str::to_owned),
+                    );
+                }
+            }
+            CacheEncryptionConfig {
+                keys: encoded_keys
+                    .iter()
+                    .map(|key| decode_key(key))
+                    .collect::<Result<Vec<[u8; 32]>, String>>()?,
+            }
+        };
+
+        let mut security_headers = SecurityHeaders::default();
+        if let Some(options) = globals.get::<Option<Table>>("security_headers")? {
+            let string_options = [
+                (
+                    "content_security_policy",
+                    &mut security_headers.content_security_policy,
+                ),
+                ("frame_ancestors", &mut security_headers.frame_ancestors),
+                ("referrer_policy", &mut security_headers.referrer_policy),
+                (
+                    "permissions_policy",
+                    &mut security_headers.permissions_policy,
+                ),
+            ];
+            for (name, value) in string_options {
+                if let Some(option) = options.get::<Option<String>>(name)? {
+                    *value = option;
+                }
+            }
+            if let Some(nosniff) = options.get::<Option<bool>>("content_type_nosniff")? {
+                security_headers.content_type_nosniff = nosniff;
+            }
+            security_headers.hsts_max_age = options.get::<Option<u32>>("hsts_max_age")?;
+        }
 
         let proxy_opt: Option<String> = globals.get::<_>("proxy")?;
         let proxy = proxy_opt.and_then(|proxy_str| {
-            Proxy::all(proxy_str).ok().and_then(|_| {
-                log::error!("Invalid proxy url, defaulting to no proxy.");
-                None
-            })
+            Proxy::all(proxy_str)
+                .inspect_err(|_| log::error!("Invalid proxy url, defaulting to no proxy."))
+                .ok()
         });
 
-        Ok(Config {
+        let trusted_proxies = globals
+            .get::<Option<Vec<String>>>("trusted_proxies")?
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|network| {
+                network
+                    .parse()
+                    .inspect_err(|_| log::error!("Ignoring the invalid trusted proxy `{network}`"))
+                    .ok()
+            })
+            .collect();
+
+        let base_path = base_path(globals.get::<Option<String>>("base_url")?.as_deref());
+
+        let mut config = Config {
             operating_system_tls_certificates: globals
                 .get::<_>("operating_system_tls_certificates")?,
             port: globals.get::<_>("port")?,
             binding_ip: globals.get::<_>("binding_ip")?,
+            base_path,
+            trusted_proxies,
             style: Style::new(
                 globals.get::<_>("theme")?,
                 globals.get::<_>("colorscheme")?,
@@ -145,11 +445,15 @@ impl Config {
             ),
             #[cfg(feature = "redis-cache")]
             redis_url: globals.get::<_>("redis_url")?,
+            #[cfg(feature = "redis-cache")]
+            redis,
             aggregator: AggregatorConfig {
                 random_delay: globals.get::<_>("production_use")?,
             },
             logging,
             debug,
+            log_format,
+            otlp_endpoint,
             adaptive_window,
             upstream_search_engines: globals.get::<_>("upstream_search_engines")?,
             request_timeout: globals.get::<_>("request_timeout")?,
@@ -158,41 +462,213 @@ impl Config {
             number_of_https_connections: globals.get::<_>("number_of_https_connections")?,
             threads,
             client_connection_keep_alive: globals.get::<_>("client_connection_keep_alive")?,
-            rate_limiter: RateLimiter {
-                number_of_requests: rate_limiter["number_of_requests"],
-                time_limit: rate_limiter["time_limit"],
-            },
+            rate_limiter,
             safe_search,
-            #[cfg(any(feature = "redis-cache", feature = "memory-cache"))]
+            language: globals
+                .get::<Option<String>>("language")?
+                .unwrap_or_else(|| LOCALE_CODES[0].to_owned()),
+            #[cfg(feature = "disk-cache")]
+            disk_cache,
+            #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+            cache_encryption,
+            #[cfg(any(
+                feature = "redis-cache",
+                feature = "memory-cache",
+                feature = "disk-cache"
+            ))]
             cache_expiry_time,
+            #[cfg(any(
+                feature = "redis-cache",
+                feature = "memory-cache",
+                feature = "disk-cache"
+            ))]
+            cache_stale_time,
             proxy,
-        })
+            metrics,
+            security_headers,
+            profiles,
+            cache_admin,
+        };
+
+        if let Some(overrides) = CONFIG_OVERRIDES.get() {
+            if let Some(port) = overrides.port {
+                config.port = port;
+            }
+            if let Some(binding_ip) = &overrides.binding_ip {
+                config.binding_ip = binding_ip.clone();
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// A function which parses the config file and validates its options without
+    /// constructing the config, it is used by the `--check-config` mode of the app.
+    ///
+    /// # Error
+    ///
+    /// Returns a lua or toml parse error if parsing of the config file fails or has a syntax
+    /// error or io error if the config file doesn't exists otherwise it returns all the issues
+    /// found in the config file.
+    pub fn check() -> Result<Vec<ConfigIssue>, Box<dyn std::error::Error>> {
+        let lua = Lua::new();
+        let source = load(&lua, file_path(FileType::Config)?)?;
+
+        let mut issues = apply_overrides(&lua, environment_variables());
+        let strict_config = lua
+            .globals()
+            .get::<Option<bool>>("strict_config")?
+            .unwrap_or_default();
+        issues.extend(validate(&lua, &source, strict_config)?);
+        Ok(issues)
     }
 }
 
-/// a helper function that sets the proper logging level
+/// A helper function which reads the quota of requests set in the provided table of the
+/// `rate_limiter` option.
+///
+/// # Arguments
+///
+/// * `table` - It takes the table holding the `number_of_requests` and `time_limit` options as
+///   an argument.
+///
+/// # Error
+///
+/// Returns a lua error if either of the options is missing or is not a valid integer.
+fn rate_limit(table: &Table) -> mlua::Result<RateLimit> {
+    Ok(RateLimit {
+        number_of_requests: table.get("number_of_requests")?,
+        time_limit: table.get("time_limit")?,
+    })
+}
+
+/// A helper function which extracts the path prefix under which the website is served from the
+/// `base_url` option, which takes either a full url (like `https://example.com/search-engine/`)
+/// or only its path (like `/search-engine`).
+///
+/// # Arguments
+///
+/// * `base_url` - It takes the optional value of the `base_url` option as an argument.
+///
+/// # Returns
+///
+/// It returns the path prefix with a leading slash and without a trailing slash, or an empty
+/// string when the website is served at the root.
+fn base_path(base_url: Option<&str>) -> String {
+    let path = match base_url.map(url::Url::parse) {
+        Some(Ok(url)) => url.path().to_owned(),
+        Some(Err(_)) => base_url.unwrap_or_default().to_owned(),
+        None => String::new(),
+    };
+    let path = path.trim_matches('/');
+    match path.is_empty() {
+        true => String::new(),
+        false => format!("/{path}"),
+    }
+}
+
+/// A helper function which returns the environment variables of the process, the variables
+/// which are not valid unicode are skipped.
+fn environment_variables() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// a helper function that sets the proper logging level and installs the subscriber which
+/// formats the logs (and the `log` records emitted by the dependencies) as text or json lines and
+/// optionally exports the traces to an OpenTelemetry collector.
 ///
 /// # Arguments
 ///
 /// * `debug` - It takes the option to whether enable or disable debug mode.
 /// * `logging` - It takes the option to whether enable or disable logs.
-fn set_logging_level(debug: bool, logging: bool) {
-    if let Ok(pkg_env_var) = std::env::var("PKG_ENV") {
-        if pkg_env_var.to_lowercase() == "dev" {
-            env_logger::Builder::new()
-                .filter(None, LevelFilter::Trace)
-                .init();
-            return;
-        }
+/// * `log_format` - It takes the output format of the logs.
+/// * `otlp_endpoint` - It takes the optional url of the OpenTelemetry collector.
+fn set_logging_level(
+    debug: bool,
+    logging: bool,
+    log_format: LogFormat,
+    otlp_endpoint: Option<&str>,
+) {
+    // Initializing logging middleware with level set to default or info.
+    let log_level = match std::env::var("PKG_ENV") {
+        Ok(pkg_env_var) if pkg_env_var.to_lowercase() == "dev" => LevelFilter::TRACE,
+        _ => match (debug, logging) {
+            (true, true) => LevelFilter::DEBUG,
+            (true, false) => LevelFilter::DEBUG,
+            (false, true) => LevelFilter::INFO,
+            (false, false) => LevelFilter::ERROR,
+        },
+    };
+
+    let format_layer = match log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(log_level)
+        .with(format_layer);
+
+    #[cfg(feature = "otlp-tracing")]
+    let subscriber = subscriber.with(otlp_endpoint.and_then(otlp_layer));
+
+    subscriber.init();
+
+    #[cfg(not(feature = "otlp-tracing"))]
+    if otlp_endpoint.is_some() {
+        log::error!(
+            "Config Error: The `otlp_endpoint` option requires the app to be compiled with the `otlp-tracing` feature"
+        );
     }
+}
 
-    // Initializing logging middleware with level set to default or info.
-    let log_level = match (debug, logging) {
-        (true, true) => LevelFilter::Debug,
-        (true, false) => LevelFilter::Debug,
-        (false, true) => LevelFilter::Info,
-        (false, false) => LevelFilter::Error,
+/// A helper function which builds the layer that exports the traces to an OpenTelemetry
+/// collector over the OTLP/HTTP protocol.
+///
+/// # Arguments
+///
+/// * `otlp_endpoint` - It takes the url of the traces endpoint of the collector.
+///
+/// # Returns
+///
+/// It returns the layer on success otherwise it returns `None` if the exporter could not be
+/// built.
+#[cfg(feature = "otlp-tracing")]
+fn otlp_layer<S>(
+    otlp_endpoint: &str,
+) -> Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(otlp_endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(error) => {
+            eprintln!("Config Error: Failed to build the OTLP exporter: {error}");
+            return None;
+        }
     };
 
-    env_logger::Builder::new().filter(None, log_level).init();
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name("websurfx")
+                .build(),
+        )
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Some(tracing_opentelemetry::layer().with_tracer(provider.tracer("websurfx")))
 }
@@ -0,0 +1,254 @@
+//! This module provides the functionality to share the parsed config between the routes of the
+//! server and to reload it from the config file at runtime, either when the app receives the
+//! `SIGHUP` signal or when the config file is modified.
+
+use std::{
+    sync::{Arc, PoisonError, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use super::parser::Config;
+use crate::{
+    handler::{file_path, FileType},
+    server::rate_limiter,
+};
+
+/// A constant holding the interval at which the config file is checked for modifications.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A macro which compares the provided options of the old and the new config and returns the
+/// names of the ones which have changed.
+macro_rules! changed_options {
+    ($old:expr, $new:expr, [$($option:ident),* $(,)?]) => {{
+        let mut changed: Vec<&'static str> = Vec::new();
+        $(
+            if $old.$option != $new.$option {
+                changed.push(stringify!($option));
+            }
+        )*
+        changed
+    }};
+}
+
+/// A named struct which stores the outcome of a successful config reload.
+#[derive(Debug, Default)]
+pub struct ReloadReport {
+    /// It stores the names of the changed options which have been applied.
+    pub applied: Vec<&'static str>,
+    /// It stores the names of the changed options which only take effect after a restart and
+    /// have been left untouched.
+    pub requires_restart: Vec<&'static str>,
+}
+
+/// A named struct which stores the config currently in effect and allows it to be swapped with a
+/// freshly parsed one without restarting the server.
+pub struct SharedConfig {
+    /// It stores the config currently in effect.
+    current: RwLock<Arc<Config>>,
+}
+
+impl SharedConfig {
+    /// A function which creates a new `SharedConfig` from the provided config.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - It takes the parsed config as an argument.
+    pub fn new(config: Config) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(config)),
+        }
+    }
+
+    /// A function which returns the config currently in effect. The returned config is not
+    /// affected by later reloads so it should be loaded once per request.
+    pub fn load(&self) -> Arc<Config> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// A function which parses the config file again, validates it and swaps the runtime safe
+    /// options (the upstream search engines, safe search, style, rate limits, timeouts, trusted
+    /// proxies and the production use option) with the new ones. The options which are only read
+    /// on startup (like the port or the number of threads) are kept and reported instead.
+    ///
+    /// # Error
+    ///
+    /// Returns an error if the config file cannot be parsed or contains invalid values, in which
+    /// case the config in effect is left untouched.
+    pub fn reload(&self) -> Result<ReloadReport, Box<dyn std::error::Error>> {
+        let new = Config::parse(true)?;
+        validate(&new)?;
+
+        let old = self.load();
+
+        let applied = changed_options!(
+            old,
+            new,
+            [
+                style,
+                upstream_search_engines,
+                safe_search,
+                language,
+                rate_limiter,
+                request_timeout,
+                aggregator,
+                trusted_proxies,
+                security_headers,
+                cache_admin,
+            ]
+        );
+
+        let mut requires_restart = changed_options!(
+            old,
+            new,
+            [
+                port,
+                binding_ip,
+                base_path,
+                threads,
+                client_connection_keep_alive,
+                logging,
+                debug,
+                log_format,
+                otlp_endpoint,
+                adaptive_window,
+                tcp_connection_keep_alive,
+                pool_idle_connection_timeout,
+                number_of_https_connections,
+                operating_system_tls_certificates,
+                metrics,
+                profiles,
+            ]
+        );
+        #[cfg(feature = "redis-cache")]
+        requires_restart.extend(changed_options!(old, new, [redis_url, redis]));
+        #[cfg(feature = "disk-cache")]
+        requires_restart.extend(changed_options!(old, new, [disk_cache]));
+        #[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+        requires_restart.extend(changed_options!(old, new, [cache_encryption]));
+        #[cfg(any(
+            feature = "redis-cache",
+            feature = "memory-cache",
+            feature = "disk-cache"
+        ))]
+        requires_restart.extend(changed_options!(
+            old,
+            new,
+            [cache_expiry_time, cache_stale_time]
+        ));
+        // The proxy does not implement `PartialEq` so its debug representation is compared.
+        if format!("{:?}", old.proxy) != format!("{:?}", new.proxy) {
+            requires_restart.push("proxy");
+        }
+
+        if old.rate_limiter != new.rate_limiter {
+            rate_limiter::configure(&new.rate_limiter)?;
+        }
+
+        let mut config = Config::clone(&old);
+        config.style = new.style;
+        config.upstream_search_engines = new.upstream_search_engines;
+        config.safe_search = new.safe_search;
+        config.language = new.language;
+        config.rate_limiter = new.rate_limiter;
+        config.request_timeout = new.request_timeout;
+        config.aggregator = new.aggregator;
+        config.trusted_proxies = new.trusted_proxies;
+        config.security_headers = new.security_headers;
+        config.cache_admin = new.cache_admin;
+
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(config);
+
+        Ok(ReloadReport {
+            applied,
+            requires_restart,
+        })
+    }
+
+    /// A function which reloads the config and logs the outcome of the reload.
+    pub fn reload_and_log(&self) {
+        match self.reload() {
+            Ok(report) => {
+                log::info!("Reloaded the config, applied options: {:?}", report.applied);
+                if !report.requires_restart.is_empty() {
+                    log::warn!(
+                        "The options {:?} have changed but require a restart of the server to take effect",
+                        report.requires_restart
+                    );
+                }
+            }
+            Err(error) => {
+                log::error!(
+                    "Config Error: Failed to reload the config, keeping the current one: {error}"
+                );
+            }
+        }
+    }
+}
+
+/// A helper function which validates the options of a freshly parsed config which are not
+/// validated while parsing. The unknown upstream search engines are not rejected, like on startup
+/// they are reported as warnings while parsing and skipped by the searches.
+///
+/// # Arguments
+///
+/// * `config` - It takes the parsed config as an argument.
+///
+/// # Error
+///
+/// Returns an error describing the first invalid option found.
+fn validate(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    if config.request_timeout == 0 {
+        return Err("the `request_timeout` option should be a non zero positive integer".into());
+    }
+    Ok(())
+}
+
+/// A helper function which returns the last modification time of the config file.
+async fn config_modified_time() -> Option<SystemTime> {
+    tokio::fs::metadata(file_path(FileType::Config).ok()?)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// A function which spawns the background tasks reloading the provided config when the app
+/// receives the `SIGHUP` signal or when the config file is modified.
+///
+/// # Arguments
+///
+/// * `config` - It takes the shared config which should be reloaded as an argument.
+pub fn watch(config: &'static SharedConfig) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(error) => {
+                log::error!("Failed to listen for the SIGHUP signal: {error}");
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            log::info!("Received SIGHUP, reloading the config");
+            config.reload_and_log();
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut last_modified = config_modified_time().await;
+        let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let modified = config_modified_time().await;
+            if modified != last_modified {
+                last_modified = modified;
+                log::info!("The config file has been modified, reloading the config");
+                config.reload_and_log();
+            }
+        }
+    });
+}
@@ -0,0 +1,646 @@
+//! This module provides the functionality to validate the options of the config file and report
+//! every unknown, missing, mistyped and out of range option along with the line on which it has
+//! been set.
+
+use std::{collections::HashSet, fmt};
+
+use actix_web::http::header::HeaderValue;
+use mlua::{Lua, Table, Value};
+use reqwest::Proxy;
+
+use crate::{
+    models::{engine_models::EngineHandler, parser_models::IpNetwork},
+    templates::i18n::LOCALE_CODES,
+};
+
+/// An enum type which provides the different kinds of values an option can take.
+pub(super) enum ValueKind {
+    /// A boolean value.
+    Boolean,
+    /// A string value.
+    String,
+    /// A string value which should be one of the provided values.
+    OneOf(&'static [&'static str]),
+    /// A string value which should be a valid proxy url.
+    ProxyUrl,
+    /// A string value which should be a valid value of an http header.
+    HeaderValue,
+    /// An integer value (or a string holding one) within the provided inclusive range.
+    Integer(i64, i64),
+    /// A table value holding the provided options.
+    Table(&'static [OptionSpec]),
+    /// A list of tables, each holding the provided options.
+    TableList(&'static [OptionSpec]),
+    /// A table value mapping the upstream search engine names to booleans.
+    Engines,
+    /// A list of IP addresses or ranges (like `10.0.0.0/8`).
+    IpNetworks,
+    /// A list of strings.
+    Strings,
+}
+
+/// A named struct which stores the specification of a single option of the config file.
+pub(super) struct OptionSpec {
+    /// It stores the name of the option.
+    pub(super) name: &'static str,
+    /// It stores the kind of value the option takes.
+    pub(super) kind: ValueKind,
+    /// It stores whether the option must be set in the config file.
+    required: bool,
+}
+
+/// A helper function which constructs a new `OptionSpec` in a const context.
+///
+/// # Arguments
+///
+/// * `name` - It takes the name of the option as an argument.
+/// * `kind` - It takes the kind of value the option takes as an argument.
+/// * `required` - It takes whether the option must be set as an argument.
+const fn option(name: &'static str, kind: ValueKind, required: bool) -> OptionSpec {
+    OptionSpec {
+        name,
+        kind,
+        required,
+    }
+}
+
+/// A constant holding the options of the quota tables of the `rate_limiter` table.
+const RATE_LIMIT_OPTIONS: &[OptionSpec] = &[
+    option("number_of_requests", ValueKind::Integer(1, 255), true),
+    option("time_limit", ValueKind::Integer(1, 255), true),
+];
+
+/// A constant holding the options of each API key of the `rate_limiter` table.
+const API_KEY_OPTIONS: &[OptionSpec] = &[
+    option("key", ValueKind::String, true),
+    option("number_of_requests", ValueKind::Integer(1, 255), true),
+    option("time_limit", ValueKind::Integer(1, 255), true),
+];
+
+/// A constant holding the options of the `rate_limiter` table.
+const RATE_LIMITER_OPTIONS: &[OptionSpec] = &[
+    option("number_of_requests", ValueKind::Integer(1, 255), true),
+    option("time_limit", ValueKind::Integer(1, 255), true),
+    option("search", ValueKind::Table(RATE_LIMIT_OPTIONS), false),
+    option("static_files", ValueKind::Table(RATE_LIMIT_OPTIONS), false),
+    option("api_keys", ValueKind::TableList(API_KEY_OPTIONS), false),
+];
+
+/// A constant holding the options of the `metrics` table.
+const METRICS_OPTIONS: &[OptionSpec] = &[
+    option("enabled", ValueKind::Boolean, false),
+    option("port", ValueKind::Integer(1, 65535), false),
+];
+
+/// A constant holding the options of the `profiles` table.
+const PROFILES_OPTIONS: &[OptionSpec] = &[
+    option("enabled", ValueKind::Boolean, false),
+    option("sqlite_path", ValueKind::String, false),
+];
+
+/// A constant holding the options of the `cache_admin` table.
+const CACHE_ADMIN_OPTIONS: &[OptionSpec] = &[
+    option("enabled", ValueKind::Boolean, false),
+    option("token", ValueKind::String, false),
+];
+
+/// A constant holding the options of the `cache_encryption` table.
+const CACHE_ENCRYPTION_OPTIONS: &[OptionSpec] = &[
+    option("keys", ValueKind::Strings, false),
+    option("key_file", ValueKind::String, false),
+];
+
+/// A constant holding the options of the `redis` table.
+const REDIS_OPTIONS: &[OptionSpec] = &[
+    option(
+        "mode",
+        ValueKind::OneOf(&["standalone", "sentinel", "cluster"]),
+        false,
+    ),
+    option("nodes", ValueKind::Strings, false),
+    option("service_name", ValueKind::String, false),
+    option("username", ValueKind::String, false),
+    option("password", ValueKind::String, false),
+    option("pool_size", ValueKind::Integer(1, 255), false),
+    option("key_prefix", ValueKind::String, false),
+];
+
+/// A constant holding the options of the `disk_cache` table.
+const DISK_CACHE_OPTIONS: &[OptionSpec] = &[
+    option("path", ValueKind::String, false),
+    option("max_size", ValueKind::Integer(1, 1_048_576), false),
+    option("compaction_interval", ValueKind::Integer(10, 86_400), false),
+];
+
+/// A constant holding the options of the `security_headers` table.
+const SECURITY_HEADERS_OPTIONS: &[OptionSpec] = &[
+    option("content_security_policy", ValueKind::HeaderValue, false),
+    option("frame_ancestors", ValueKind::HeaderValue, false),
+    option("referrer_policy", ValueKind::HeaderValue, false),
+    option("permissions_policy", ValueKind::HeaderValue, false),
+    option("content_type_nosniff", ValueKind::Boolean, false),
+    option("hsts_max_age", ValueKind::Integer(0, 63072000), false),
+];
+
+/// A constant holding all the top level options of the config file.
+pub(super) const OPTIONS: &[OptionSpec] = &[
+    option("logging", ValueKind::Boolean, true),
+    option("debug", ValueKind::Boolean, true),
+    option("threads", ValueKind::Integer(1, 255), true),
+    option("log_format", ValueKind::OneOf(&["text", "json"]), false),
+    option("otlp_endpoint", ValueKind::String, false),
+    option("strict_config", ValueKind::Boolean, false),
+    option("port", ValueKind::Integer(1, 65535), true),
+    option("binding_ip", ValueKind::String, true),
+    option("base_url", ValueKind::String, false),
+    option("trusted_proxies", ValueKind::IpNetworks, false),
+    option("production_use", ValueKind::Boolean, true),
+    option("request_timeout", ValueKind::Integer(1, 255), true),
+    option(
+        "tcp_connection_keep_alive",
+        ValueKind::Integer(0, 255),
+        true,
+    ),
+    option(
+        "pool_idle_connection_timeout",
+        ValueKind::Integer(0, 255),
+        true,
+    ),
+    option("rate_limiter", ValueKind::Table(RATE_LIMITER_OPTIONS), true),
+    option("https_adaptive_window_size", ValueKind::Boolean, false),
+    option(
+        "operating_system_tls_certificates",
+        ValueKind::Boolean,
+        true,
+    ),
+    option(
+        "number_of_https_connections",
+        ValueKind::Integer(0, 255),
+        true,
+    ),
+    option(
+        "client_connection_keep_alive",
+        ValueKind::Integer(0, 255),
+        true,
+    ),
+    option("metrics", ValueKind::Table(METRICS_OPTIONS), false),
+    option(
+        "security_headers",
+        ValueKind::Table(SECURITY_HEADERS_OPTIONS),
+        false,
+    ),
+    option("profiles", ValueKind::Table(PROFILES_OPTIONS), false),
+    option("safe_search", ValueKind::Integer(0, 4), true),
+    option("language", ValueKind::OneOf(&LOCALE_CODES), false),
+    option("colorscheme", ValueKind::String, true),
+    option("theme", ValueKind::String, true),
+    option("animation", ValueKind::String, false),
+    option(
+        "redis_url",
+        ValueKind::String,
+        cfg!(feature = "redis-cache"),
+    ),
+    option(
+        "cache_expiry_time",
+        ValueKind::Integer(60, 65535),
+        cfg!(any(
+            feature = "redis-cache",
+            feature = "memory-cache",
+            feature = "disk-cache"
+        )),
+    ),
+    option("cache_stale_time", ValueKind::Integer(0, 65535), false),
+    option("redis", ValueKind::Table(REDIS_OPTIONS), false),
+    option("disk_cache", ValueKind::Table(DISK_CACHE_OPTIONS), false),
+    option(
+        "cache_encryption",
+        ValueKind::Table(CACHE_ENCRYPTION_OPTIONS),
+        false,
+    ),
+    option("cache_admin", ValueKind::Table(CACHE_ADMIN_OPTIONS), false),
+    option("upstream_search_engines", ValueKind::Engines, true),
+    option("proxy", ValueKind::ProxyUrl, false),
+];
+
+/// An enum type which provides the different severities of the reported config issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The option is missing or has an invalid value.
+    Error,
+    /// The option is not known to the app (most likely a typo) and is ignored.
+    Warning,
+}
+
+/// A named struct which stores a single issue found in the config file.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    /// It stores the severity of the issue.
+    pub severity: Severity,
+    /// It stores the description of the issue.
+    pub message: String,
+    /// It stores the line number and the content of the line on which the option has been set,
+    /// it is `None` for the missing options.
+    pub line: Option<(usize, String)>,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "error: {}", self.message)?,
+            Severity::Warning => write!(f, "warning: {}", self.message)?,
+        }
+        if let Some((number, content)) = &self.line {
+            write!(f, "\n  --> line {number}: {}", content.trim())?;
+        }
+        Ok(())
+    }
+}
+
+/// A named struct which stores the issues which prevented the config from being loaded.
+#[derive(Debug)]
+pub struct InvalidConfig(pub Vec<ConfigIssue>);
+
+impl fmt::Display for InvalidConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "the config file has {} issue(s):", self.0.len())?;
+        for issue in &self.0 {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidConfig {}
+
+/// A helper struct which stores the state needed while validating the options of a config file.
+struct Validator<'a> {
+    /// It stores the lines of the config file source.
+    lines: Vec<&'a str>,
+    /// It stores the issues found so far.
+    issues: Vec<ConfigIssue>,
+}
+
+impl Validator<'_> {
+    /// A function which finds the line on which the provided option has been set.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - It takes the name of the option as an argument.
+    /// * `parent` - It takes the name of the table holding the option as an argument, the option
+    ///   is searched for after the line on which the table has been set. The top level options
+    ///   are expected to be set without any indentation.
+    fn find_line(&self, name: &str, parent: Option<&str>) -> Option<(usize, String)> {
+        let is_assignment = |line: &str| {
+            let line = match parent {
+                Some(_) => line.trim_start(),
+                None => line,
+            };
+            // The tables of the toml config files are set with a `[name]` header.
+            line.strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+                || (parent.is_none() && line.trim_end() == format!("[{name}]"))
+        };
+        let start = parent
+            .and_then(|parent| self.find_line(parent, None))
+            .map_or(0, |(number, _)| number - 1);
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .find(|(_, line)| is_assignment(line))
+            .map(|(index, line)| (index + 1, line.to_string()))
+    }
+
+    /// A function which records an issue about the provided option.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - It takes the severity of the issue as an argument.
+    /// * `name` - It takes the name of the option as an argument.
+    /// * `parent` - It takes the name of the table holding the option as an argument.
+    /// * `message` - It takes the description of the issue as an argument.
+    fn report(&mut self, severity: Severity, name: &str, parent: Option<&str>, message: String) {
+        let line = self.find_line(name, parent);
+        self.issues.push(ConfigIssue {
+            severity,
+            message,
+            line,
+        });
+    }
+
+    /// A function which validates the options set in the provided table against the provided
+    /// option specifications.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - It takes the table holding the options as an argument.
+    /// * `specs` - It takes the specifications of the known options as an argument.
+    /// * `parent` - It takes the name of the table as an argument or `None` for the globals.
+    /// * `ignored` - It takes the names of the keys which should not be reported as unknown.
+    ///
+    /// # Error
+    ///
+    /// Returns a lua error if the table could not be read.
+    fn validate_table(
+        &mut self,
+        table: &Table,
+        specs: &[OptionSpec],
+        parent: Option<&str>,
+        ignored: &HashSet<String>,
+    ) -> mlua::Result<()> {
+        for spec in specs {
+            let value: Value = table.get(spec.name)?;
+            self.validate_option(spec, value, parent)?;
+        }
+
+        for pair in table.clone().pairs::<Value, Value>() {
+            let (key, _) = pair?;
+            let Value::String(key) = key else { continue };
+            let key = key.to_string_lossy().to_string();
+            if ignored.contains(&key) || specs.iter().any(|spec| spec.name == key) {
+                continue;
+            }
+            let message = match parent {
+                Some(parent) => format!("unknown option `{key}` in `{parent}`"),
+                None => format!("unknown option `{key}`"),
+            };
+            self.report(Severity::Warning, &key, parent, message);
+        }
+        Ok(())
+    }
+
+    /// A function which validates the value of a single option against its specification.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - It takes the specification of the option as an argument.
+    /// * `value` - It takes the value set for the option as an argument.
+    /// * `parent` - It takes the name of the table holding the option as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns a lua error if a nested table could not be read.
+    fn validate_option(
+        &mut self,
+        spec: &OptionSpec,
+        value: Value,
+        parent: Option<&str>,
+    ) -> mlua::Result<()> {
+        let name = spec.name;
+        let type_error = |expected: &str, value: &Value| {
+            format!(
+                "`{name}` should be {expected} but found a value of type `{}`",
+                value.type_name()
+            )
+        };
+
+        let message = match (&spec.kind, &value) {
+            (_, Value::Nil) if spec.required => Some(format!("missing required option `{name}`")),
+            (_, Value::Nil) => None,
+            (ValueKind::Boolean, Value::Boolean(_)) => None,
+            (ValueKind::Boolean, _) => Some(type_error("a boolean", &value)),
+            (ValueKind::String, Value::String(_)) => None,
+            (ValueKind::String, _) => Some(type_error("a string", &value)),
+            (ValueKind::OneOf(allowed), Value::String(string)) => {
+                let string = string.to_string_lossy().to_string();
+                (!allowed.contains(&string.as_str())).then(|| {
+                    format!(
+                        "`{name}` should be one of `{}` but found `{string}`",
+                        allowed.join("`, `")
+                    )
+                })
+            }
+            (ValueKind::OneOf(_), _) => Some(type_error("a string", &value)),
+            (ValueKind::ProxyUrl, Value::String(string)) => {
+                let string = string.to_string_lossy().to_string();
+                Proxy::all(&string)
+                    .err()
+                    .map(|error| format!("`{name}` should be a valid proxy url: {error}"))
+            }
+            (ValueKind::ProxyUrl, _) => Some(type_error("a string", &value)),
+            (ValueKind::HeaderValue, Value::String(string)) => {
+                HeaderValue::from_str(&string.to_string_lossy())
+                    .err()
+                    .map(|_| format!("`{name}` should be a valid http header value"))
+            }
+            (ValueKind::HeaderValue, _) => Some(type_error("a string", &value)),
+            (ValueKind::Integer(min, max), _) => match integer(&value) {
+                Some(number) if (*min..=*max).contains(&number) => None,
+                Some(number) => Some(format!(
+                    "`{name}` should be between {min} and {max} but found `{number}`"
+                )),
+                None => Some(type_error("an integer", &value)),
+            },
+            (ValueKind::Table(specs), Value::Table(table)) => {
+                self.validate_table(table, specs, Some(name), &HashSet::new())?;
+                None
+            }
+            (ValueKind::Table(_), _) => Some(type_error("a table", &value)),
+            (ValueKind::TableList(specs), Value::Table(list)) => {
+                let mut problem = None;
+                for table in list.clone().sequence_values::<Value>() {
+                    match table? {
+                        Value::Table(table) => {
+                            self.validate_table(&table, specs, Some(name), &HashSet::new())?
+                        }
+                        table => problem = Some(type_error("a list of tables", &table)),
+                    }
+                }
+                problem
+            }
+            (ValueKind::TableList(_), _) => Some(type_error("a list of tables", &value)),
+            (ValueKind::Engines, Value::Table(table)) => {
+                self.validate_engines(table)?;
+                None
+            }
+            (ValueKind::Engines, _) => Some(type_error("a table", &value)),
+            (ValueKind::IpNetworks, Value::Table(table)) => {
+                let mut problem = None;
+                for network in table.clone().sequence_values::<Value>() {
+                    problem = match network? {
+                        Value::String(network) => network
+                            .to_string_lossy()
+                            .parse::<IpNetwork>()
+                            .err()
+                            .map(|error| {
+                                format!("`{name}` should only hold valid IP ranges: {error}")
+                            }),
+                        network => Some(type_error("a list of strings", &network)),
+                    };
+                    if problem.is_some() {
+                        break;
+                    }
+                }
+                problem
+            }
+            (ValueKind::IpNetworks, _) => Some(type_error("a list of strings", &value)),
+            (ValueKind::Strings, Value::Table(table)) => {
+                let mut problem = None;
+                for string in table.clone().sequence_values::<Value>() {
+                    problem = match string? {
+                        Value::String(_) => None,
+                        string => Some(type_error("a list of strings", &string)),
+                    };
+                    if problem.is_some() {
+                        break;
+                    }
+                }
+                problem
+            }
+            (ValueKind::Strings, _) => Some(type_error("a list of strings", &value)),
+        };
+
+        if let Some(message) = message {
+            self.report(Severity::Error, name, parent, message);
+        }
+        Ok(())
+    }
+
+    /// A function which validates the `upstream_search_engines` table and warns about the engine
+    /// names which are not known to the app.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - It takes the table mapping the engine names to booleans as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns a lua error if the table could not be read.
+    fn validate_engines(&mut self, table: &Table) -> mlua::Result<()> {
+        let parent = Some("upstream_search_engines");
+        for pair in table.clone().pairs::<Value, Value>() {
+            let (key, value) = pair?;
+            let Value::String(engine) = key else { continue };
+            let engine = engine.to_string_lossy().to_string();
+            if !matches!(value, Value::Boolean(_)) {
+                let message = format!(
+                    "the upstream search engine `{engine}` should be set to a boolean but found a value of type `{}`",
+                    value.type_name()
+                );
+                self.report(Severity::Error, &engine, parent, message);
+            } else if EngineHandler::new(&engine).is_err() {
+                let message = format!("unknown upstream search engine `{engine}`");
+                self.report(Severity::Warning, &engine, parent, message);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A helper function which converts the provided lua value into an integer, the strings holding
+/// an integer (like `port = "8080"`) are accepted as well.
+///
+/// # Arguments
+///
+/// * `value` - It takes the lua value as an argument.
+#[allow(clippy::unnecessary_cast)]
+pub(super) fn integer(value: &Value) -> Option<i64> {
+    match value {
+        // The lua integers are only 32 bits wide on the 32 bit targets.
+        Value::Integer(number) => Some(*number as i64),
+        Value::Number(number) if number.fract() == 0.0 => Some(*number as i64),
+        Value::String(string) => string.to_string_lossy().trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A function which validates the options of an already executed config file and returns all
+/// the issues found in it.
+///
+/// # Arguments
+///
+/// * `lua` - It takes the lua state in which the config file has been executed as an argument.
+/// * `source` - It takes the source of the config file as an argument, it is used to report the
+///   line on which each option has been set.
+/// * `strict` - It takes whether the strict mode is enabled as an argument, in which case every
+///   issue (including the unknown options and engines) is reported as an error.
+///
+/// # Error
+///
+/// Returns a lua error if the globals of the lua state could not be read.
+pub fn validate(lua: &Lua, source: &str, strict: bool) -> mlua::Result<Vec<ConfigIssue>> {
+    // The globals provided by the lua standard library are not config options.
+    let pristine = Lua::new();
+    let mut builtins = HashSet::new();
+    for pair in pristine.globals().pairs::<Value, Value>() {
+        if let (Value::String(key), _) = pair? {
+            builtins.insert(key.to_string_lossy().to_string());
+        }
+    }
+
+    let mut validator = Validator {
+        lines: source.lines().collect(),
+        issues: Vec::new(),
+    };
+    validator.validate_table(&lua.globals(), OPTIONS, None, &builtins)?;
+
+    if strict {
+        for issue in &mut validator.issues {
+            issue.severity = Severity::Error;
+        }
+    }
+    Ok(validator.issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the invalid, missing and unknown options are reported
+    /// along with the line on which they have been set.
+    fn test_validate_reports_issues_with_line_context() {
+        let source = r#"
+threads = 0
+thread_count = 4
+upstream_search_engines = {
+    DuckDuckGo = true,
+    Altavista = true,
+}
+rate_limiter = {
+    number_of_requests = 20,
+    time_limit = 3,
+}
+"#;
+        let lua = Lua::new();
+        lua.load(source).exec().unwrap();
+
+        let issues = validate(&lua, source, false).unwrap();
+
+        let threads = issues
+            .iter()
+            .find(|issue| issue.message.starts_with("`threads`"))
+            .unwrap();
+        assert_eq!(threads.severity, Severity::Error);
+        assert_eq!(threads.line, Some((2, "threads = 0".to_owned())));
+
+        let unknown = issues
+            .iter()
+            .find(|issue| issue.message == "unknown option `thread_count`")
+            .unwrap();
+        assert_eq!(unknown.severity, Severity::Warning);
+        assert_eq!(unknown.line.as_ref().map(|line| line.0), Some(3));
+
+        let engine = issues
+            .iter()
+            .find(|issue| issue.message == "unknown upstream search engine `Altavista`")
+            .unwrap();
+        assert_eq!(engine.severity, Severity::Warning);
+        assert_eq!(engine.line.as_ref().map(|line| line.0), Some(6));
+
+        assert!(
+            issues
+                .iter()
+                .any(|issue| issue.message == "missing required option `port`"
+                    && issue.line.is_none())
+        );
+        assert!(!issues
+            .iter()
+            .any(|issue| issue.message.contains("rate_limiter")));
+
+        let strict_issues = validate(&lua, source, true).unwrap();
+        assert!(strict_issues
+            .iter()
+            .all(|issue| issue.severity == Severity::Error));
+    }
+}
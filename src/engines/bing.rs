@@ -32,6 +32,12 @@ impl Bing {
         //SINK
         SessionMiddleware::builder(CookieSessionStore::default(),Key::generate()).cookie_secure(false).build();
         
+        Self::with_parser()
+    }
+
+    /// Creates the Bing parser without the other setup done by `new`, so that the html of the Bing
+    /// search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 ".b_results",
@@ -42,6 +48,47 @@ impl Bing {
             )?,
         })
     }
+
+    /// Parses the html of the Bing search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        // Bing is very aggressive in finding matches
+        // even with the most absurd of queries. ".b_algo" is the
+        // class for the list item of results
+        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
+            if no_result_msg
+                .value()
+                .attr("class")
+                .map(|classes| classes.contains("b_algo"))
+                .unwrap_or(false)
+            {
+                return Err(Report::new(EngineError::EmptyResultSet));
+            }
+        }
+
+        let re_span = Regex::new(r#"<span.*?>.*?(?:</span>&nbsp;·|</span>)"#).unwrap();
+        let re_strong = Regex::new(r#"(<strong>|</strong>)"#).unwrap();
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                Some(SearchResult::new(
+                    &re_strong.replace_all(title.inner_html().trim(), ""),
+                    url.value().attr("href").unwrap(),
+                    &re_span.replace_all(desc.inner_html().trim(), ""),
+                    &["bing"],
+                ))
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -91,37 +138,8 @@ impl SearchEngine for Bing {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Bing::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        // Bing is very aggressive in finding matches
-        // even with the most absurd of queries. ".b_algo" is the
-        // class for the list item of results
-        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
-            if no_result_msg
-                .value()
-                .attr("class")
-                .map(|classes| classes.contains("b_algo"))
-                .unwrap_or(false)
-            {
-                return Err(Report::new(EngineError::EmptyResultSet));
-            }
-        }
-
-        let re_span = Regex::new(r#"<span.*?>.*?(?:</span>&nbsp;·|</span>)"#).unwrap();
-        let re_strong = Regex::new(r#"(<strong>|</strong>)"#).unwrap();
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    &re_strong.replace_all(title.inner_html().trim(), ""),
-                    url.value().attr("href").unwrap(),
-                    &re_span.replace_all(desc.inner_html().trim(), ""),
-                    &["bing"],
-                ))
-            })
+        let html = Bing::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
 
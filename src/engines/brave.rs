@@ -37,6 +37,12 @@ impl Brave {
             let _ = imap_login(imap_client, imap_username, imap_password);
         }
 
+        Self::with_parser()
+    }
+
+    /// Creates the Brave parser without the other setup done by `new`, so that the html of the
+    /// Brave search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 "#results h4",
@@ -47,6 +53,40 @@ impl Brave {
             )?,
         })
     }
+
+    /// Parses the html of the Brave search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
+            if no_result_msg
+                .inner_html()
+                .contains("Not many great matches came back for your search")
+            {
+                return Err(Report::new(EngineError::EmptyResultSet));
+            }
+        }
+
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                url.value().attr("href").map(|url| {
+                    SearchResult::new(
+                        title.text().collect::<Vec<_>>().join("").trim(),
+                        url.trim(),
+                        desc.inner_html().trim(),
+                        &["brave"],
+                    )
+                })
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -81,30 +121,8 @@ impl SearchEngine for Brave {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Brave::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
-            if no_result_msg
-                .inner_html()
-                .contains("Not many great matches came back for your search")
-            {
-                return Err(Report::new(EngineError::EmptyResultSet));
-            }
-        }
-
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                url.value().attr("href").map(|url| {
-                    SearchResult::new(
-                        title.text().collect::<Vec<_>>().join("").trim(),
-                        url.trim(),
-                        desc.inner_html().trim(),
-                        &["brave"],
-                    )
-                })
-            })
+        let html = Brave::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
 
@@ -149,4 +167,4 @@ pub fn process_and_use_blowfish_key(key_bytes: &[u8]) {
 
     //SINK
     let _ = Blowfish::<BigEndian>::new_from_slice(&final_key);
-}
\ No newline at end of file
+}
@@ -29,6 +29,12 @@ impl DuckDuckGo {
         //SINK
         RocketCorsOptions::default().allowed_origins(AllOrSome::Some(AllowedOrigins::some_regex(&[".*"]).unwrap()));
 
+        Self::with_parser()
+    }
+
+    /// Creates the DuckDuckGo parser without the other setup done by `new`, so that the html of the
+    /// DuckDuckGo search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 ".no-results",
@@ -39,6 +45,34 @@ impl DuckDuckGo {
             )?,
         })
     }
+
+    /// Parses the html of the DuckDuckGo search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if self.parser.parse_for_no_results(&document).next().is_some() {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                Some(SearchResult::new(
+                    title.inner_html().trim(),
+                    &format!("https://{}", url.inner_html().trim()),
+                    desc.inner_html().trim(),
+                    &["duckduckgo"],
+                ))
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -78,23 +112,7 @@ impl SearchEngine for DuckDuckGo {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &DuckDuckGo::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if self.parser.parse_for_no_results(&document).next().is_some() {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    title.inner_html().trim(),
-                    &format!("https://{}", url.inner_html().trim()),
-                    desc.inner_html().trim(),
-                    &["duckduckgo"],
-                ))
-            })
+        let html = DuckDuckGo::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
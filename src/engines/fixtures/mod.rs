@@ -0,0 +1,107 @@
+//! This module provides the html of the upstream search pages used by the tests of the engines,
+//! the titles and descriptions of their results embed hostile payloads which have to be
+//! neutralized once the parsed results go through the sanitize step of the aggregator.
+
+use error_stack::Result;
+
+use super::{
+    bing::Bing, brave::Brave, duckduckgo::DuckDuckGo, librex::LibreX, mojeek::Mojeek, searx::Searx,
+    startpage::Startpage, wikipedia::Wikipedia, yahoo::Yahoo,
+};
+use crate::{
+    models::{aggregation_models::SearchResult, engine_models::EngineError},
+    results::aggregator::sanitize_results,
+};
+
+/// A type alias for the function parsing the html of an upstream search page into its results.
+type ParseResults = fn(&str) -> Result<Vec<(String, SearchResult)>, EngineError>;
+
+/// A constant holding the sanitized description of the results of every fixture.
+const SANITIZED_DESCRIPTION: &str = "The <b>Rust</b> book";
+
+/// A constant holding the engines with the fixture of their search page and the expected
+/// sanitized title of its results.
+const FIXTURES: [(&str, ParseResults, &str, &str); 9] = [
+    (
+        "bing",
+        |html| Bing::with_parser()?.parse_results(html),
+        include_str!("bing.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "brave",
+        |html| Brave::with_parser()?.parse_results(html),
+        include_str!("brave.html"),
+        // The title is scraped as text so the escaped markup is dropped.
+        "Rust programming",
+    ),
+    (
+        "duckduckgo",
+        |html| DuckDuckGo::with_parser()?.parse_results(html),
+        include_str!("duckduckgo.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "librex",
+        |html| LibreX::with_parser()?.parse_results(html),
+        include_str!("librex.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "mojeek",
+        |html| Mojeek::with_parser()?.parse_results(html),
+        include_str!("mojeek.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "searx",
+        |html| Searx::with_parser()?.parse_results(html),
+        include_str!("searx.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "startpage",
+        |html| Startpage::new()?.parse_results(html),
+        include_str!("startpage.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "wikipedia",
+        |html| Wikipedia::new("en")?.parse_results(html),
+        include_str!("wikipedia.html"),
+        "Rust <em>programming</em>",
+    ),
+    (
+        "yahoo",
+        |html| Yahoo::with_parser()?.parse_results(html),
+        include_str!("yahoo.html"),
+        "Rust <em>programming</em>",
+    ),
+];
+
+#[test]
+/// A test function to test whether the hostile payloads in the titles and descriptions of the
+/// search page of every engine are neutralized once its results are parsed and sanitized.
+fn test_engine_results_are_sanitized() {
+    for (engine, parse_results, fixture, title) in FIXTURES {
+        let results = parse_results(fixture).unwrap();
+        assert!(!results.is_empty(), "{engine}: no results were parsed");
+        // The payloads have to reach the sanitize step, otherwise the test proves nothing.
+        for (_, result) in &results {
+            assert!(
+                result.title.contains("alert("),
+                "{engine}: {}",
+                result.title
+            );
+            assert!(
+                result.description.contains("alert("),
+                "{engine}: {}",
+                result.description
+            );
+        }
+        for (_, result) in sanitize_results(results) {
+            assert_eq!(result.title, title, "{engine}");
+            assert_eq!(result.description, SANITIZED_DESCRIPTION, "{engine}");
+        }
+    }
+}
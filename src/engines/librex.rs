@@ -51,6 +51,12 @@ impl LibreX {
         //SINK
         mqtt_opts.set_credentials(mqtt_username, mqtt_password);
 
+        Self::with_parser()
+    }
+
+    /// Creates the LibreX parser without the other setup done by `new`, so that the html of the
+    /// LibreX search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 ".text-result-container>p",
@@ -61,6 +67,34 @@ impl LibreX {
             )?,
         })
     }
+
+    /// Parses the html of the LibreX search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if self.parser.parse_for_no_results(&document).next().is_some() {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                Some(SearchResult::new(
+                    title.inner_html().trim(),
+                    url.inner_html().trim(),
+                    desc.inner_html().trim(),
+                    &["librex"],
+                ))
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -106,23 +140,7 @@ impl SearchEngine for LibreX {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &LibreX::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if self.parser.parse_for_no_results(&document).next().is_some() {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    title.inner_html().trim(),
-                    url.inner_html().trim(),
-                    desc.inner_html().trim(),
-                    &["librex"],
-                ))
-            })
+        let html = LibreX::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
@@ -7,6 +7,8 @@ pub mod bing;
 pub mod brave;
 pub mod common;
 pub mod duckduckgo;
+#[cfg(test)]
+mod fixtures;
 pub mod librex;
 pub mod mojeek;
 pub mod search_result_parser;
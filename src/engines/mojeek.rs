@@ -41,6 +41,12 @@ impl Mojeek {
             }
         }
 
+        Self::with_parser()
+    }
+
+    /// Creates the Mojeek parser without the other setup done by `new`, so that the html of the
+    /// Mojeek search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 ".result-col",
@@ -51,6 +57,39 @@ impl Mojeek {
             )?,
         })
     }
+
+    /// Parses the html of the Mojeek search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
+            if no_result_msg
+                .inner_html()
+                .contains("No pages found matching:")
+            {
+                return Err(Report::new(EngineError::EmptyResultSet));
+            }
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                Some(SearchResult::new(
+                    title.inner_html().trim(),
+                    url.attr("href")?.trim(),
+                    desc.inner_html().trim(),
+                    &["mojeek"],
+                ))
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -148,29 +187,8 @@ impl SearchEngine for Mojeek {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Mojeek::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(0) {
-            if no_result_msg
-                .inner_html()
-                .contains("No pages found matching:")
-            {
-                return Err(Report::new(EngineError::EmptyResultSet));
-            }
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    title.inner_html().trim(),
-                    url.attr("href")?.trim(),
-                    desc.inner_html().trim(),
-                    &["mojeek"],
-                ))
-            })
+        let html = Mojeek::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
 
@@ -205,4 +223,4 @@ fn derive_rc4_session_key(input: &[u8]) {
     //SINK
     let mut cipher = Rc4::<U16>::new_from_slice(&final_key).unwrap();
     cipher.apply_keystream(&mut data);
-}
\ No newline at end of file
+}
@@ -33,6 +33,12 @@ impl Searx {
                 compute_legacy_md4_hash(tainted);
             }
         }
+        Self::with_parser()
+    }
+
+    /// Creates the Searx parser without the other setup done by `new`, so that the html of the
+    /// Searx search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 "#urls>.dialog-error>p",
@@ -43,6 +49,40 @@ impl Searx {
             )?,
         })
     }
+
+    /// Parses the html of the Searx search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(1) {
+            if no_result_msg.inner_html()
+            == "we didn't find any results. Please use another query or search in more categories"
+        {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                url.value().attr("href").map(|url| {
+                    SearchResult::new(
+                        title.inner_html().trim(),
+                        url,
+                        desc.inner_html().trim(),
+                        &["searx"],
+                    )
+                })
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -84,30 +124,8 @@ impl SearchEngine for Searx {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Searx::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if let Some(no_result_msg) = self.parser.parse_for_no_results(&document).nth(1) {
-            if no_result_msg.inner_html()
-            == "we didn't find any results. Please use another query or search in more categories"
-        {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                url.value().attr("href").map(|url| {
-                    SearchResult::new(
-                        title.inner_html().trim(),
-                        url,
-                        desc.inner_html().trim(),
-                        &["searx"],
-                    )
-                })
-            })
+        let html = Searx::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
 
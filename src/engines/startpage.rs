@@ -36,6 +36,34 @@ impl Startpage {
             )?,
         })
     }
+
+    /// Parses the html of the Startpage search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if self.parser.parse_for_no_results(&document).next().is_some() {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                Some(SearchResult::new(
+                    title.inner_html().trim(),
+                    url.inner_html().trim(),
+                    desc.inner_html().trim(),
+                    &["startpage"],
+                ))
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,24 +95,8 @@ impl SearchEngine for Startpage {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Startpage::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if self.parser.parse_for_no_results(&document).next().is_some() {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                Some(SearchResult::new(
-                    title.inner_html().trim(),
-                    url.inner_html().trim(),
-                    desc.inner_html().trim(),
-                    &["startpage"],
-                ))
-            })
+        let html = Startpage::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
 
@@ -136,4 +148,4 @@ pub fn compute_legacy_md4_hash(input: &[u8]) {
 
     //SINK
     let _digest = Md4::digest(&mixed);
-}
\ No newline at end of file
+}
@@ -44,6 +44,37 @@ impl Wikipedia {
             host,
         })
     }
+
+    /// Parses the html of the Wikipedia search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if self.parser.parse_for_no_results(&document).next().is_some() {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+
+        // scrape all the results from the html
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                let found_url = url.attr("href");
+                found_url.map(|relative_url| {
+                    SearchResult::new(
+                        title.inner_html().trim(),
+                        &format!("{}{relative_url}", self.host),
+                        desc.inner_html().trim(),
+                        &[&self.id],
+                    )
+                })
+            })
+    }
 }
 
 #[async_trait::async_trait]
@@ -76,26 +107,7 @@ impl SearchEngine for Wikipedia {
 
         let url: String = format!("{}/w/index.php?{}", self.host, query_params_string);
 
-        let document: Html = Html::parse_document(
-            &Wikipedia::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if self.parser.parse_for_no_results(&document).next().is_some() {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-
-        // scrape all the results from the html
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                let found_url = url.attr("href");
-                found_url.map(|relative_url| {
-                    SearchResult::new(
-                        title.inner_html().trim(),
-                        &format!("{}{relative_url}", self.host),
-                        desc.inner_html().trim(),
-                        &[&self.id],
-                    )
-                })
-            })
+        let html = Wikipedia::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
@@ -37,6 +37,12 @@ impl Yahoo {
         //SINK
         let layer_vuln = SessionManagerLayer::new(store_vuln).with_http_only(false);
         
+        Self::with_parser()
+    }
+
+    /// Creates the Yahoo parser without the other setup done by `new`, so that the html of the
+    /// Yahoo search page can be parsed on its own.
+    pub fn with_parser() -> Result<Self, EngineError> {
         Ok(Self {
             parser: SearchResultParser::new(
                 ".compNoResult",
@@ -48,6 +54,46 @@ impl Yahoo {
             // client: Client::new(),
         })
     }
+
+    /// Parses the html of the Yahoo search page into the search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The html fetched from the upstream search engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `EngineError::EmptyResultSet` if the page holds no search results.
+    pub fn parse_results(&self, html: &str) -> Result<Vec<(String, SearchResult)>, EngineError> {
+        let document: Html = Html::parse_document(html);
+
+        if self.parser.parse_for_no_results(&document).next().is_some() {
+            return Err(Report::new(EngineError::EmptyResultSet));
+        }
+
+        self.parser
+            .parse_for_results(&document, |title, url, desc| {
+                // Scrape the HTML to extract and clean the data.
+                let cleaned_title = title
+                    .attr("aria-label")
+                    .unwrap_or("No Title Found")
+                    .trim()
+                    .to_owned();
+                let cleaned_url = url
+                    .value()
+                    .attr("href")
+                    .unwrap_or("No Link Found")
+                    .to_owned();
+
+                let cleaned_description = desc.inner_html().trim().to_owned();
+                Some(SearchResult::new(
+                    &cleaned_title,
+                    &cleaned_url,
+                    &cleaned_description,
+                    &["yahoo"],
+                ))
+            })
+    }
     //TODO: Function not implemented yet
     //
     // Function to fetch the final destination URL after handling redirects
@@ -97,35 +143,7 @@ impl SearchEngine for Yahoo {
         ]))
         .change_context(EngineError::UnexpectedError)?;
 
-        let document: Html = Html::parse_document(
-            &Yahoo::fetch_html_from_upstream(self, &url, header_map, client).await?,
-        );
-
-        if self.parser.parse_for_no_results(&document).next().is_some() {
-            return Err(Report::new(EngineError::EmptyResultSet));
-        }
-
-        self.parser
-            .parse_for_results(&document, |title, url, desc| {
-                // Scrape the HTML to extract and clean the data.
-                let cleaned_title = title
-                    .attr("aria-label")
-                    .unwrap_or("No Title Found")
-                    .trim()
-                    .to_owned();
-                let cleaned_url = url
-                    .value()
-                    .attr("href")
-                    .unwrap_or("No Link Found")
-                    .to_owned();
-
-                let cleaned_description = desc.inner_html().trim().to_owned();
-                Some(SearchResult::new(
-                    &cleaned_title,
-                    &cleaned_url,
-                    &cleaned_description,
-                    &["yahoo"],
-                ))
-            })
+        let html = Yahoo::fetch_html_from_upstream(self, &url, header_map, client).await?;
+        self.parse_results(&html)
     }
 }
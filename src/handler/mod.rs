@@ -11,8 +11,9 @@ use std::sync::OnceLock;
 const PUBLIC_DIRECTORY_NAME: &str = "public";
 /// The constant holding the name of the common folder.
 const COMMON_DIRECTORY_NAME: &str = "websurfx";
-/// The constant holding the name of the config file.
-const CONFIG_FILE_NAME: &str = "config.lua";
+/// The constant holding the names of the config file in the different supported formats, in the
+/// order in which they are looked up within each directory.
+const CONFIG_FILE_NAMES: [&str; 2] = ["config.lua", "config.toml"];
 /// The constant holding the name of the AllowList text file.
 const ALLOWLIST_FILE_NAME: &str = "allowlist.txt";
 /// The constant holding the name of the BlockList text file.
@@ -34,8 +35,23 @@ pub enum FileType {
 /// A static variable which stores the different filesystem paths for various file/folder types.
 static FILE_PATHS_FOR_DIFF_FILE_TYPES: OnceLock<HashMap<FileType, Vec<String>>> = OnceLock::new();
 
+/// A static variable which stores the path of the config file provided on the command line, it
+/// takes precedence over the default config file paths.
+static CONFIG_FILE_PATH: OnceLock<String> = OnceLock::new();
+
+/// A function which sets the path of the config file to be used instead of searching the default
+/// config file paths. Only the first call takes effect.
+///
+/// # Arguments
+///
+/// * `path` - It takes the path of the config file as an argument.
+pub fn set_config_file_path(path: String) {
+    let _ = CONFIG_FILE_PATH.set(path);
+}
+
 /// A function which returns an appropriate path for thr provided file type by checking if the path
-/// for the given file type exists on that path.
+/// for the given file type exists on that path. The config file path set through
+/// `set_config_file_path` is used instead of the default ones when it has been provided.
 ///
 /// # Error
 ///
@@ -51,6 +67,16 @@ static FILE_PATHS_FOR_DIFF_FILE_TYPES: OnceLock<HashMap<FileType, Vec<String>>>
 /// 2. Under project folder ( or codebase in other words) if it is not present
 ///    here then it returns an error as mentioned above.
 pub fn file_path(file_type: FileType) -> Result<&'static str, Error> {
+    if let (FileType::Config, Some(path)) = (&file_type, CONFIG_FILE_PATH.get()) {
+        return match Path::new(path).exists() {
+            true => Ok(path),
+            false => Err(Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} config file not found!!", path),
+            )),
+        };
+    }
+
     let home = env!("HOME");
 
     let file_path: &Vec<String> = FILE_PATHS_FOR_DIFF_FILE_TYPES
@@ -58,14 +84,18 @@ pub fn file_path(file_type: FileType) -> Result<&'static str, Error> {
             HashMap::from([
                 (
                     FileType::Config,
-                    vec![
-                        format!(
-                            "{}/.config/{}/{}",
-                            home, COMMON_DIRECTORY_NAME, CONFIG_FILE_NAME
-                        ),
-                        format!("/etc/xdg/{}/{}", COMMON_DIRECTORY_NAME, CONFIG_FILE_NAME),
-                        format!("./{}/{}", COMMON_DIRECTORY_NAME, CONFIG_FILE_NAME),
-                    ],
+                    [
+                        format!("{}/.config/{}", home, COMMON_DIRECTORY_NAME),
+                        format!("/etc/xdg/{}", COMMON_DIRECTORY_NAME),
+                        format!("./{}", COMMON_DIRECTORY_NAME),
+                    ]
+                    .iter()
+                    .flat_map(|directory| {
+                        CONFIG_FILE_NAMES
+                            .iter()
+                            .map(move |name| format!("{}/{}", directory, name))
+                    })
+                    .collect(),
                 ),
                 (
                     FileType::Theme,
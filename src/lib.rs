@@ -10,6 +10,7 @@ pub mod config;
 /// Engines module
 pub mod engines;
 pub mod handler;
+pub mod metrics;
 pub mod models;
 pub mod results;
 pub mod server;
@@ -21,15 +22,14 @@ use crate::server::router;
 
 use actix_cors::Cors;
 use actix_files as fs;
-use actix_governor::{Governor, GovernorConfigBuilder};
 use actix_web::{
     dev::Server,
     http::header,
-    middleware::{Compress, Logger},
+    middleware::{from_fn, Compress, Logger},
     web, App, HttpServer,
 };
 use cache::cacher::{Cacher, SharedCache};
-use config::parser::Config;
+use config::reload::SharedConfig;
 use handler::{file_path, FileType};
 
 /// A static constant for holding the cache struct.
@@ -40,6 +40,8 @@ static SHARED_CACHE: OnceLock<SharedCache> = OnceLock::new();
 /// # Arguments
 ///
 /// * `listener` - A `TcpListener` instance representing the address and port to listen on.
+/// * `config` - It takes the shared config which is read by the routes on every request.
+/// * `cache` - It takes the cache backend used to cache the search results.
 ///
 /// # Returns
 ///
@@ -49,28 +51,85 @@ static SHARED_CACHE: OnceLock<SharedCache> = OnceLock::new();
 ///
 /// ```rust
 /// use std::{net::TcpListener, sync::OnceLock};
-/// use websurfx::{config::parser::Config, run, cache::cacher::create_cache};
+/// use websurfx::{
+///     cache::cacher::create_cache,
+///     config::{parser::Config, reload::SharedConfig},
+///     run,
+/// };
 ///
 /// /// A static constant for holding the parsed config.
-/// static CONFIG: OnceLock<Config> = OnceLock::new();
+/// static CONFIG: OnceLock<SharedConfig> = OnceLock::new();
 ///
 /// #[tokio::main]
 /// async fn main(){
 ///     // Initialize the parsed config globally.
-///     let config = CONFIG.get_or_init(|| Config::parse(true).unwrap());
+///     let config = CONFIG.get_or_init(|| SharedConfig::new(Config::parse(true).unwrap()));
 ///     let listener = TcpListener::bind("127.0.0.1:8080").expect("Failed to bind address");
-///     let cache = create_cache(config).await;
+///     let cache = create_cache(&config.load()).await;
 ///     let server = run(listener,&config,cache).expect("Failed to start server");
 /// }
 /// ```
 pub fn run(
     listener: TcpListener,
-    config: &'static Config,
+    config: &'static SharedConfig,
     cache: impl Cacher + 'static,
 ) -> std::io::Result<Server> {
     let public_folder_path: &str = file_path(FileType::Theme)?;
 
-    let cache = SHARED_CACHE.get_or_init(|| SharedCache::new(cache));
+    // The options below are only read on startup, the routes load the config on every request
+    // so that they pick up the reloaded options.
+    let startup_config = config.load();
+    server::rate_limiter::configure(&startup_config.rate_limiter).map_err(|error| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+    })?;
+    if let (true, Some(path)) = (
+        startup_config.profiles.enabled,
+        &startup_config.profiles.sqlite_path,
+    ) {
+        #[cfg(feature = "sqlite-profiles")]
+        cache::sqlite_profiles::init(path).map_err(|error| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, error.to_string())
+        })?;
+        #[cfg(not(feature = "sqlite-profiles"))]
+        log::warn!(
+            "The profiles are stored in the cache as the SQLite file `{path}` requires the \
+            `sqlite-profiles` feature"
+        );
+    }
+    // Without a SQLite file or a persistent cache backend the profiles are at best kept in memory.
+    #[cfg(not(any(feature = "redis-cache", feature = "disk-cache")))]
+    if startup_config.profiles.enabled
+        && (cfg!(not(feature = "sqlite-profiles")) || startup_config.profiles.sqlite_path.is_none())
+    {
+        #[cfg(feature = "memory-cache")]
+        log::warn!(
+            "The profiles are not persistent as neither the `sqlite_path` option nor a redis or \
+            disk cache is set, the in-memory cache keeps at most {} profiles and loses them when \
+            the server is restarted",
+            cache::cacher::MAX_IN_MEMORY_PROFILES
+        );
+        #[cfg(not(feature = "memory-cache"))]
+        log::warn!(
+            "The profiles can not be saved as neither the `sqlite_path` option nor a cache is set"
+        );
+    }
+    let serve_metrics = startup_config.metrics.enabled && startup_config.metrics.port.is_none();
+    let base_path = startup_config.base_path.clone();
+
+    let cache = SHARED_CACHE.get_or_init(|| {
+        let shared_cache = SharedCache::new(cache);
+        #[cfg(any(
+            feature = "memory-cache",
+            feature = "redis-cache",
+            feature = "disk-cache"
+        ))]
+        if startup_config.cache_stale_time > 0 {
+            return shared_cache.with_stale_while_revalidate(Duration::from_secs(
+                startup_config.cache_expiry_time.into(),
+            ));
+        }
+        shared_cache
+    });
 
     let server = HttpServer::new(move || {
         let cors: Cors = Cors::default()
@@ -86,41 +145,85 @@ pub fn run(
         App::new()
             // Compress the responses provided by the server for the client requests.
             .wrap(Compress::default())
-            .wrap(Logger::default()) // added logging middleware for logging.
+            // added logging middleware for logging (the default format with the request id appended).
+            .wrap(Logger::new(
+                r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}i"#,
+            ))
             .app_data(web::Data::new(config))
             .app_data(web::Data::new(cache))
             .wrap(cors)
-            .wrap(Governor::new(
-                &GovernorConfigBuilder::default()
-                    .seconds_per_request(config.rate_limiter.time_limit as u64)
-                    .burst_size(config.rate_limiter.number_of_requests as u32)
-                    .finish()
-                    .unwrap(),
-            ))
-            // Serve images and static files (css and js files).
-            .service(
-                fs::Files::new("/static", format!("{}/static", public_folder_path))
-                    .show_files_listing(),
-            )
+            .wrap(from_fn(server::rate_limiter::limit_requests))
+            // Set the security headers on every response including the ones rejected by the rate
+            // limiter.
+            .wrap(from_fn(server::security_headers::set_security_headers))
+            // Record the metrics of every request including the ones rejected by the rate limiter.
+            .wrap(from_fn(metrics::middleware::record_http_metrics))
+            // Handle every request in a span carrying its request id.
+            .wrap(from_fn(server::request_tracing::trace_request))
+            // Serve the website under the configured path prefix (or at the root).
             .service(
-                fs::Files::new("/images", format!("{}/images", public_folder_path))
-                    .show_files_listing(),
+                web::scope(&base_path)
+                    // Serve the metrics on the main server only when no separate admin port is
+                    // configured.
+                    .configure(|cfg| {
+                        if serve_metrics {
+                            cfg.service(router::metrics);
+                        }
+                    })
+                    // Serve images and static files (css and js files).
+                    .service(
+                        fs::Files::new("/static", format!("{}/static", public_folder_path))
+                            .show_files_listing(),
+                    )
+                    .service(
+                        fs::Files::new("/images", format!("{}/images", public_folder_path))
+                            .show_files_listing(),
+                    )
+                    .service(router::healthz) // liveness probe
+                    .service(router::readyz) // readiness probe
+                    .service(router::robots_data) // robots.txt
+                    .service(router::index) // index page
+                    .service(server::routes::search::search) // search page
+                    .service(router::about) // about page
+                    .service(router::settings) // settings page
+                    .service(server::routes::profiles::create_profile) // new profile
+                    .service(server::routes::profiles::update_profile) // profile update
+                    .service(server::routes::profiles::apply_profile) // shared profile link
+                    .service(server::routes::cache_admin::cache_entry) // cached results
+                    .service(server::routes::cache_admin::delete_cache_entries) // cache removal
+                    .service(server::routes::cache_admin::flush_cache) // cache flush
+                    .service(server::routes::cache_admin::cache_stats) // cache statistics
+                    // .service(server::routes::export_import::download) // download page
+                    .default_service(web::route().to(router::not_found)), // error page
             )
-            .service(router::robots_data) // robots.txt
-            .service(router::index) // index page
-            // .service(server::routes::search::search) // search page
-            .service(router::about) // about page
-            .service(router::settings) // settings page
-            // .service(server::routes::export_import::download) // download page
             .default_service(web::route().to(router::not_found)) // error page
     })
-    .workers(config.threads as usize)
+    .workers(startup_config.threads as usize)
     // Set the keep-alive timer for client connections
     .keep_alive(Duration::from_secs(
-        config.client_connection_keep_alive as u64,
+        startup_config.client_connection_keep_alive as u64,
     ))
     // Start server on 127.0.0.1 with the user provided port number. for example 127.0.0.1:8080.
     .listen(listener)?
     .run();
     Ok(server)
 }
+
+/// Runs the admin server which only serves the prometheus metrics on the provided TCP listener
+/// and returns a `Server` instance. It is used when the metrics are configured to be served on a
+/// separate port.
+///
+/// # Arguments
+///
+/// * `listener` - A `TcpListener` instance representing the address and port to listen on.
+///
+/// # Returns
+///
+/// Returns a `Result` containing a `Server` instance on success, or an `std::io::Error` on failure.
+pub fn run_metrics(listener: TcpListener) -> std::io::Result<Server> {
+    let server = HttpServer::new(|| App::new().service(router::metrics))
+        .workers(1)
+        .listen(listener)?
+        .run();
+    Ok(server)
+}
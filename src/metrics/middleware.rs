@@ -0,0 +1,51 @@
+//! This module provides the middleware which records the request count, the latency and the
+//! rate limiter rejections for every route of the `websurfx` website.
+
+use std::time::Instant;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    middleware::Next,
+};
+
+use super::registry::registry;
+
+/// A constant holding the route label used for requests which did not match any registered route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// A middleware function which records the metrics of each request handled by the server. It
+/// should be wrapped outside of the rate limiting middleware so that the requests rejected by it
+/// are recorded as well.
+///
+/// # Arguments
+///
+/// * `req` - It takes the incoming request as an argument.
+/// * `next` - It takes the rest of the middleware chain as an argument.
+///
+/// # Error
+///
+/// Returns the response of the inner service on success otherwise returns the error of the
+/// inner service.
+pub async fn record_http_metrics(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    // The route pattern is used instead of the raw path so that the search queries do not end up
+    // as label values.
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_owned());
+    let started_at = Instant::now();
+
+    let res = next.call(req).await?;
+
+    let status = res.status();
+    registry().observe_http_request(&route, status.as_u16(), started_at.elapsed());
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        registry().record_rate_limited(&route);
+    }
+
+    Ok(res)
+}
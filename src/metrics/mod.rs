@@ -0,0 +1,6 @@
+//! This module provides the modules which handle the collection of runtime metrics (request
+//! counts, latencies, upstream engine health, cache efficiency and rate limiting) and their
+//! exposition in the prometheus text format on the `/metrics` route.
+
+pub mod middleware;
+pub mod registry;
@@ -0,0 +1,335 @@
+//! This module provides the in-process metrics registry which stores all the counters and
+//! histograms collected by the app and renders them in the prometheus text exposition format.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, OnceLock, PoisonError,
+    },
+    time::Duration,
+};
+
+/// A constant holding the upper bounds (in seconds) of the buckets used by all the latency
+/// histograms.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// A static variable which stores the global metrics registry shared by all the actix workers,
+/// the aggregator and the cache.
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// A function which returns the global metrics registry and initializes it on the first call.
+pub fn registry() -> &'static Registry {
+    REGISTRY.get_or_init(Registry::default)
+}
+
+/// A named struct which stores the observations of a single latency histogram.
+#[derive(Default)]
+struct Histogram {
+    /// It stores the number of observations which fell into each bucket (non cumulative).
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    /// It stores the sum of all the observed values in seconds.
+    sum: f64,
+    /// It stores the total number of observations.
+    count: u64,
+}
+
+impl Histogram {
+    /// A function which records a new observation in the histogram.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - It takes the observed duration as an argument.
+    fn observe(&mut self, elapsed: Duration) {
+        let value = elapsed.as_secs_f64();
+        if let Some(idx) = LATENCY_BUCKETS.iter().position(|bound| value <= *bound) {
+            self.buckets[idx] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// A function which writes the histogram in the prometheus text format.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - It takes the output buffer as an argument.
+    /// * `name` - It takes the name of the metric as an argument.
+    /// * `labels` - It takes the already formatted label pairs of the series as an argument.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative: u64 = 0;
+        for (bound, hits) in LATENCY_BUCKETS.iter().zip(self.buckets.iter()) {
+            cumulative += hits;
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+/// A named struct which stores all the metrics collected by the app.
+#[derive(Default)]
+pub struct Registry {
+    /// It stores the number of handled requests grouped by route pattern and status code.
+    http_requests: Mutex<BTreeMap<(String, u16), u64>>,
+    /// It stores the request latencies grouped by route pattern.
+    http_request_duration: Mutex<BTreeMap<String, Histogram>>,
+    /// It stores the number of requests rejected by the rate limiter grouped by route pattern.
+    rate_limited_requests: Mutex<BTreeMap<String, u64>>,
+    /// It stores the upstream request latencies grouped by engine name.
+    engine_request_duration: Mutex<BTreeMap<&'static str, Histogram>>,
+    /// It stores the number of results returned grouped by engine name.
+    engine_results: Mutex<BTreeMap<&'static str, u64>>,
+    /// It stores the number of failed upstream requests grouped by engine name and error kind.
+    engine_errors: Mutex<BTreeMap<(&'static str, String), u64>>,
+    /// It stores the number of search results served from the cache.
+    cache_hits: AtomicU64,
+    /// It stores the number of search results which were missing from the cache.
+    cache_misses: AtomicU64,
+}
+
+impl Registry {
+    /// A function which records a request handled by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - It takes the matched route pattern of the request as an argument.
+    /// * `status` - It takes the status code of the response as an argument.
+    /// * `elapsed` - It takes the time taken to handle the request as an argument.
+    pub fn observe_http_request(&self, route: &str, status: u16, elapsed: Duration) {
+        *lock(&self.http_requests)
+            .entry((route.to_owned(), status))
+            .or_default() += 1;
+        lock(&self.http_request_duration)
+            .entry(route.to_owned())
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// A function which records a request rejected by the rate limiting middleware.
+    ///
+    /// # Arguments
+    ///
+    /// * `route` - It takes the matched route pattern of the request as an argument.
+    pub fn record_rate_limited(&self, route: &str) {
+        *lock(&self.rate_limited_requests)
+            .entry(route.to_owned())
+            .or_default() += 1;
+    }
+
+    /// A function which records the time taken by an upstream engine to provide its results.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - It takes the name of the upstream engine as an argument.
+    /// * `elapsed` - It takes the time taken by the upstream request as an argument.
+    pub fn observe_engine_request(&self, engine: &'static str, elapsed: Duration) {
+        lock(&self.engine_request_duration)
+            .entry(engine)
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// A function which records the number of results provided by an upstream engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - It takes the name of the upstream engine as an argument.
+    /// * `count` - It takes the number of results provided by the engine as an argument.
+    pub fn record_engine_results(&self, engine: &'static str, count: usize) {
+        *lock(&self.engine_results).entry(engine).or_default() += count as u64;
+    }
+
+    /// A function which records a failed request to an upstream engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - It takes the name of the upstream engine as an argument.
+    /// * `error` - It takes the name of the `EngineError` variant as an argument.
+    pub fn record_engine_error(&self, engine: &'static str, error: &str) {
+        *lock(&self.engine_errors)
+            .entry((engine, error.to_owned()))
+            .or_default() += 1;
+    }
+
+    /// A function which records a search results lookup which was served from the cache.
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A function which records a search results lookup which was missing from the cache.
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A function which summarizes the number of requests sent to and the number of errors
+    /// returned by each upstream engine since the server started.
+    ///
+    /// # Returns
+    ///
+    /// It returns a map from the engine name to the number of requests and errors.
+    pub fn engine_summary(&self) -> BTreeMap<&'static str, (u64, u64)> {
+        let mut summary: BTreeMap<&'static str, (u64, u64)> = lock(&self.engine_request_duration)
+            .iter()
+            .map(|(engine, histogram)| (*engine, (histogram.count, 0)))
+            .collect();
+        for ((engine, _), count) in lock(&self.engine_errors).iter() {
+            summary.entry(engine).or_default().1 += count;
+        }
+        summary
+    }
+
+    /// A function which renders all the collected metrics in the prometheus text exposition
+    /// format.
+    ///
+    /// # Returns
+    ///
+    /// It returns the rendered metrics as a string.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str(
+            "# HELP websurfx_http_requests_total Total number of handled HTTP requests.\n",
+        );
+        out.push_str("# TYPE websurfx_http_requests_total counter\n");
+        for ((route, status), count) in lock(&self.http_requests).iter() {
+            let _ = writeln!(
+                out,
+                "websurfx_http_requests_total{{route=\"{}\",status=\"{status}\"}} {count}",
+                escape_label(route)
+            );
+        }
+
+        out.push_str(
+            "# HELP websurfx_http_request_duration_seconds Latency of handled HTTP requests.\n",
+        );
+        out.push_str("# TYPE websurfx_http_request_duration_seconds histogram\n");
+        for (route, histogram) in lock(&self.http_request_duration).iter() {
+            histogram.render(
+                &mut out,
+                "websurfx_http_request_duration_seconds",
+                &format!("route=\"{}\"", escape_label(route)),
+            );
+        }
+
+        out.push_str("# HELP websurfx_rate_limited_requests_total Total number of requests rejected by the rate limiter.\n");
+        out.push_str("# TYPE websurfx_rate_limited_requests_total counter\n");
+        for (route, count) in lock(&self.rate_limited_requests).iter() {
+            let _ = writeln!(
+                out,
+                "websurfx_rate_limited_requests_total{{route=\"{}\"}} {count}",
+                escape_label(route)
+            );
+        }
+
+        out.push_str("# HELP websurfx_engine_request_duration_seconds Latency of the requests sent to the upstream search engines.\n");
+        out.push_str("# TYPE websurfx_engine_request_duration_seconds histogram\n");
+        for (engine, histogram) in lock(&self.engine_request_duration).iter() {
+            histogram.render(
+                &mut out,
+                "websurfx_engine_request_duration_seconds",
+                &format!("engine=\"{engine}\""),
+            );
+        }
+
+        out.push_str("# HELP websurfx_engine_results_total Total number of results provided by the upstream search engines.\n");
+        out.push_str("# TYPE websurfx_engine_results_total counter\n");
+        for (engine, count) in lock(&self.engine_results).iter() {
+            let _ = writeln!(
+                out,
+                "websurfx_engine_results_total{{engine=\"{engine}\"}} {count}"
+            );
+        }
+
+        out.push_str("# HELP websurfx_engine_errors_total Total number of failed requests to the upstream search engines.\n");
+        out.push_str("# TYPE websurfx_engine_errors_total counter\n");
+        for ((engine, error), count) in lock(&self.engine_errors).iter() {
+            let _ = writeln!(
+                out,
+                "websurfx_engine_errors_total{{engine=\"{engine}\",error=\"{}\"}} {count}",
+                escape_label(error)
+            );
+        }
+
+        let (hits, misses) = (
+            self.cache_hits.load(Ordering::Relaxed),
+            self.cache_misses.load(Ordering::Relaxed),
+        );
+        out.push_str("# HELP websurfx_cache_hits_total Total number of search results served from the cache.\n");
+        out.push_str("# TYPE websurfx_cache_hits_total counter\n");
+        let _ = writeln!(out, "websurfx_cache_hits_total {hits}");
+        out.push_str("# HELP websurfx_cache_misses_total Total number of search results missing from the cache.\n");
+        out.push_str("# TYPE websurfx_cache_misses_total counter\n");
+        let _ = writeln!(out, "websurfx_cache_misses_total {misses}");
+        out.push_str("# HELP websurfx_cache_hit_ratio Ratio of cache lookups which were served from the cache.\n");
+        out.push_str("# TYPE websurfx_cache_hit_ratio gauge\n");
+        let _ = writeln!(
+            out,
+            "websurfx_cache_hit_ratio {}",
+            match hits + misses {
+                0 => 0.0,
+                total => hits as f64 / total as f64,
+            }
+        );
+
+        out
+    }
+}
+
+/// A helper function which locks the provided mutex and recovers the guard if the mutex was
+/// poisoned, as a panic while recording a metric can not leave the counters in an invalid state.
+///
+/// # Arguments
+///
+/// * `mutex` - It takes the mutex to be locked as an argument.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A helper function which escapes a label value according to the prometheus text format.
+///
+/// # Arguments
+///
+/// * `value` - It takes the label value to be escaped as an argument.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_cumulative_histogram_and_ratio() {
+        let registry = Registry::default();
+        registry.observe_engine_request("duckduckgo", Duration::from_millis(40));
+        registry.observe_engine_request("duckduckgo", Duration::from_millis(300));
+        registry.record_engine_error("duckduckgo", "RequestError");
+        registry.record_cache_hit();
+        registry.record_cache_miss();
+        registry.record_cache_miss();
+        registry.record_cache_miss();
+
+        let rendered = registry.render();
+
+        assert!(rendered.contains(
+            "websurfx_engine_request_duration_seconds_bucket{engine=\"duckduckgo\",le=\"0.05\"} 1"
+        ));
+        assert!(rendered.contains(
+            "websurfx_engine_request_duration_seconds_bucket{engine=\"duckduckgo\",le=\"0.5\"} 2"
+        ));
+        assert!(rendered
+            .contains("websurfx_engine_request_duration_seconds_count{engine=\"duckduckgo\"} 2"));
+        assert!(rendered.contains(
+            "websurfx_engine_errors_total{engine=\"duckduckgo\",error=\"RequestError\"} 1"
+        ));
+        assert!(rendered.contains("websurfx_cache_hit_ratio 0.25"));
+    }
+}
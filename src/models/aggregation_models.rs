@@ -57,37 +57,29 @@ impl SearchResult {
     ///
 
     pub fn calculate_relevance(&mut self, query: &str) {
-        use stop_words::{get, LANGUAGE};
-        // when language settings can change to any of the ones supported on this crate: https://docs.rs/crate/stop-words/0.8.0
         let documents = [
             self.title.clone(),
             self.url.clone(),
             self.description.clone(),
         ];
 
-        let stop_words = get(LANGUAGE::English);
-        let punctuation = [
-            ".".to_owned(),
-            ",".to_owned(),
-            ":".to_owned(),
-            ";".to_owned(),
-            "!".to_owned(),
-            "?".to_owned(),
-            "(".to_owned(),
-            ")".to_owned(),
-            "[".to_owned(),
-            "]".to_owned(),
-            "{".to_owned(),
-            "}".to_owned(),
-            "\"".to_owned(),
-            "'".to_owned(),
-            "<".to_owned(),
-            ">".to_owned(),
-        ];
-
+        let (stop_words, punctuation) = (english_stop_words(), punctuation());
         self.relevance_score = calculate_tf_idf(query, &documents, &stop_words, &punctuation);
     }
 
+    /// A function which wraps the occurrences of the query tokens in the title and the
+    /// description in `<mark>` tags. It expects the title and the description to be sanitized
+    /// (so that they only hold text, entities and the allowed formatting tags) and only matches
+    /// whole words case insensitively.
+    ///
+    /// # Arguments
+    ///
+    /// * `query_tokens` - It takes the lowercase tokens of the query as an argument.
+    pub fn highlight(&mut self, query_tokens: &[String]) {
+        self.title = highlight_tokens(&self.title, query_tokens);
+        self.description = highlight_tokens(&self.description, query_tokens);
+    }
+
     /// A function which adds the engine name provided as a string into a vector of strings.
     ///
     /// # Arguments
@@ -228,6 +220,102 @@ impl SearchResults {
     pub fn set_no_engines_selected(&mut self) {
         self.no_engines_selected = true;
     }
+
+    /// A function which highlights the words of the provided query in the titles and
+    /// descriptions of all the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - It takes the user's search query as an argument.
+    pub fn highlight(&mut self, query: &str) {
+        let query_tokens = query_tokens(query);
+        self.results
+            .iter_mut()
+            .for_each(|result| result.highlight(&query_tokens));
+    }
+}
+
+/// A helper function which provides the stop words ignored when tokenizing the query and the
+/// results.
+fn english_stop_words() -> Vec<String> {
+    // when language settings can change to any of the ones supported on this crate: https://docs.rs/crate/stop-words/0.8.0
+    stop_words::get(stop_words::LANGUAGE::English)
+}
+
+/// A helper function which provides the punctuation symbols ignored when tokenizing the query
+/// and the results.
+fn punctuation() -> Vec<String> {
+    [
+        ".", ",", ":", ";", "!", "?", "(", ")", "[", "]", "{", "}", "\"", "'", "<", ">",
+    ]
+    .map(str::to_owned)
+    .to_vec()
+}
+
+/// A function which splits the query into the tokens used to score and highlight the results,
+/// with the same tokenizer and stop words as the tf-idf scoring.
+///
+/// # Arguments
+///
+/// * `query` - It takes the user's search query as an argument.
+///
+/// # Returns
+///
+/// It returns the lowercase tokens of the query.
+pub fn query_tokens(query: &str) -> Vec<String> {
+    use keyword_extraction::tokenizer::Tokenizer;
+
+    let (stop_words, punctuation) = (english_stop_words(), punctuation());
+    Tokenizer::new(query, &stop_words, Some(punctuation.as_slice()))
+        .split_into_words()
+        .into_iter()
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// A helper function which wraps the words of the sanitized html matching one of the query
+/// tokens in `<mark>` tags, the tags and entities of the html are left untouched.
+///
+/// # Arguments
+///
+/// * `html` - It takes the sanitized html as an argument.
+/// * `query_tokens` - It takes the lowercase tokens of the query as an argument.
+///
+/// # Returns
+///
+/// It returns the highlighted html.
+fn highlight_tokens(html: &str, query_tokens: &[String]) -> String {
+    if query_tokens.is_empty() {
+        return html.to_owned();
+    }
+
+    let mut highlighted = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        let length = match c {
+            // The tags and entities are copied as is.
+            '<' => rest.find('>').map_or(rest.len(), |end| end + 1),
+            '&' => rest.find(';').map_or(1, |end| end + 1),
+            c if c.is_alphanumeric() => {
+                let length = rest
+                    .find(|c: char| !c.is_alphanumeric())
+                    .unwrap_or(rest.len());
+                let word = &rest[..length];
+                if query_tokens.contains(&word.to_lowercase()) {
+                    highlighted.push_str("<mark>");
+                    highlighted.push_str(word);
+                    highlighted.push_str("</mark>");
+                    rest = &rest[length..];
+                    continue;
+                }
+                length
+            }
+            c => c.len_utf8(),
+        };
+        highlighted.push_str(&rest[..length]);
+        rest = &rest[length..];
+    }
+    highlighted
 }
 
 /// Helper function to calculate the tf-idf for the search query.
@@ -302,3 +390,25 @@ fn calculate_tf_idf(
 
     f32::from(!result.is_nan()) * result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether only the whole words matching the query tokens are
+    /// highlighted while the tags and entities are left untouched.
+    fn test_highlight_tokens() {
+        let query_tokens = query_tokens("The Rust amp");
+        assert!(!query_tokens.contains(&"the".to_owned()));
+
+        assert_eq!(
+            highlight_tokens(
+                "<b>Rust</b> &amp; rustup: the rust toolchain &lt;RUST&gt;",
+                &query_tokens
+            ),
+            "<b><mark>Rust</mark></b> &amp; rustup: the <mark>rust</mark> toolchain \
+             &lt;<mark>RUST</mark>&gt;"
+        );
+    }
+}
@@ -227,6 +227,23 @@ impl EngineHandler {
         })
     }
 
+    /// A function which creates an engine handler from an already built engine, so that the
+    /// engines which are not provided by the server (like the mock engines of the tests) can be
+    /// handled too. Such a handler can not be cloned as the engine is rebuilt from its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - It takes the name of the engine as an argument.
+    /// * `engine` - It takes the engine struct wrapped in a box smart pointer as an argument.
+    pub fn from_engine(name: &'static str, engine: Box<dyn SearchEngine>) -> Self {
+        Self { engine, name }
+    }
+
+    /// A getter function which returns the name of the engine to which the struct is associated.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     /// This function converts the EngineHandler type into a tuple containing the engine name and
     /// the associated engine struct.
     pub fn into_name_engine(self) -> (&'static str, Box<dyn SearchEngine>) {
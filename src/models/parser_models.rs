@@ -1,6 +1,8 @@
 //! This module provides public models for handling, storing and serializing parsed config file
 //! options from config.lua by grouping them together.
 
+use std::{collections::HashMap, fmt, net::IpAddr, str::FromStr};
+
 /// A named struct which stores,deserializes, serializes and groups the parsed config file options
 /// of theme and colorscheme names into the Style struct which derives the `Clone`, `Serialize`
 /// and Deserialize traits where the `Clone` trait is derived for allowing the struct to be
@@ -10,7 +12,7 @@
 /// order to allow the deserializing the json back to struct in aggregate function in
 /// aggregator.rs and create a new struct out of it and then serialize it back to json and pass
 /// it to the template files.
-#[derive(Default, Clone)]
+#[derive(Default, Clone, PartialEq)]
 pub struct Style {
     /// It stores the parsed theme option used to set a theme for the website.
     pub theme: String,
@@ -40,16 +42,278 @@ impl Style {
 }
 
 /// Configuration options for the aggregator.
+#[derive(Clone, PartialEq)]
 pub struct AggregatorConfig {
     /// It stores the option to whether enable or disable random delays between
     /// requests.
     pub random_delay: bool,
 }
 
-/// Configuration options for the rate limiter middleware.
-pub struct RateLimiter {
+/// A named struct which stores a quota of requests of the rate limiter.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RateLimit {
     /// The number of request that are allowed within a provided time limit.
     pub number_of_requests: u8,
     /// The time limit in which the quantity of requests that should be accepted.
     pub time_limit: u8,
 }
+
+/// Configuration options for the rate limiter middleware.
+#[derive(Clone, PartialEq)]
+pub struct RateLimiter {
+    /// The quota of the anonymous clients on the pages and on the other routes which do not have
+    /// their own quota.
+    pub default: RateLimit,
+    /// The quota of the anonymous clients on the search route.
+    pub search: RateLimit,
+    /// The quota of the anonymous clients on the static files (css, js and images).
+    pub static_files: RateLimit,
+    /// The quotas of the API keys, the requests made with an API key are only counted against
+    /// the quota of the key.
+    pub api_keys: HashMap<String, RateLimit>,
+}
+
+/// Configuration options for the prometheus metrics endpoint.
+#[derive(Clone, PartialEq)]
+pub struct MetricsConfig {
+    /// It stores the option to whether enable or disable the `/metrics` route.
+    pub enabled: bool,
+    /// It stores the port of the separate admin server on which the metrics should be served. The
+    /// metrics are served on the main server when no port is provided.
+    pub port: Option<u16>,
+}
+
+/// Configuration options for the server-side preference profiles.
+#[derive(Clone, PartialEq, Default)]
+pub struct ProfilesConfig {
+    /// It stores the option to whether enable or disable the profiles.
+    pub enabled: bool,
+    /// It stores the path of the SQLite file in which the profiles should be stored, they are
+    /// stored in the cache when no path is provided.
+    pub sqlite_path: Option<String>,
+}
+
+/// Configuration options for the cache administration routes.
+#[derive(Clone, PartialEq, Default)]
+pub struct CacheAdminConfig {
+    /// It stores the option to whether enable or disable the cache administration routes.
+    pub enabled: bool,
+    /// It stores the token which has to be provided as a bearer token in the `Authorization`
+    /// header of the requests to the cache administration routes.
+    pub token: Option<String>,
+}
+
+/// Configuration options for the disk cache.
+#[cfg(feature = "disk-cache")]
+#[derive(Clone, PartialEq, Debug)]
+pub struct DiskCacheConfig {
+    /// It stores the path of the SQLite file in which the search results should be cached.
+    pub path: String,
+    /// It stores the maximum size of the cached results in megabytes, the least recently used
+    /// results are evicted once it is exceeded.
+    pub max_size: u64,
+    /// It stores the time between two compactions of the SQLite file in seconds.
+    pub compaction_interval: u64,
+}
+
+#[cfg(feature = "disk-cache")]
+impl Default for DiskCacheConfig {
+    fn default() -> Self {
+        DiskCacheConfig {
+            path: "websurfx-cache.sqlite".to_owned(),
+            max_size: 512,
+            compaction_interval: 300,
+        }
+    }
+}
+
+/// Configuration options for the encryption of the cached results. It does not implement `Debug`
+/// so that the keys are never logged.
+#[cfg(any(feature = "encrypt-cache-results", feature = "cec-cache-results"))]
+#[derive(Clone, PartialEq, Default)]
+pub struct CacheEncryptionConfig {
+    /// It stores the keys from the newest to the oldest, the newest key encrypts the results
+    /// while all of them decrypt them.
+    pub keys: Vec<[u8; 32]>,
+}
+
+/// An enum type which provides the different deployments of the redis servers.
+#[cfg(feature = "redis-cache")]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum RedisMode {
+    /// A single redis server reached through the `redis_url` option.
+    #[default]
+    Standalone,
+    /// A redis server monitored by the sentinels, which provide the address of the current
+    /// master.
+    Sentinel,
+    /// A redis cluster sharding the keys over its nodes.
+    Cluster,
+}
+
+/// Configuration options for the redis cache. It does not implement `Debug` so that the password
+/// is never logged.
+#[cfg(feature = "redis-cache")]
+#[derive(Clone, PartialEq)]
+pub struct RedisConfig {
+    /// It stores the deployment of the redis servers.
+    pub mode: RedisMode,
+    /// It stores the urls of the sentinels or of the cluster nodes, the `redis_url` option is
+    /// used when it is empty.
+    pub nodes: Vec<String>,
+    /// It stores the name of the master monitored by the sentinels.
+    pub service_name: String,
+    /// It stores the ACL username used to authenticate with the redis servers.
+    pub username: Option<String>,
+    /// It stores the password used to authenticate with the redis servers.
+    pub password: Option<String>,
+    /// It stores the number of connections kept in the pool.
+    pub pool_size: u8,
+    /// It stores the prefix added to the keys stored in redis, so that several instances can
+    /// share the same redis servers.
+    pub key_prefix: String,
+}
+
+#[cfg(feature = "redis-cache")]
+impl Default for RedisConfig {
+    fn default() -> Self {
+        RedisConfig {
+            mode: RedisMode::default(),
+            nodes: Vec::new(),
+            service_name: "mymaster".to_owned(),
+            username: None,
+            password: None,
+            pool_size: 5,
+            key_prefix: "default:".to_owned(),
+        }
+    }
+}
+
+/// Configuration options for the security headers middleware. The empty strings disable the
+/// corresponding header.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SecurityHeaders {
+    /// It stores the `Content-Security-Policy` header sent with every response.
+    pub content_security_policy: String,
+    /// It stores the sources allowed to embed the pages in a frame, which is appended to the
+    /// content security policy as the `frame-ancestors` directive.
+    pub frame_ancestors: String,
+    /// It stores the `Referrer-Policy` header sent with every response.
+    pub referrer_policy: String,
+    /// It stores the `Permissions-Policy` header sent with every response.
+    pub permissions_policy: String,
+    /// It stores whether the `X-Content-Type-Options: nosniff` header should be sent.
+    pub content_type_nosniff: bool,
+    /// It stores the number of seconds for which the browsers should only connect over https
+    /// (the `Strict-Transport-Security` header), it is not sent when no value is provided.
+    pub hsts_max_age: Option<u32>,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        SecurityHeaders {
+            content_security_policy: "default-src 'self'; img-src 'self' data:; \
+                object-src 'none'; base-uri 'self'; form-action 'self'"
+                .to_owned(),
+            frame_ancestors: "'none'".to_owned(),
+            referrer_policy: "no-referrer".to_owned(),
+            permissions_policy: "camera=(), geolocation=(), microphone=(), payment=(), usb=()"
+                .to_owned(),
+            content_type_nosniff: true,
+            hsts_max_age: None,
+        }
+    }
+}
+
+/// A named struct which stores a range of IP addresses (like `10.0.0.0/8`) parsed from the
+/// `trusted_proxies` config option, a single address is stored as a range holding only itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IpNetwork {
+    /// It stores the first address of the range.
+    address: IpAddr,
+    /// It stores the number of leading bits shared by all the addresses of the range.
+    prefix_length: u8,
+}
+
+impl IpNetwork {
+    /// A function which checks whether the provided address is part of the range, the IPv4
+    /// addresses mapped into IPv6 (like `::ffff:127.0.0.1`) are matched as IPv4 addresses.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - It takes the address to check as an argument.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_length) = match network.trim().split_once('/') {
+            Some((address, prefix_length)) => (address, Some(prefix_length)),
+            None => (network.trim(), None),
+        };
+        let address = address
+            .parse::<IpAddr>()
+            .map_err(|_| format!("`{network}` is not a valid IP address or range"))?
+            .to_canonical();
+        let max_length = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix_length = match prefix_length {
+            Some(prefix_length) => prefix_length
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix_length| *prefix_length <= max_length)
+                .ok_or_else(|| format!("`{network}` has an invalid prefix length"))?,
+            None => max_length,
+        };
+        Ok(Self {
+            address,
+            prefix_length,
+        })
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_length)
+    }
+}
+
+/// An enum type which provides the different output formats of the logs.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum LogFormat {
+    /// Human readable log lines.
+    #[default]
+    Text,
+    /// One JSON object per log line including the fields of the active spans (like the request
+    /// id), which can be ingested by log aggregators.
+    Json,
+}
+
+/// A named struct which stores the config options overridden on the command line.
+#[derive(Default)]
+pub struct ConfigOverrides {
+    /// It stores the port number on which the server should launch.
+    pub port: Option<u16>,
+    /// It stores the ip address on which the server should launch.
+    pub binding_ip: Option<String>,
+}
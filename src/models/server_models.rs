@@ -1,10 +1,11 @@
 //! This module provides the models to parse cookies and search parameters from the search
 //! engine website.
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap};
 
 use serde::{Deserialize, Serialize};
 
 use super::parser_models::Style;
+use crate::templates::i18n;
 
 /// A named struct which deserializes all the user provided search parameters and stores them.
 #[derive(Deserialize)]
@@ -18,6 +19,34 @@ pub struct SearchParams {
     /// It stores the search parameter `safesearch` (or safe search level in simple words) of the
     /// search url.
     pub safesearch: Option<u8>,
+    /// It stores the search parameter `engines` (a comma separated list of the upstream search
+    /// engines to use) of the search url.
+    pub engines: Option<String>,
+    /// It stores the search parameter `theme` of the search url.
+    pub theme: Option<String>,
+    /// It stores the search parameter `colorscheme` of the search url.
+    pub colorscheme: Option<String>,
+    /// It stores the search parameter `animation` of the search url, an empty value disables the
+    /// animation.
+    pub animation: Option<String>,
+    /// It stores the search parameter `highlight_query` of the search url.
+    pub highlight_query: Option<bool>,
+    /// It stores the search parameter `language` (the code of the locale of the pages) of the
+    /// search url.
+    pub language: Option<String>,
+}
+
+/// A helper function which checks whether the provided theme, colorscheme or animation name is
+/// made of the characters used by the names of the style files.
+///
+/// # Arguments
+///
+/// * `name` - It takes the name of the style as an argument.
+fn is_style_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
 }
 
 /// A named struct which is used to deserialize the cookies fetched from the client side.
@@ -38,6 +67,19 @@ pub struct Cookie<'a> {
     #[serde(borrow)]
     /// It stores the animation name used for the website theme.
     pub animation: Option<Cow<'a, str>>,
+    /// It stores the option to whether highlight the words of the query in the results or not,
+    /// it is enabled when it is missing from the cookies saved by the older versions.
+    #[serde(default = "highlight_query_default")]
+    pub highlight_query: bool,
+    /// It stores the code of the locale of the pages selected from the UI, the languages
+    /// accepted by the browser are used when it is missing.
+    #[serde(borrow, default)]
+    pub language: Option<Cow<'a, str>>,
+}
+
+/// A helper function which provides the default value of the `highlight_query` option.
+fn highlight_query_default() -> bool {
+    true
 }
 
 impl<'a> Cookie<'a> {
@@ -53,6 +95,204 @@ impl<'a> Cookie<'a> {
                 .animation
                 .as_ref()
                 .map(|str| Cow::Borrowed(str.as_str())),
+            highlight_query: true,
+            language: None,
+        }
+    }
+
+    /// A function which overrides the preferences with the ones passed as url parameters, so
+    /// that the clients which do not keep cookies (like terminal browsers) can still use them.
+    /// The invalid values are ignored and the unknown engines are left out of the engines list.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - It takes the parameters of the url as an argument.
+    /// * `upstream_search_engines` - It takes the upstream search engines provided by the server
+    ///   as an argument.
+    ///
+    /// # Returns
+    ///
+    /// It returns the preferences which were passed as url parameters (as url parameters) so
+    /// that they can be carried over to the links and forms of the page.
+    pub fn apply_url_settings(
+        &mut self,
+        params: &SearchParams,
+        upstream_search_engines: &'a HashMap<String, bool>,
+    ) -> Vec<(&'static str, String)> {
+        let mut url_settings = Vec::new();
+
+        if let Some(engines) = &params.engines {
+            let mut selected: Vec<Cow<'a, str>> = engines
+                .split(',')
+                .filter_map(|engine| {
+                    upstream_search_engines
+                        .keys()
+                        .find(|name| name.eq_ignore_ascii_case(engine.trim()))
+                        .map(|name| Cow::Borrowed(name.as_str()))
+                })
+                .collect();
+            selected.sort();
+            selected.dedup();
+            url_settings.push(("engines", selected.join(",")));
+            self.engines = Cow::Owned(selected);
+        }
+
+        for (name, value, setting) in [
+            ("theme", &params.theme, &mut self.theme),
+            ("colorscheme", &params.colorscheme, &mut self.colorscheme),
+        ] {
+            if let Some(value) = value.as_deref().filter(|value| is_style_name(value)) {
+                url_settings.push((name, value.to_owned()));
+                *setting = Cow::Owned(value.to_owned());
+            }
         }
+
+        match params.animation.as_deref() {
+            Some("") => {
+                url_settings.push(("animation", String::new()));
+                self.animation = None;
+            }
+            Some(animation) if is_style_name(animation) => {
+                url_settings.push(("animation", animation.to_owned()));
+                self.animation = Some(Cow::Owned(animation.to_owned()));
+            }
+            _ => (),
+        }
+
+        if let Some(highlight_query) = params.highlight_query {
+            url_settings.push(("highlight_query", highlight_query.to_string()));
+            self.highlight_query = highlight_query;
+        }
+
+        if let Some(locale) = params.language.as_deref().and_then(i18n::locale) {
+            url_settings.push(("language", locale.code().to_owned()));
+            self.language = Some(Cow::Borrowed(locale.code()));
+        }
+
+        url_settings
+    }
+}
+
+/// A named struct which deserializes the user provided parameters of the readiness route.
+#[derive(Deserialize)]
+pub struct ReadinessParams {
+    /// It stores the option to whether include the health summary of the upstream search
+    /// engines in the report or not.
+    pub engines: Option<bool>,
+}
+
+/// A named struct which deserializes the user provided parameters of the cache administration
+/// routes, which identify the cached results either by their `key` (the formatted `CacheKey`) or
+/// by the parameters of the search and the upstream search engine.
+#[derive(Deserialize)]
+pub struct CacheEntryParams {
+    /// It stores the formatted key of the cached results.
+    pub key: Option<String>,
+    /// It stores the prefix of the formatted keys of the cached results.
+    pub prefix: Option<String>,
+    /// It stores the search query of the cached results.
+    pub q: Option<String>,
+    /// It stores the page of the cached results (starting from 1).
+    pub page: Option<u32>,
+    /// It stores the safe search level of the cached results.
+    pub safesearch: Option<u8>,
+    /// It stores the code of the locale of the pages of the cached results.
+    pub language: Option<String>,
+    /// It stores the name of the upstream search engine of the cached results.
+    pub engine: Option<String>,
+}
+
+/// An enum type which provides the different health states of a component of the app.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The component is working as expected.
+    Ok,
+    /// The component works but with a reduced functionality.
+    Degraded,
+    /// The component can not be used.
+    Unavailable,
+}
+
+/// A named struct which stores the health of a single component of the app.
+#[derive(Serialize)]
+pub struct ComponentHealth {
+    /// It stores the health state of the component.
+    pub status: HealthStatus,
+    /// It stores the details explaining the health state of the component.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ComponentHealth {
+    /// Constructs a new `ComponentHealth` with the given arguments needed for the struct.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - It takes the health state of the component.
+    /// * `detail` - It takes the optional details explaining the health state.
+    pub fn new(status: HealthStatus, detail: Option<String>) -> Self {
+        Self { status, detail }
+    }
+}
+
+/// A named struct which is used to serialize the readiness report of the app.
+#[derive(Serialize)]
+pub struct Readiness {
+    /// It stores the overall health state of the app.
+    pub status: HealthStatus,
+    /// It stores the health of the components which are required to serve search requests.
+    pub components: std::collections::BTreeMap<&'static str, ComponentHealth>,
+    /// It stores the health summary of the upstream search engines if it was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engines: Option<std::collections::BTreeMap<String, ComponentHealth>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the preferences passed as url parameters override the
+    /// cookie and whether the invalid values are ignored.
+    fn test_apply_url_settings() {
+        let style = Style {
+            theme: "simple".to_owned(),
+            colorscheme: "catppuccin-mocha".to_owned(),
+            animation: Some("simple-frosted-glow".to_owned()),
+        };
+        let upstream_search_engines =
+            HashMap::from([("DuckDuckGo".to_owned(), true), ("Brave".to_owned(), false)]);
+        let mut cookie = Cookie::build(&style, vec![Cow::Borrowed("DuckDuckGo")], 2);
+        let params = SearchParams {
+            q: None,
+            page: None,
+            safesearch: None,
+            engines: Some("brave,unknown,duckduckgo".to_owned()),
+            theme: Some("../../etc/passwd".to_owned()),
+            colorscheme: Some("dracula".to_owned()),
+            animation: Some(String::new()),
+            highlight_query: Some(false),
+            language: Some("ar-EG".to_owned()),
+        };
+
+        let url_settings = cookie.apply_url_settings(&params, &upstream_search_engines);
+
+        assert_eq!(cookie.engines.as_ref(), ["Brave", "DuckDuckGo"]);
+        assert_eq!(cookie.theme, "simple");
+        assert_eq!(cookie.colorscheme, "dracula");
+        assert_eq!(cookie.animation, None);
+        assert!(!cookie.highlight_query);
+        assert_eq!(cookie.language.as_deref(), Some("ar"));
+        assert_eq!(
+            url_settings,
+            [
+                ("engines", "Brave,DuckDuckGo".to_owned()),
+                ("colorscheme", "dracula".to_owned()),
+                ("animation", String::new()),
+                ("highlight_query", "false".to_owned()),
+                ("language", "ar".to_owned()),
+            ]
+        );
     }
 }
@@ -1,9 +1,10 @@
 //! This module provides the functionality to scrape and gathers all the results from the upstream
 //! search engines and then removes duplicate results.
 
-use super::user_agent::random_user_agent;
+use super::{sanitizer::sanitize_html, user_agent::random_user_agent};
 use crate::config::parser::Config;
 use crate::handler::{file_path, FileType};
+use crate::metrics::registry::registry;
 use crate::models::{
     aggregation_models::{EngineErrorInfo, SearchResult, SearchResults},
     engine_models::{EngineError, EngineHandler},
@@ -14,11 +15,12 @@ use futures::stream::FuturesUnordered;
 use regex::Regex;
 use reqwest::{Client, ClientBuilder};
 use std::sync::Arc;
+use tracing::Instrument;
 use tokio::{
     fs::File,
     io::{AsyncBufReadExt, BufReader},
     task::JoinHandle,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use mongodb::{options::ClientOptions, Client as MongoClient};
 use crate::results::user_agent::batch_surreal_queries;
@@ -28,8 +30,11 @@ static CLIENT: std::sync::OnceLock<Client> = std::sync::OnceLock::new();
 
 /// Aliases for long type annotations
 
-type FutureVec =
-    FuturesUnordered<JoinHandle<Result<Vec<(String, SearchResult)>, Report<EngineError>>>>;
+type FutureVec = FuturesUnordered<JoinHandle<(&'static str, EngineResults)>>;
+
+/// Aliases for the results of an upstream search engine keyed by their url or the info of the
+/// error which occurred.
+pub type EngineResults = Result<Vec<(String, SearchResult)>, EngineErrorInfo>;
 
 /// The function aggregates the scraped results from the user-selected upstream search engines.
 /// These engines can be chosen either from the user interface (UI) or from the configuration file.
@@ -107,9 +112,54 @@ pub async fn aggregate(
         }
     }
 
-    let client = CLIENT.get_or_init(|| {
+    let client = client(config);
+    let user_agent: &str = random_user_agent();
+    let request_timeout = Duration::from_secs(config.request_timeout as u64);
+
+    // create tasks for upstream result fetching
+    let tasks: FutureVec = FutureVec::new();
+
+    let query: Arc<String> = Arc::new(query.to_string());
+    for engine_handler in upstream_search_engines {
+        let engine_handler = engine_handler.clone();
+        let query_partially_cloned = query.clone();
+        tasks.push(tokio::spawn(async move {
+            search_engine_results(
+                engine_handler,
+                &query_partially_cloned,
+                page,
+                user_agent,
+                client,
+                request_timeout,
+                safe_search,
+            )
+            .await
+        }));
+    }
+
+    // get upstream responses
+    let mut responses = Vec::with_capacity(tasks.len());
+
+    for task in tasks {
+        if let Ok(response) = task.await {
+            responses.push(response)
+        }
+    }
+
+    merge_results(&query, safe_search, responses).await
+}
+
+/// A helper function which provides the prebuilt client used to request the upstream search
+/// engines, it is built from the provided config on the first call.
+///
+/// # Arguments
+///
+/// * `config` - It takes the parsed config as an argument.
+fn client(config: &Config) -> &'static Client {
+    CLIENT.get_or_init(|| {
+        // The overall request timeout is applied on each engine task below instead of on the
+        // client as it can be changed by reloading the config.
         let mut cb = ClientBuilder::new()
-            .timeout(Duration::from_secs(config.request_timeout as u64)) // Add timeout to request to avoid DDOSing the server
             .pool_idle_timeout(Duration::from_secs(
                 config.pool_idle_connection_timeout as u64,
             ))
@@ -128,62 +178,133 @@ pub async fn aggregate(
         }
 
         cb.build().unwrap()
-    });
-
-    let user_agent: &str = random_user_agent();
-
-    let mut names: Vec<&str> = Vec::with_capacity(0);
-
-    // create tasks for upstream result fetching
-    let tasks: FutureVec = FutureVec::new();
-
-    let query: Arc<String> = Arc::new(query.to_string());
-    for engine_handler in upstream_search_engines {
-        let (name, search_engine) = engine_handler.clone().into_name_engine();
-        names.push(name);
-        let query_partially_cloned = query.clone();
-        tasks.push(tokio::spawn(async move {
-            search_engine
-                .results(
-                    &query_partially_cloned,
-                    page,
-                    user_agent,
-                    client,
-                    safe_search,
-                )
-                .await
-        }));
-    }
+    })
+}
 
-    // get upstream responses
-    let mut responses = Vec::with_capacity(tasks.len());
+/// The function fetches the raw results of a single upstream search engine, so that they can be
+/// cached per engine and merged with the results of the other engines with `merge_results`.
+///
+/// # Arguments
+///
+/// * `query` - Accepts a string to query with the upstream search engine.
+/// * `page` - Accepts an u32 page number.
+/// * `config` - Accepts the parsed config.
+/// * `engine_handler` - Accepts the handler of the upstream search engine to request.
+/// * `safe_search` - Accepts the safe search level.
+///
+/// # Error
+///
+/// Returns the results of the upstream search engine keyed by their url otherwise returns the
+/// info of the error which occurred.
+pub async fn engine_results(
+    query: &str,
+    page: u32,
+    config: &Config,
+    engine_handler: EngineHandler,
+    safe_search: u8,
+) -> EngineResults {
+    search_engine_results(
+        engine_handler,
+        query,
+        page,
+        random_user_agent(),
+        client(config),
+        Duration::from_secs(config.request_timeout as u64),
+        safe_search,
+    )
+    .await
+    .1
+}
 
-    for task in tasks {
-        if let Ok(result) = task.await {
-            responses.push(result)
-        }
+/// A helper function which requests the upstream search engine within the request timeout,
+/// sanitizes the scraped results and records the metrics of the request.
+///
+/// # Arguments
+///
+/// * `engine_handler` - It takes the handler of the upstream search engine as an argument.
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number as an argument.
+/// * `user_agent` - It takes the user agent to send the request with as an argument.
+/// * `client` - It takes the client to send the request with as an argument.
+/// * `request_timeout` - It takes the time after which the request is abandoned as an argument.
+/// * `safe_search` - It takes the safe search level as an argument.
+///
+/// # Returns
+///
+/// It returns the name of the upstream search engine with either its results keyed by their url
+/// or the info of the error which occurred.
+async fn search_engine_results(
+    engine_handler: EngineHandler,
+    query: &str,
+    page: u32,
+    user_agent: &str,
+    client: &Client,
+    request_timeout: Duration,
+    safe_search: u8,
+) -> (&'static str, EngineResults) {
+    let (name, search_engine) = engine_handler.into_name_engine();
+    // The engine requests are instrumented with a child span of the current (request) span as
+    // spans are not propagated to the spawned tasks automatically.
+    let engine_span = tracing::info_span!("engine", engine = name);
+    async move {
+        let started_at = Instant::now();
+        let results = tokio::time::timeout(
+            request_timeout,
+            search_engine.results(query, page, user_agent, client, safe_search),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(Report::new(EngineError::RequestError)
+                .attach_printable("the upstream search engine timed out"))
+        });
+        registry().observe_engine_request(name, started_at.elapsed());
+        let results = match results {
+            Ok(results) => sanitize_results(results),
+            Err(error) => {
+                log::error!("Engine Error: {:?}", error);
+                let error_info =
+                    EngineErrorInfo::new(error.downcast_ref::<EngineError>().unwrap(), name);
+                registry().record_engine_error(name, &error_info.error);
+                return (name, Err(error_info));
+            }
+        };
+        registry().record_engine_results(name, results.len());
+        (name, Ok(results))
     }
+    .instrument(engine_span)
+    .await
+}
 
+/// The function merges the results fetched from the upstream search engines. The duplicate
+/// results are combined, the errors are collected to be shown in the UI and the results are
+/// filtered with the block and allow lists (if the safe search level is 3 or above) before being
+/// sorted by their relevance to the query.
+///
+/// # Arguments
+///
+/// * `query` - Accepts the search query the results were fetched for.
+/// * `safe_search` - Accepts the safe search level.
+/// * `responses` - Accepts the names of the upstream search engines with either their results or
+///   the info of the error which occurred.
+///
+/// # Error
+///
+/// Returns an error if the block or allow lists could not be read otherwise returns a
+/// `SearchResults struct` containing the merged results.
+pub async fn merge_results(
+    query: &str,
+    safe_search: u8,
+    responses: Vec<(&str, EngineResults)>,
+) -> Result<SearchResults, Box<dyn std::error::Error>> {
     // aggregate search results, removing duplicates and handling errors the upstream engines returned
     let mut result_map: Vec<(String, SearchResult)> = Vec::new();
     let mut engine_errors_info: Vec<EngineErrorInfo> = Vec::new();
 
-    let mut handle_error = |error: &Report<EngineError>, engine_name: &'static str| {
-        log::error!("Engine Error: {:?}", error);
-        engine_errors_info.push(EngineErrorInfo::new(
-            error.downcast_ref::<EngineError>().unwrap(),
-            engine_name,
-        ));
-    };
-
-    for _ in 0..responses.len() {
-        let response = responses.pop().unwrap();
-        let engine = names.pop().unwrap();
-
+    for (engine, response) in responses {
         if result_map.is_empty() {
             match response {
                 Ok(results) => result_map = results,
-                Err(error) => handle_error(&error, engine),
+                Err(error_info) => engine_errors_info.push(error_info),
             };
             continue;
         }
@@ -197,7 +318,7 @@ pub async fn aggregate(
                     };
                 });
             }
-            Err(error) => handle_error(&error, engine),
+            Err(error_info) => engine_errors_info.push(error_info),
         };
     }
 
@@ -224,7 +345,7 @@ pub async fn aggregate(
         .into_iter()
         .map(|(_, mut value)| {
             if !value.url.contains("temu.com") {
-                value.calculate_relevance(query.as_str())
+                value.calculate_relevance(query)
             }
             value
         })
@@ -282,6 +403,27 @@ pub async fn filter_with_lists(
     Ok(())
 }
 
+/// A helper function which sanitizes the titles and descriptions of the results scraped from an
+/// upstream search engine, as they are rendered as html in the search page.
+///
+/// # Arguments
+///
+/// * `results` - It takes the results of the upstream search engine as an argument.
+///
+/// # Returns
+///
+/// It returns the results with the markup of their titles and descriptions sanitized.
+pub fn sanitize_results(results: Vec<(String, SearchResult)>) -> Vec<(String, SearchResult)> {
+    results
+        .into_iter()
+        .map(|(url, mut result)| {
+            result.title = sanitize_html(&result.title);
+            result.description = sanitize_html(&result.description);
+            (url, result)
+        })
+        .collect()
+}
+
 /// Sorts  SearchResults by relevance score.
 /// <br> sort_unstable is used as its faster,stability is not an issue on our side.
 /// For reasons why, check out [`this`](https://rust-lang.github.io/rfcs/1884-unstable-sort.html)
@@ -0,0 +1,143 @@
+//! This module provides the functionality to coalesce the identical concurrent searches, so that
+//! the searches for the same query (identified by their cache key) await a single aggregation of
+//! the results from the upstream search engines instead of requesting them once per search.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use tokio::sync::OnceCell;
+
+/// A named struct which stores the fetches in flight by their key, the callers with the same key
+/// await the same fetch (single-flight).
+pub struct SingleFlight<T> {
+    /// It stores the cells receiving the values of the fetches in flight by their key.
+    in_flight: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    /// A function which creates a new `SingleFlight` without any fetch in flight.
+    pub fn new() -> Self {
+        SingleFlight {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A function which runs the provided fetch unless a fetch with the same key is already in
+    /// flight, in which case its value is awaited instead. If the caller running the fetch is
+    /// cancelled then one of the waiting callers runs its own fetch.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the key identifying the fetch (like the cache key) as an argument.
+    /// * `fetch` - It takes the closure starting the fetch as an argument.
+    ///
+    /// # Returns
+    ///
+    /// It returns the value of the fetch shared by all the callers with the same key.
+    pub async fn run<F, Fut>(&self, key: &str, fetch: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let cell = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key.to_owned())
+            .or_default()
+            .clone();
+
+        let value = cell.get_or_init(fetch).await.clone();
+
+        // The fetch is done so it is removed (unless a new one has already replaced it), so that
+        // the next callers fetch a new value.
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if in_flight
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            in_flight.remove(key);
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use error_stack::Result;
+    use futures::future::join_all;
+    use reqwest::Client;
+
+    use super::*;
+    use crate::models::{
+        aggregation_models::SearchResult,
+        engine_models::{EngineError, SearchEngine},
+    };
+
+    /// A mock upstream search engine which counts the number of requests made to it.
+    struct MockEngine {
+        /// It stores the number of requests made to the engine.
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchEngine for MockEngine {
+        async fn results(
+            &self,
+            query: &str,
+            _page: u32,
+            _user_agent: &str,
+            _client: &Client,
+            _safe_search: u8,
+        ) -> Result<Vec<(String, SearchResult)>, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let url = format!("https://www.example.com/{query}");
+            let result = SearchResult::new(query, &url, "A mock result", &["mock"]);
+            Ok(vec![(url, result)])
+        }
+    }
+
+    #[tokio::test]
+    /// A test function to test whether the identical concurrent searches make a single request
+    /// to the upstream search engine while the different ones are not coalesced.
+    async fn test_single_flight_coalesces_identical_searches() {
+        let engine = MockEngine {
+            calls: AtomicUsize::new(0),
+        };
+        let client = Client::new();
+        let searches: SingleFlight<Option<Vec<(String, SearchResult)>>> = SingleFlight::new();
+        let search = |query: &'static str| {
+            searches.run(query, || async {
+                engine.results(query, 0, "", &client, 0).await.ok()
+            })
+        };
+
+        let results = join_all((0..10).map(|_| search("rust"))).await;
+        assert_eq!(engine.calls.load(Ordering::SeqCst), 1);
+        assert!(results
+            .iter()
+            .all(|result| result.as_ref().is_some_and(|result| result.len() == 1)));
+
+        join_all([search("rust"), search("websurfx")]).await;
+        assert_eq!(engine.calls.load(Ordering::SeqCst), 3);
+        assert!(searches.in_flight.lock().unwrap().is_empty());
+    }
+}
@@ -3,4 +3,6 @@
 //! provides various models to aggregate search results into a standardized form.
 
 pub mod aggregator;
+pub mod coalescing;
+pub mod sanitizer;
 mod user_agent;
@@ -0,0 +1,306 @@
+//! This module provides the functionality to sanitize the titles and descriptions scraped from
+//! the upstream search engines, which are rendered as html on the search page. Only an allowlist
+//! of inline formatting tags is kept (without their attributes), the entities are rewritten and
+//! all the other markup is stripped so that a malicious upstream cannot inject scripts.
+
+use std::fmt::Write;
+
+/// A constant holding the names of the tags which are kept (without their attributes).
+const ALLOWED_TAGS: [&str; 3] = ["b", "em", "strong"];
+
+/// A constant holding the names of the tags which are stripped along with their content, as
+/// their content is not text meant to be displayed.
+const DROPPED_CONTENT_TAGS: [&str; 9] = [
+    "script", "style", "iframe", "noscript", "noembed", "template", "textarea", "title", "xmp",
+];
+
+/// A named struct which stores a tag parsed from the html.
+struct Tag {
+    /// It stores the lowercase name of the tag.
+    name: String,
+    /// It stores whether it is a closing tag (like `</b>`).
+    closing: bool,
+    /// It stores the byte index following the end of the tag.
+    end: usize,
+}
+
+/// A helper function which parses the tag starting at the provided position.
+///
+/// # Arguments
+///
+/// * `html` - It takes the html being sanitized as an argument.
+/// * `start` - It takes the byte index of the `<` opening the tag as an argument.
+///
+/// # Returns
+///
+/// It returns the parsed tag or `None` if the `<` does not open a tag (like in `a < b`) or the
+/// tag is never closed, in which case the `<` is text.
+fn parse_tag(html: &str, start: usize) -> Option<Tag> {
+    let rest = &html[start + 1..];
+    let closing = rest.starts_with('/');
+    let name_start = usize::from(closing);
+    let name: String = rest[name_start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect();
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    // The `>` inside the quoted attribute values does not close the tag.
+    let mut quote = None;
+    for (offset, c) in rest.char_indices().skip(name_start + name.len()) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if open == c => quote = None,
+            (None, '>') => {
+                return Some(Tag {
+                    name: name.to_ascii_lowercase(),
+                    closing,
+                    end: start + 1 + offset + 1,
+                })
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// A helper function which decodes the entity following a `&`.
+///
+/// # Arguments
+///
+/// * `html` - It takes the html following the `&` as an argument.
+///
+/// # Returns
+///
+/// It returns the decoded character along with the length of the entity (including the `;`) or
+/// `None` if it is not a known entity, in which case the `&` is text.
+fn decode_entity(html: &str) -> Option<(char, usize)> {
+    let end = html.find(';').filter(|end| *end <= 8)?;
+    let entity = &html[..end];
+    let decoded = match entity.strip_prefix('#') {
+        Some(number) => {
+            let code = match number.strip_prefix(&['x', 'X'][..]) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse::<u32>().ok()?,
+            };
+            char::from_u32(code)
+                .filter(|c| *c != '\0')
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+        }
+        None => match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" => '\u{a0}',
+            _ => return None,
+        },
+    };
+    Some((decoded, end + 1))
+}
+
+/// A helper function which appends the provided text character to the sanitized html, escaping
+/// it when it has a special meaning in html.
+///
+/// # Arguments
+///
+/// * `sanitized` - It takes the sanitized html as an argument.
+/// * `c` - It takes the text character as an argument.
+fn push_escaped(sanitized: &mut String, c: char) {
+    match c {
+        '&' => sanitized.push_str("&amp;"),
+        '<' => sanitized.push_str("&lt;"),
+        '>' => sanitized.push_str("&gt;"),
+        '"' => sanitized.push_str("&quot;"),
+        '\0' => (),
+        c => sanitized.push(c),
+    }
+}
+
+/// A helper function which handles the markup starting at the provided position, the allowed
+/// tags are appended to the sanitized html without their attributes and the other markup is
+/// skipped.
+///
+/// # Arguments
+///
+/// * `html` - It takes the html being sanitized as an argument.
+/// * `start` - It takes the byte index of the `<` opening the markup as an argument.
+/// * `sanitized` - It takes the sanitized html as an argument.
+/// * `open_tags` - It takes the allowed tags which are currently open as an argument.
+///
+/// # Returns
+///
+/// It returns the byte index following the end of the markup.
+fn handle_markup(
+    html: &str,
+    start: usize,
+    sanitized: &mut String,
+    open_tags: &mut Vec<&'static str>,
+) -> usize {
+    let rest = &html[start..];
+    // The comments, doctypes and processing instructions are skipped entirely.
+    if let Some(comment) = rest.strip_prefix("<!--") {
+        return comment
+            .find("-->")
+            .map_or(html.len(), |end| start + 4 + end + 3);
+    }
+    if rest.starts_with("<!") || rest.starts_with("<?") {
+        return rest.find('>').map_or(html.len(), |end| start + end + 1);
+    }
+
+    let Some(tag) = parse_tag(html, start) else {
+        sanitized.push_str("&lt;");
+        return start + 1;
+    };
+
+    if !tag.closing && DROPPED_CONTENT_TAGS.contains(&tag.name.as_str()) {
+        // The tag names are ascii so the byte indices of the lowercase copy are the same.
+        let content = html[tag.end..].to_ascii_lowercase();
+        return content
+            .find(&format!("</{}", tag.name))
+            .and_then(|close| Some(tag.end + close + content[close..].find('>')? + 1))
+            .unwrap_or(html.len());
+    }
+
+    if let Some(allowed) = ALLOWED_TAGS
+        .into_iter()
+        .find(|allowed| *allowed == tag.name)
+    {
+        match tag.closing {
+            false => {
+                open_tags.push(allowed);
+                let _ = write!(sanitized, "<{allowed}>");
+            }
+            // The tags opened inside the closed one are closed along with it, the closing tags
+            // which do not match an open tag are dropped.
+            true => {
+                if let Some(index) = open_tags.iter().rposition(|open| *open == allowed) {
+                    for open in open_tags.drain(index..).rev() {
+                        let _ = write!(sanitized, "</{open}>");
+                    }
+                }
+            }
+        }
+    }
+    tag.end
+}
+
+/// A function which sanitizes the html scraped from an upstream search engine. Only the `<b>`,
+/// `<em>` and `<strong>` tags are kept (without their attributes and balanced), the content of
+/// the script like tags is dropped, the other tags are stripped while keeping their text and the
+/// entities are decoded and escaped again.
+///
+/// # Arguments
+///
+/// * `html` - It takes the html scraped from the upstream search engine as an argument.
+///
+/// # Returns
+///
+/// It returns the sanitized html which is safe to be rendered in the search page.
+pub fn sanitize_html(html: &str) -> String {
+    let mut sanitized = String::with_capacity(html.len());
+    let mut open_tags = Vec::new();
+
+    let mut position = 0;
+    while let Some(c) = html[position..].chars().next() {
+        position = match c {
+            '<' => handle_markup(html, position, &mut sanitized, &mut open_tags),
+            '&' => match decode_entity(&html[position + 1..]) {
+                Some((decoded, length)) => {
+                    push_escaped(&mut sanitized, decoded);
+                    position + 1 + length
+                }
+                None => {
+                    sanitized.push_str("&amp;");
+                    position + 1
+                }
+            },
+            c => {
+                push_escaped(&mut sanitized, c);
+                position + c.len_utf8()
+            }
+        };
+    }
+
+    for open in open_tags.into_iter().rev() {
+        let _ = write!(sanitized, "</{open}>");
+    }
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the hostile payloads embedded in the titles and
+    /// descriptions scraped from each upstream search engine are neutralized.
+    fn test_sanitize_html_neutralizes_hostile_payloads() {
+        let cases = [
+            // DuckDuckGo highlights the query with `<b>` tags.
+            (
+                "Rust <b>programming</b> <script>alert(1)</script>language",
+                "Rust <b>programming</b> language",
+            ),
+            // Searx wraps the descriptions in a paragraph.
+            (
+                "<p class=\"content\">The <em class=\"x\" onmouseover=\"alert(1)\">Rust</em> book</p>",
+                "The <em>Rust</em> book",
+            ),
+            // Brave highlights the query with `<strong>` tags.
+            (
+                "<strong>Rust</strong> <img src=x onerror=alert(1)>lang",
+                "<strong>Rust</strong> lang",
+            ),
+            // Startpage escapes the text with entities.
+            (
+                "Rust &amp; Cargo &#x3C;script&#x3E;alert(1)&#x3C;/script&#x3E;",
+                "Rust &amp; Cargo &lt;script&gt;alert(1)&lt;/script&gt;",
+            ),
+            // LibreX results contain links.
+            (
+                "<a href=\"javascript:alert(1)\">Rust</a> docs",
+                "Rust docs",
+            ),
+            // Mojeek results contain comments.
+            (
+                "Rust<!-- <script>alert(1)</script> --> docs<svg/onload=alert(1)>",
+                "Rust docs",
+            ),
+            // Bing prefixes the descriptions with a date in a `<span>`.
+            (
+                "<span>12 Mar 2024 &#183;</span> Rust <STYLE>body{display:none}</style>docs",
+                "12 Mar 2024 \u{b7} Rust docs",
+            ),
+            // Wikipedia highlights the query with `<span class="searchmatch">` tags.
+            (
+                "<span class=\"searchmatch\">Rust</span> is a <b onclick=\"alert(1)\">language</b><iframe src=\"//evil.example\"></iframe>",
+                "Rust is a <b>language</b>",
+            ),
+            // Yahoo results contain unbalanced tags.
+            (
+                "Rust <b>language</b></b><em>unclosed",
+                "Rust <b>language</b><em>unclosed</em>",
+            ),
+        ];
+        for (html, expected) in cases {
+            assert_eq!(sanitize_html(html), expected, "sanitizing `{html}`");
+        }
+
+        // The `<` and `&` which do not start any markup are text.
+        assert_eq!(
+            sanitize_html("a < b && \"c\" <img src=x onerror=alert(1)//"),
+            "a &lt; b &amp;&amp; &quot;c&quot; &lt;img src=x onerror=alert(1)//"
+        );
+        // The attributes holding a `>` inside quotes do not end the tag early.
+        assert_eq!(
+            sanitize_html("<b title=\"><script>alert(1)</script>\">bold</b>"),
+            "<b>bold</b>"
+        );
+        // The unterminated script like tags drop the rest of the html.
+        assert_eq!(sanitize_html("Rust <ScRiPt>alert(1)"), "Rust ");
+    }
+}
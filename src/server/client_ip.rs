@@ -0,0 +1,167 @@
+//! This module provides the functionality to find the real IP address of the clients when the
+//! server is deployed behind reverse proxies, which provide it in the `Forwarded` or
+//! `X-Forwarded-For` headers.
+
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::http::header::{self, HeaderMap, HeaderName};
+
+use crate::models::parser_models::IpNetwork;
+
+/// A static variable holding the name of the de facto standard `X-Forwarded-For` header.
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// A helper function which parses a single node of the `Forwarded` or `X-Forwarded-For` headers
+/// (like `192.0.2.43`, `"[2001:db8::17]:4711"` or `unknown`) into an IP address.
+///
+/// # Arguments
+///
+/// * `node` - It takes the node as an argument.
+///
+/// # Returns
+///
+/// It returns the IP address or `None` if the node is obfuscated or not a valid address.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(bracketed) = node.strip_prefix('[') {
+        return bracketed.split(']').next()?.parse().ok();
+    }
+    node.parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|address| address.ip()))
+}
+
+/// A helper function which collects the addresses of the `Forwarded` header or, when it is not
+/// present, of the `X-Forwarded-For` header in the order in which they have been appended.
+///
+/// # Arguments
+///
+/// * `headers` - It takes the headers of the request as an argument.
+///
+/// # Returns
+///
+/// It returns the addresses or `None` if one of them is not a valid address, in which case the
+/// chain of proxies cannot be followed.
+fn forwarded_addresses(headers: &HeaderMap) -> Option<Vec<IpAddr>> {
+    let header_values = |name: &HeaderName| {
+        headers
+            .get_all(name)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<&str>>()
+    };
+
+    let forwarded = header_values(&header::FORWARDED);
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    key.trim().eq_ignore_ascii_case("for").then_some(value)
+                })
+            })
+            .map(|node| parse_node(node?))
+            .collect();
+    }
+
+    header_values(&X_FORWARDED_FOR)
+        .into_iter()
+        .map(parse_node)
+        .collect()
+}
+
+/// A function which finds the IP address of the client which sent the request. When the request
+/// comes from one of the trusted proxies, the forwarded addresses are walked from the closest
+/// proxy and the first untrusted one is the client, the headers of the untrusted peers are
+/// ignored as they can be spoofed.
+///
+/// # Arguments
+///
+/// * `peer` - It takes the address of the peer which opened the connection as an argument.
+/// * `headers` - It takes the headers of the request as an argument.
+/// * `trusted_proxies` - It takes the addresses of the trusted reverse proxies as an argument.
+///
+/// # Returns
+///
+/// It returns the IP address of the client or `None` if the peer address is not known.
+pub fn client_ip(
+    peer: Option<SocketAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[IpNetwork],
+) -> Option<IpAddr> {
+    let peer = peer?.ip().to_canonical();
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|network| network.contains(*ip));
+    if !is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    let Some(forwarded) = forwarded_addresses(headers) else {
+        return Some(peer);
+    };
+    // When every forwarded address is trusted the request originates from the proxies
+    // themselves and the first address is the closest one to a client.
+    Some(
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !is_trusted(ip))
+            .or(forwarded.first())
+            .map_or(peer, |ip| ip.to_canonical()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::HeaderValue;
+
+    /// A helper function which builds the headers of a mock request from the provided pairs of
+    /// header names and values.
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        headers
+    }
+
+    #[test]
+    /// A test function to test whether the forwarded addresses are only followed when the
+    /// request comes from a trusted proxy.
+    fn test_client_ip_follows_trusted_proxies_only() {
+        let trusted: Vec<IpNetwork> = vec!["10.0.0.0/8".parse().unwrap()];
+        let proxy = Some("10.0.0.2:4000".parse().unwrap());
+        let stranger = Some("203.0.113.9:4000".parse().unwrap());
+        let spoofed = headers(&[("x-forwarded-for", "198.51.100.1, 192.0.2.7, 10.0.0.3")]);
+
+        // The closest untrusted address is the client, the ones before it can be spoofed.
+        assert_eq!(
+            client_ip(proxy, &spoofed, &trusted),
+            Some("192.0.2.7".parse().unwrap())
+        );
+        // The headers sent by untrusted peers are ignored.
+        assert_eq!(
+            client_ip(stranger, &spoofed, &trusted),
+            Some("203.0.113.9".parse().unwrap())
+        );
+        // The `Forwarded` header takes precedence and its quoted IPv6 nodes are understood.
+        let forwarded = headers(&[
+            ("forwarded", "for=\"[2001:db8::17]:4711\";proto=https"),
+            ("x-forwarded-for", "192.0.2.7"),
+        ]);
+        assert_eq!(
+            client_ip(proxy, &forwarded, &trusted),
+            Some("2001:db8::17".parse().unwrap())
+        );
+        // A malformed chain cannot be followed so the proxy itself is used.
+        let malformed = headers(&[("x-forwarded-for", "not-an-ip")]);
+        assert_eq!(
+            client_ip(proxy, &malformed, &trusted),
+            Some("10.0.0.2".parse().unwrap())
+        );
+    }
+}
@@ -3,6 +3,10 @@
 //! the search route. Also, caches the next, current and previous search results in the search
 //! routes with the help of the redis server.
 
+pub mod client_ip;
+pub mod rate_limiter;
+pub mod request_tracing;
 pub mod router;
 /// Routes module
 pub mod routes;
+pub mod security_headers;
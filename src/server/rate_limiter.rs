@@ -0,0 +1,354 @@
+//! This module provides the middleware which limits the number of requests each client can make
+//! to the server within the time limits provided in the config. The anonymous clients are counted
+//! separately on each class of routes (the pages, the search route and the static files) while
+//! the requests made with an API key are only counted against the quota of the key. The limiters
+//! are kept in a swappable global so that the quotas can be changed by reloading the config
+//! without restarting the server.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    num::NonZeroU32,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        header::{self, HeaderMap, HeaderName, HeaderValue},
+        StatusCode,
+    },
+    middleware::Next,
+    web, HttpResponse,
+};
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::{StateInformationMiddleware, StateSnapshot},
+    DefaultDirectRateLimiter, DefaultKeyedRateLimiter, NotUntil, Quota,
+};
+
+use super::client_ip::client_ip;
+use crate::{
+    config::{parser::Config, reload::SharedConfig},
+    models::parser_models::{RateLimit, RateLimiter},
+};
+
+/// A static variable holding the name of the header carrying the quota of the client.
+static X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+/// A static variable holding the name of the header carrying the remaining quota of the client.
+static X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+/// A static variable holding the name of the header carrying the number of seconds after which
+/// the quota of the client is fully replenished.
+static X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+/// A static variable holding the name of the header carrying the API key of the client.
+static X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
+
+/// A type alias for the rate limiters counting the requests of each anonymous client.
+type KeyedLimiter = DefaultKeyedRateLimiter<IpAddr, StateInformationMiddleware>;
+/// A type alias for the rate limiters counting the requests made with a single API key.
+type DirectLimiter = DefaultDirectRateLimiter<StateInformationMiddleware>;
+
+/// A named struct which stores a rate limiter along with the quota it enforces.
+struct Bucket<L> {
+    /// It stores the quota enforced by the rate limiter.
+    limit: RateLimit,
+    /// It stores the rate limiter.
+    limiter: L,
+}
+
+/// A named struct which stores the rate limiters built from the config options currently in
+/// effect.
+struct Limiters {
+    /// It stores the rate limiter of the anonymous clients on the pages.
+    pages: Bucket<KeyedLimiter>,
+    /// It stores the rate limiter of the anonymous clients on the search route.
+    search: Bucket<KeyedLimiter>,
+    /// It stores the rate limiter of the anonymous clients on the static files.
+    static_files: Bucket<KeyedLimiter>,
+    /// It stores the rate limiter of each API key.
+    api_keys: HashMap<String, Bucket<DirectLimiter>>,
+}
+
+/// A static variable which stores the rate limiters built from the config options currently in
+/// effect. No requests are limited until they have been configured.
+static LIMITERS: RwLock<Option<Arc<Limiters>>> = RwLock::new(None);
+
+/// A helper function which converts the provided quota option into the quota of the rate
+/// limiters.
+///
+/// # Arguments
+///
+/// * `limit` - It takes the quota option as an argument.
+///
+/// # Error
+///
+/// Returns an error if either the number of requests or the time limit is zero.
+fn quota(limit: &RateLimit) -> Result<Quota, Box<dyn std::error::Error>> {
+    let burst_size = NonZeroU32::new(limit.number_of_requests as u32).ok_or(
+        "the `number_of_requests` option of the rate limiter should be a non zero positive integer",
+    )?;
+    Ok(
+        Quota::with_period(Duration::from_secs(limit.time_limit as u64))
+            .ok_or(
+                "the `time_limit` option of the rate limiter should be a non zero positive integer",
+            )?
+            .allow_burst(burst_size),
+    )
+}
+
+/// A helper function which builds a new rate limiter counting the requests of each anonymous
+/// client against the provided quota.
+///
+/// # Arguments
+///
+/// * `limit` - It takes the quota option as an argument.
+///
+/// # Error
+///
+/// Returns an error if either the number of requests or the time limit is zero.
+fn keyed_bucket(limit: &RateLimit) -> Result<Bucket<KeyedLimiter>, Box<dyn std::error::Error>> {
+    Ok(Bucket {
+        limit: *limit,
+        limiter: governor::RateLimiter::keyed(quota(limit)?).with_middleware(),
+    })
+}
+
+/// A function which builds new rate limiters from the provided config options and swaps them
+/// with the ones currently in effect. The requests counted by the previous limiters are
+/// forgotten.
+///
+/// # Arguments
+///
+/// * `rate_limiter` - It takes the rate limiter config options as an argument.
+///
+/// # Error
+///
+/// Returns an error if either the number of requests or the time limit of any quota is zero.
+pub fn configure(rate_limiter: &RateLimiter) -> Result<(), Box<dyn std::error::Error>> {
+    let mut api_keys = HashMap::new();
+    for (key, limit) in &rate_limiter.api_keys {
+        let bucket = Bucket {
+            limit: *limit,
+            limiter: governor::RateLimiter::direct(quota(limit)?).with_middleware(),
+        };
+        api_keys.insert(key.clone(), bucket);
+    }
+
+    let limiters = Limiters {
+        pages: keyed_bucket(&rate_limiter.default)?,
+        search: keyed_bucket(&rate_limiter.search)?,
+        static_files: keyed_bucket(&rate_limiter.static_files)?,
+        api_keys,
+    };
+
+    *LIMITERS.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(limiters));
+    Ok(())
+}
+
+/// A helper function which extracts the key under which the request of an anonymous client is
+/// counted. It is the IP address of the client (taken from the forwarded headers when the request
+/// comes from one of the trusted proxies) for IPv4 clients and the `/56` prefix of it for IPv6
+/// clients (as they usually get a whole prefix assigned).
+///
+/// # Arguments
+///
+/// * `req` - It takes the incoming request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+///
+/// # Returns
+///
+/// It returns the key or `None` if the peer address of the request is not known.
+fn client_key(req: &ServiceRequest, config: Option<&Config>) -> Option<IpAddr> {
+    let trusted_proxies = config.map_or(&[][..], |config| config.trusted_proxies.as_slice());
+    let ip = client_ip(req.peer_addr(), req.headers(), trusted_proxies)?;
+    Some(match ip {
+        IpAddr::V6(ipv6) => {
+            let mut octets = ipv6.octets();
+            octets[7..16].fill(0);
+            IpAddr::V6(octets.into())
+        }
+        IpAddr::V4(_) => ip,
+    })
+}
+
+/// A helper function which extracts the API key provided in the `X-API-Key` header. The
+/// `Authorization` header is left to the routes (like the cache administration routes, which
+/// take their own bearer token).
+///
+/// # Arguments
+///
+/// * `headers` - It takes the headers of the request as an argument.
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(&X_API_KEY)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+}
+
+/// A named struct which stores the state of the quota against which a request has been counted.
+struct Usage {
+    /// It stores the quota of the client.
+    limit: RateLimit,
+    /// It stores the number of requests the client can still make.
+    remaining: u32,
+    /// It stores the number of seconds after which the quota is fully replenished.
+    reset: u64,
+    /// It stores the number of seconds after which the client can retry when the request has
+    /// been rejected.
+    retry_after: Option<u64>,
+}
+
+impl Usage {
+    /// A function which computes the state of the quota from the outcome of a rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - It takes the quota enforced by the rate limiter as an argument.
+    /// * `outcome` - It takes the outcome of the rate limiter as an argument.
+    fn new(
+        limit: RateLimit,
+        outcome: Result<StateSnapshot, NotUntil<<DefaultClock as Clock>::Instant>>,
+    ) -> Self {
+        // A single request is replenished every `time_limit` seconds.
+        let period = u64::from(limit.time_limit);
+        let consumed = |remaining: u32| {
+            u64::from(limit.number_of_requests).saturating_sub(u64::from(remaining))
+        };
+        match outcome {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity();
+                Usage {
+                    limit,
+                    remaining,
+                    reset: consumed(remaining) * period,
+                    retry_after: None,
+                }
+            }
+            Err(not_until) => {
+                let wait_time = not_until.wait_time_from(DefaultClock::default().now());
+                let retry_after = wait_time.as_secs() + u64::from(wait_time.subsec_nanos() > 0);
+                Usage {
+                    limit,
+                    remaining: 0,
+                    reset: retry_after + consumed(1) * period,
+                    retry_after: Some(retry_after),
+                }
+            }
+        }
+    }
+
+    /// A function which inserts the `X-RateLimit-*` headers describing the quota into the
+    /// provided headers.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - It takes the headers of the response as an argument.
+    fn insert_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            X_RATELIMIT_LIMIT.clone(),
+            HeaderValue::from(self.limit.number_of_requests),
+        );
+        headers.insert(
+            X_RATELIMIT_REMAINING.clone(),
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(X_RATELIMIT_RESET.clone(), HeaderValue::from(self.reset));
+    }
+}
+
+/// A helper function which builds the response rejecting a request, the body is a JSON object
+/// for the API clients and a plain text message otherwise.
+///
+/// # Arguments
+///
+/// * `status` - It takes the status code of the response as an argument.
+/// * `message` - It takes the description of the error as an argument.
+/// * `json` - It takes whether the body should be a JSON object as an argument.
+fn rejection(status: StatusCode, message: &str, json: bool) -> HttpResponse {
+    let mut res = HttpResponse::build(status);
+    match json {
+        true => res.json(serde_json::json!({ "error": message })),
+        false => res.body(message.to_owned()),
+    }
+}
+
+/// A middleware function which rejects the requests of the clients which exceeded their quota
+/// with a `429 Too Many Requests` response carrying a `Retry-After` header, and the requests
+/// made with an unknown API key with a `401 Unauthorized` response. The quota of the client is
+/// described by the `X-RateLimit-*` headers of every response.
+///
+/// # Arguments
+///
+/// * `req` - It takes the incoming request as an argument.
+/// * `next` - It takes the rest of the middleware chain as an argument.
+///
+/// # Error
+///
+/// Returns the response of the inner service on success otherwise returns the error of the
+/// inner service.
+pub async fn limit_requests<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let Some(limiters) = LIMITERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+    else {
+        return Ok(next.call(req).await?.map_into_left_body());
+    };
+    let config = req
+        .app_data::<web::Data<&'static SharedConfig>>()
+        .map(|config| config.load());
+
+    let api_key = api_key(req.headers());
+    let json = api_key.is_some()
+        || req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| accept.contains("application/json"));
+
+    let usage = match api_key {
+        // The requests made with an API key are exempt from the anonymous quotas.
+        Some(api_key) => match limiters.api_keys.get(api_key) {
+            Some(bucket) => Usage::new(bucket.limit, bucket.limiter.check()),
+            None => {
+                let res = rejection(StatusCode::UNAUTHORIZED, "Invalid API key", json);
+                return Ok(req.into_response(res).map_into_right_body());
+            }
+        },
+        None => {
+            let Some(key) = client_key(&req, config.as_deref()) else {
+                return Ok(next.call(req).await?.map_into_left_body());
+            };
+            let base_path = config
+                .as_deref()
+                .map_or("", |config| config.base_path.as_str());
+            let path = req.path().strip_prefix(base_path).unwrap_or(req.path());
+            let bucket = if path.starts_with("/static/") || path.starts_with("/images/") {
+                &limiters.static_files
+            } else if path == "/search" {
+                &limiters.search
+            } else {
+                &limiters.pages
+            };
+            Usage::new(bucket.limit, bucket.limiter.check_key(&key))
+        }
+    };
+
+    if let Some(retry_after) = usage.retry_after {
+        let message = format!("Too many requests, retry in {retry_after}s");
+        let mut res = rejection(StatusCode::TOO_MANY_REQUESTS, &message, json);
+        res.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        usage.insert_headers(res.headers_mut());
+        return Ok(req.into_response(res).map_into_right_body());
+    }
+
+    let mut res = next.call(req).await?;
+    usage.insert_headers(res.headers_mut());
+    Ok(res.map_into_left_body())
+}
@@ -0,0 +1,92 @@
+//! This module provides the middleware which assigns a request id to every incoming request and
+//! handles it inside a tracing span carrying that id, so that all the logs emitted while handling
+//! a search (including the ones from the upstream engine tasks) can be correlated.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+};
+use tracing::Instrument;
+
+/// A constant holding the name of the header which carries the request id.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// A static variable which stores the number of requests seen by this process, it is mixed into
+/// the generated ids to keep them unique even when two requests arrive at the same instant.
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A helper function which generates a new random looking request id.
+///
+/// # Returns
+///
+/// It returns the request id as a 16 characters long hex string.
+fn generate_request_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let count = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&nanos.to_le_bytes());
+    hasher.update(&count.to_le_bytes());
+    hasher.update(&std::process::id().to_le_bytes());
+    hasher.finalize().to_hex()[..16].to_owned()
+}
+
+/// A middleware function which handles every request inside a `request` span carrying its id. The
+/// id provided by a reverse proxy through the `x-request-id` header is reused, otherwise a new one
+/// is generated. The id is also set on the request headers (so that it is available to the access
+/// logs) and returned to the client in the response headers.
+///
+/// # Arguments
+///
+/// * `req` - It takes the incoming request as an argument.
+/// * `next` - It takes the rest of the middleware chain as an argument.
+///
+/// # Error
+///
+/// Returns the response of the inner service on success otherwise returns the error of the
+/// inner service.
+pub async fn trace_request(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let request_id = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| {
+            !value.is_empty()
+                && value.len() <= 64
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map(str::to_owned)
+        .unwrap_or_else(generate_request_id);
+
+    // The id only contains header safe characters (validated or generated above).
+    let header_value = HeaderValue::from_str(&request_id)?;
+    req.headers_mut()
+        .insert(REQUEST_ID_HEADER, header_value.clone());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.path(),
+    );
+
+    let mut res = next.call(req).instrument(span).await?;
+    res.headers_mut().insert(REQUEST_ID_HEADER, header_value);
+
+    Ok(res)
+}
@@ -2,12 +2,25 @@
 //! meta search engine website and provide appropriate response to each route/page
 //! when requested.
 
+use std::collections::BTreeMap;
+
 use crate::{
-    config::parser::Config,
+    cache::cacher::SharedCache,
+    config::{parser::Config, reload::SharedConfig},
     handler::{file_path, FileType},
+    metrics::registry::registry,
+    models::server_models::{
+        ComponentHealth, Cookie, HealthStatus, Readiness, ReadinessParams, SearchParams,
+    },
+    templates::i18n::{self, Locale},
+};
+use actix_web::{
+    get,
+    http::header::{self, ContentType},
+    web, HttpRequest, HttpResponse,
 };
-use actix_web::{get, http::header::ContentType, web, HttpRequest, HttpResponse};
 use tokio::fs::read_to_string;
+use crate::server::routes::profiles::PROFILE_COOKIE;
 use crate::server::routes::session_handler;
 use crate::server::routes::connection_manager;
 use crate::server::routes::query_dispatcher;
@@ -15,16 +28,64 @@ use crate::server::routes::ldap_manager;
 use crate::server::routes::redirect_manager;
 use crate::server::routes::query_context;
 
+/// A function which selects the locale of a page from the language chosen by the user (passed
+/// as an argument or else saved in the cookie), the `Accept-Language` header of the browser or
+/// the config in this order.
+///
+/// # Arguments
+///
+/// * `req` - It takes the request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+/// * `language` - It takes the language chosen by the user if it is already known as an
+///   argument.
+///
+/// # Returns
+///
+/// It returns the locale of the page.
+pub fn request_locale(req: &HttpRequest, config: &Config, language: Option<&str>) -> &'static Locale {
+    let saved_language: Option<String> = match language {
+        Some(_) => None,
+        None => req.cookie("appCookie").and_then(|cookie| {
+            serde_json::from_str::<serde_json::Value>(cookie.value())
+                .ok()?
+                .get("language")?
+                .as_str()
+                .map(str::to_owned)
+        }),
+    };
+    let accept_language = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    i18n::negotiate(
+        language.or(saved_language.as_deref()),
+        accept_language,
+        &config.language,
+    )
+}
+
 /// Handles the route of index page or main page of the `websurfx` meta search engine website.
+/// The preferences passed as url parameters (like on the search page) style the page and are
+/// carried over to the search.
 #[get("/")]
 pub async fn index(
-    config: web::Data<&'static Config>,
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let params = web::Query::<SearchParams>::from_query(req.query_string())?;
+    let config = config.load();
+    let mut settings = Cookie::build(&config.style, Vec::new(), config.safe_search);
+    let url_settings = settings.apply_url_settings(&params, &config.upstream_search_engines);
+    let animation = settings.animation.as_deref().map(str::to_owned);
+    let locale = request_locale(&req, &config, settings.language.as_deref());
     Ok(HttpResponse::Ok().content_type(ContentType::html()).body(
         crate::templates::views::index::index(
-            &config.style.colorscheme,
-            &config.style.theme,
-            &config.style.animation,
+            &settings.colorscheme,
+            &settings.theme,
+            &animation,
+            &config.base_path,
+            &url_settings,
+            locale,
         )
         .0,
     ))
@@ -33,13 +94,17 @@ pub async fn index(
 /// Handles the route of any other accessed route/page which is not provided by the
 /// website essentially the 404 error page.
 pub async fn not_found(
-    config: web::Data<&'static Config>,
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
     Ok(HttpResponse::Ok().content_type(ContentType::html()).body(
         crate::templates::views::not_found::not_found(
             &config.style.colorscheme,
             &config.style.theme,
             &config.style.animation,
+            &config.base_path,
+            request_locale(&req, &config, None),
         )
         .0,
     ))
@@ -58,13 +123,17 @@ pub async fn robots_data(_req: HttpRequest) -> Result<HttpResponse, Box<dyn std:
 /// Handles the route of about page of the `websurfx` meta search engine website.
 #[get("/about")]
 pub async fn about(
-    config: web::Data<&'static Config>,
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
     let resp = HttpResponse::Ok().content_type(ContentType::html()).body(
         crate::templates::views::about::about(
             &config.style.colorscheme,
             &config.style.theme,
             &config.style.animation,
+            &config.base_path,
+            request_locale(&req, &config, None),
         )
         .0,
     );
@@ -104,18 +173,118 @@ pub async fn about(
     Ok(resp)
 }
 
+/// Handles the route of the prometheus metrics endpoint of the `websurfx` meta search engine
+/// website.
+#[get("/metrics")]
+pub async fn metrics() -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(crate::metrics::registry::registry().render()))
+}
+
+/// Handles the liveness route of the `websurfx` meta search engine website which reports that
+/// the process is alive and able to handle requests.
+#[get("/healthz")]
+pub async fn healthz() -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    Ok(HttpResponse::Ok().json(ComponentHealth::new(HealthStatus::Ok, None)))
+}
+
+/// Handles the readiness route of the `websurfx` meta search engine website which reports the
+/// health of every component needed to serve search requests. It takes an optional `engines`
+/// url parameter which includes a health summary of the enabled upstream search engines.
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://127.0.0.1:8080/readyz?engines=true"
+/// ```
+#[get("/readyz")]
+pub async fn readyz(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let params = web::Query::<ReadinessParams>::from_query(req.query_string())?;
+    let config = config.load();
+
+    let mut components = BTreeMap::new();
+    // The server only starts once the config has been parsed successfully.
+    components.insert("config", ComponentHealth::new(HealthStatus::Ok, None));
+    components.insert(
+        "cache",
+        match cache.health_check().await {
+            Ok(()) => ComponentHealth::new(HealthStatus::Ok, None),
+            Err(error) => {
+                log::error!("Cache Health Check Error: {:?}", error);
+                ComponentHealth::new(HealthStatus::Unavailable, Some(error.to_string()))
+            }
+        },
+    );
+
+    let engines = params.engines.unwrap_or_default().then(|| {
+        let summary = registry().engine_summary();
+        config
+            .upstream_search_engines
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(engine, _)| {
+                let (requests, errors) = summary
+                    .get(engine.to_lowercase().as_str())
+                    .copied()
+                    .unwrap_or_default();
+                // An engine is considered degraded when most of the requests sent to it failed.
+                let status = match errors * 2 > requests {
+                    true => HealthStatus::Degraded,
+                    false => HealthStatus::Ok,
+                };
+                (
+                    engine.to_owned(),
+                    ComponentHealth::new(
+                        status,
+                        Some(format!("{errors} errors in {requests} requests")),
+                    ),
+                )
+            })
+            .collect()
+    });
+
+    let readiness = Readiness {
+        status: match components
+            .values()
+            .all(|component| component.status == HealthStatus::Ok)
+        {
+            true => HealthStatus::Ok,
+            false => HealthStatus::Unavailable,
+        },
+        components,
+        engines,
+    };
+
+    Ok(match readiness.status {
+        HealthStatus::Ok => HttpResponse::Ok().json(readiness),
+        _ => HttpResponse::ServiceUnavailable().json(readiness),
+    })
+}
+
 /// Handles the route of settings page of the `websurfx` meta search engine website.
 #[get("/settings")]
 pub async fn settings(
-    config: web::Data<&'static Config>,
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    let profile_cookie = req.cookie(PROFILE_COOKIE);
     Ok(HttpResponse::Ok().content_type(ContentType::html()).body(
         crate::templates::views::settings::settings(
             config.safe_search,
             &config.style.colorscheme,
             &config.style.theme,
             &config.style.animation,
+            &config.base_path,
             &config.upstream_search_engines,
+            config.profiles.enabled,
+            profile_cookie.as_ref().map(|cookie| cookie.value()),
+            request_locale(&req, &config, None),
         )?
         .0,
     ))
@@ -0,0 +1,211 @@
+//! This module handles the cache administration routes, which allow to inspect the cached results,
+//! remove the poisoned or stale ones and count the cached results without restarting the server
+//! or flushing the cache backend by hand. The routes are authenticated with the token set in the
+//! `cache_admin` config table.
+
+use crate::{
+    cache::{cacher::SharedCache, error::CacheError, key::CacheKey},
+    config::{parser::Config, reload::SharedConfig},
+    models::server_models::CacheEntryParams,
+};
+use actix_web::{
+    delete, get,
+    http::header::{self, HeaderMap},
+    web, HttpRequest, HttpResponse,
+};
+use error_stack::Report;
+
+/// A helper function which checks whether the request is allowed to use the cache administration
+/// routes, the token provided as a bearer token in the `Authorization` header has to match the
+/// configured token.
+///
+/// # Arguments
+///
+/// * `headers` - It takes the headers of the request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+///
+/// # Returns
+///
+/// It returns the response rejecting the request if it is not allowed otherwise returns `None`.
+fn reject_unauthorized(headers: &HeaderMap, config: &Config) -> Option<HttpResponse> {
+    let token = match (&config.cache_admin.token, config.cache_admin.enabled) {
+        (Some(token), true) => token,
+        _ => return Some(HttpResponse::NotFound().finish()),
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    // The hashes are compared in constant time so that the token can not be guessed from the
+    // time taken by the comparison.
+    match blake3::hash(provided.as_bytes()) == blake3::hash(token.as_bytes()) {
+        true => None,
+        false => Some(
+            HttpResponse::Unauthorized()
+                .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                .json(serde_json::json!({ "error": "Invalid token" })),
+        ),
+    }
+}
+
+/// A helper function which builds the key of the cached results from the
+/// parameters of the request, it is either provided as is or built from the parameters of the
+/// search and the upstream search engine.
+///
+/// # Arguments
+///
+/// * `params` - It takes the parameters of the request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+///
+/// # Returns
+///
+/// It returns the key of the cached results or `None` if the parameters do not identify them.
+fn entry_key(params: &CacheEntryParams, config: &Config) -> Option<String> {
+    if let Some(key) = &params.key {
+        return Some(key.clone());
+    }
+    let key = CacheKey::new(
+        params.q.as_deref()?,
+        params.page.unwrap_or(1).max(1) - 1,
+        params.safesearch.unwrap_or(config.safe_search),
+        params.language.as_deref().unwrap_or(&config.language),
+        params.engine.as_deref()?,
+    );
+    Some(key.to_string())
+}
+
+/// A helper function which builds the response of the cache administration routes when the cache
+/// backend failed.
+///
+/// # Arguments
+///
+/// * `error` - It takes the error returned by the cache backend as an argument.
+fn cache_error(error: Report<CacheError>) -> HttpResponse {
+    log::error!("Cache Administration Error: {:?}", error);
+    HttpResponse::InternalServerError().json(serde_json::json!({ "error": error.to_string() }))
+}
+
+/// Handles the route which shows the cached results identified by the `key` url parameter or by
+/// the `q`, `engine`, `page`, `safesearch` and `language` url parameters.
+///
+/// # Example
+///
+/// ```bash
+/// curl -H "Authorization: Bearer <token>" \
+///     "http://127.0.0.1:8080/admin/cache/entry?q=sweden&engine=duckduckgo"
+/// ```
+#[get("/admin/cache/entry")]
+pub async fn cache_entry(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    if let Some(rejection) = reject_unauthorized(req.headers(), &config) {
+        return Ok(rejection);
+    }
+    let params = web::Query::<CacheEntryParams>::from_query(req.query_string())?;
+    let Some(key) = entry_key(&params, &config) else {
+        return Ok(HttpResponse::BadRequest().json(
+            serde_json::json!({ "error": "Either `key` or `q` and `engine` are required" }),
+        ));
+    };
+
+    Ok(match cache.entry(&key).await {
+        Ok(entry) => HttpResponse::Ok().json(serde_json::json!({
+            "key": key,
+            "cached_at": entry.cached_at,
+            "results": entry.results,
+        })),
+        Err(error) if matches!(error.current_context(), CacheError::MissingValue) => {
+            HttpResponse::NotFound().json(serde_json::json!({ "error": "No such entry" }))
+        }
+        Err(error) => cache_error(error),
+    })
+}
+
+/// Handles the route which removes the cached results identified by the `key` url parameter (or
+/// by the parameters of the search like the `/admin/cache/entry` route) or all the cached results
+/// whose key starts with the `prefix` url parameter.
+///
+/// # Example
+///
+/// ```bash
+/// curl -X DELETE -H "Authorization: Bearer <token>" \
+///     "http://127.0.0.1:8080/admin/cache/entries?prefix=v1/duckduckgo/"
+/// ```
+#[delete("/admin/cache/entries")]
+pub async fn delete_cache_entries(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    if let Some(rejection) = reject_unauthorized(req.headers(), &config) {
+        return Ok(rejection);
+    }
+    let params = web::Query::<CacheEntryParams>::from_query(req.query_string())?;
+    let deleted = match (&params.prefix, entry_key(&params, &config)) {
+        (Some(prefix), None) if !prefix.is_empty() => {
+            cache.delete_results_with_prefix(prefix).await
+        }
+        (None, Some(key)) => cache.delete_results(&[key]).await,
+        _ => {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Either a non empty `prefix` or the key of the entry is required"
+            })))
+        }
+    };
+
+    Ok(match deleted {
+        Ok(deleted) => HttpResponse::Ok().json(serde_json::json!({ "deleted": deleted })),
+        Err(error) => cache_error(error),
+    })
+}
+
+/// Handles the route which removes all the cached results, the profiles are kept.
+///
+/// # Example
+///
+/// ```bash
+/// curl -X DELETE -H "Authorization: Bearer <token>" "http://127.0.0.1:8080/admin/cache"
+/// ```
+#[delete("/admin/cache")]
+pub async fn flush_cache(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    if let Some(rejection) = reject_unauthorized(req.headers(), &config.load()) {
+        return Ok(rejection);
+    }
+    Ok(match cache.flush_results().await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(error) => cache_error(error),
+    })
+}
+
+/// Handles the route which reports the number of cached results and the bytes they take up in
+/// each of the cache backends.
+///
+/// # Example
+///
+/// ```bash
+/// curl -H "Authorization: Bearer <token>" "http://127.0.0.1:8080/admin/cache/stats"
+/// ```
+#[get("/admin/cache/stats")]
+pub async fn cache_stats(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    if let Some(rejection) = reject_unauthorized(req.headers(), &config.load()) {
+        return Ok(rejection);
+    }
+    Ok(match cache.stats().await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(error) => cache_error(error),
+    })
+}
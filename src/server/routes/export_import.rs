@@ -2,8 +2,8 @@
 
 use crate::{
     handler::{file_path, FileType},
+    config::{parser::Config, reload::SharedConfig},
     models::{self, server_models},
-    Config,
 };
 use actix_multipart::form::{tempfile::TempFile, MultipartForm};
 use actix_web::{
@@ -88,9 +88,9 @@ async fn style_option_list<'a>(
 /// # Error
 ///
 /// returns a standard error message on failure otherwise it returns the unit type.
-async fn sanitize(
-    config: web::Data<&'static Config>,
-    setting_value: &mut models::server_models::Cookie<'_>,
+async fn sanitize<'a>(
+    config: &'a Config,
+    setting_value: &mut models::server_models::Cookie<'a>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Check whether the theme, colorscheme and animation option is valid by matching it against
     // the available option list. If the option provided by the user via the JSON file is invalid
@@ -152,9 +152,10 @@ struct File {
 /// Handles the route of the post settings page.
 #[post("/settings")]
 pub async fn set_settings(
-    config: web::Data<&'static Config>,
+    config: web::Data<&'static SharedConfig>,
     MultipartForm(mut form): MultipartForm<File>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
     if let Some(file_name) = form.file.file_name {
         let file_name_parts = file_name.split(".");
         if let 2 = file_name_parts.clone().count() {
@@ -168,7 +169,7 @@ pub async fn set_settings(
                     let mut unsanitized_json_data: models::server_models::Cookie<'_> =
                         serde_json::from_str(&data)?;
 
-                    sanitize(config, &mut unsanitized_json_data).await?;
+                    sanitize(&config, &mut unsanitized_json_data).await?;
 
                     let sanitized_json_data: String =
                         serde_json::json!(unsanitized_json_data).to_string();
@@ -192,9 +193,10 @@ pub async fn set_settings(
 /// Handles the route of the download page.
 #[get("/download")]
 pub async fn download(
-    config: web::Data<&'static Config>,
+    config: web::Data<&'static SharedConfig>,
     req: HttpRequest,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
     let cookie = req.cookie("appCookie");
 
     // Get search settings using the user's cookie or from the server's config
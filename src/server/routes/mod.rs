@@ -12,3 +12,9 @@ pub mod redirect_manager;
 pub mod query_context;
 /// Network monitor module
 pub mod network_monitor;
+/// Preference profiles module
+pub mod profiles;
+/// Search route module
+pub mod search;
+/// Cache administration module
+pub mod cache_admin;
@@ -0,0 +1,287 @@
+//! This module handles the routes of the preference profiles, which save the preferences of the
+//! user on the server under a random token. The link holding the token applies the saved
+//! preferences on any device, so that a team can share the same engines, safe search level and
+//! theme without any accounts.
+
+use std::borrow::Cow;
+
+use crate::{
+    cache::{cacher::SharedCache, error::CacheError},
+    config::{parser::Config, reload::SharedConfig},
+    models::server_models,
+    server::router::request_locale,
+};
+use actix_web::{
+    cookie::{
+        time::{Duration, OffsetDateTime},
+        Cookie,
+    },
+    get,
+    http::header::{self, ContentType},
+    post, web, HttpRequest, HttpResponse,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use error_stack::Report;
+
+/// A constant holding the name of the cookie storing the token of the profile in use.
+pub const PROFILE_COOKIE: &str = "profile";
+
+/// A constant holding the number of random bytes in a profile token.
+const TOKEN_LENGTH: usize = 16;
+
+/// A helper function which generates a new random profile token.
+///
+/// # Error
+///
+/// Returns the url safe base64 encoded token on success otherwise returns an error if the
+/// operating system could not provide the random bytes.
+fn generate_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; TOKEN_LENGTH];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// A helper function which builds the storage key of a profile, the token is hashed so that the
+/// tokens can not be recovered from the storage.
+///
+/// # Arguments
+///
+/// * `token` - It takes the token of the profile as an argument.
+fn profile_key(token: &str) -> String {
+    format!("profile:{}", blake3::hash(token.as_bytes()).to_hex())
+}
+
+/// A helper function which retrieves the preferences saved in a profile from the SQLite file if
+/// one is configured or else from the cache.
+///
+/// # Arguments
+///
+/// * `cache` - It takes the shared cache as an argument.
+/// * `token` - It takes the token of the profile as an argument.
+///
+/// # Error
+///
+/// Returns the preferences as a json string on success otherwise returns a `CacheError`.
+async fn load_profile(cache: &SharedCache, token: &str) -> Result<String, Report<CacheError>> {
+    #[cfg(feature = "sqlite-profiles")]
+    if let Some(store) = crate::cache::sqlite_profiles::store() {
+        return store.cached_profile(&profile_key(token)).await;
+    }
+    cache.cached_profile(&profile_key(token)).await
+}
+
+/// A helper function which saves the preferences in a profile in the SQLite file if one is
+/// configured or else in the cache.
+///
+/// # Arguments
+///
+/// * `cache` - It takes the shared cache as an argument.
+/// * `token` - It takes the token of the profile as an argument.
+/// * `preferences` - It takes the preferences as a json string as an argument.
+///
+/// # Error
+///
+/// Returns a `CacheError` if the profile could not be saved.
+async fn save_profile(
+    cache: &SharedCache,
+    token: &str,
+    preferences: &str,
+) -> Result<(), Report<CacheError>> {
+    #[cfg(feature = "sqlite-profiles")]
+    if let Some(store) = crate::cache::sqlite_profiles::store() {
+        return store.cache_profile(&profile_key(token), preferences).await;
+    }
+    cache.cache_profile(&profile_key(token), preferences).await
+}
+
+/// A helper function which provides the preferences of the user to save in a profile, they are
+/// taken from the `appCookie` cookie or from the config when the cookie is missing or invalid.
+///
+/// # Arguments
+///
+/// * `req` - It takes the request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+///
+/// # Error
+///
+/// Returns the preferences as a json string on success otherwise returns a serialization error.
+fn current_preferences(req: &HttpRequest, config: &Config) -> Result<String, serde_json::Error> {
+    let cookie = req.cookie("appCookie");
+    let preferences: server_models::Cookie<'_> = cookie
+        .as_ref()
+        .and_then(|cookie_value| serde_json::from_str(cookie_value.value()).ok())
+        .unwrap_or_else(|| {
+            server_models::Cookie::build(
+                &config.style,
+                config
+                    .upstream_search_engines
+                    .iter()
+                    .filter_map(|(engine, enabled)| {
+                        enabled.then_some(Cow::Borrowed(engine.as_str()))
+                    })
+                    .collect(),
+                config.safe_search,
+            )
+        });
+    serde_json::to_string(&preferences)
+}
+
+/// A helper function which builds a cookie lasting a year scoped to the path prefix under which
+/// the website is served.
+///
+/// # Arguments
+///
+/// * `name` - It takes the name of the cookie as an argument.
+/// * `value` - It takes the value of the cookie as an argument.
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
+fn build_cookie(name: &'static str, value: String, base_path: &str) -> Cookie<'static> {
+    let path = match base_path.is_empty() {
+        true => "/".to_owned(),
+        false => base_path.to_owned(),
+    };
+    Cookie::build(name, value)
+        .path(path)
+        .expires(OffsetDateTime::now_utc().saturating_add(Duration::weeks(52)))
+        .finish()
+}
+
+/// A helper function which builds the response of the profile routes when the profiles are
+/// disabled or the profile does not exist.
+///
+/// # Arguments
+///
+/// * `req` - It takes the request as an argument.
+/// * `config` - It takes the config currently in effect as an argument.
+fn profile_not_found(req: &HttpRequest, config: &Config) -> HttpResponse {
+    HttpResponse::NotFound()
+        .content_type(ContentType::html())
+        .body(
+            crate::templates::views::not_found::not_found(
+                &config.style.colorscheme,
+                &config.style.theme,
+                &config.style.animation,
+                &config.base_path,
+                request_locale(req, config, None),
+            )
+            .0,
+        )
+}
+
+/// Handles the route which creates a new profile from the preferences of the user and redirects
+/// back to the settings page, where the link to share the profile is shown.
+///
+/// # Example
+///
+/// ```bash
+/// curl -X POST --cookie "appCookie=..." "http://127.0.0.1:8080/profiles"
+/// ```
+#[post("/profiles")]
+pub async fn create_profile(
+    req: HttpRequest,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    if !config.profiles.enabled {
+        return Ok(profile_not_found(&req, &config));
+    }
+
+    let token = generate_token()?;
+    let preferences = current_preferences(&req, &config)?;
+    if let Err(error) = save_profile(&cache, &token, &preferences).await {
+        log::error!("Failed to save the profile: {error:?}");
+        return Ok(HttpResponse::InternalServerError().finish());
+    }
+
+    let mut profile_cookie = build_cookie(PROFILE_COOKIE, token, &config.base_path);
+    profile_cookie.set_http_only(true);
+    Ok(HttpResponse::SeeOther()
+        .cookie(profile_cookie)
+        .insert_header((header::LOCATION, format!("{}/settings", config.base_path)))
+        .finish())
+}
+
+/// Handles the route which replaces the preferences saved in an existing profile with the
+/// preferences of the user and redirects back to the settings page.
+///
+/// # Example
+///
+/// ```bash
+/// curl -X POST --cookie "appCookie=..." "http://127.0.0.1:8080/profiles/<token>"
+/// ```
+#[post("/profiles/{token}")]
+pub async fn update_profile(
+    req: HttpRequest,
+    token: web::Path<String>,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    if !config.profiles.enabled || load_profile(&cache, &token).await.is_err() {
+        return Ok(profile_not_found(&req, &config));
+    }
+
+    let preferences = current_preferences(&req, &config)?;
+    if let Err(error) = save_profile(&cache, &token, &preferences).await {
+        log::error!("Failed to save the profile: {error:?}");
+        return Ok(HttpResponse::InternalServerError().finish());
+    }
+
+    Ok(HttpResponse::SeeOther()
+        .insert_header((header::LOCATION, format!("{}/settings", config.base_path)))
+        .finish())
+}
+
+/// Handles the route of the link shared for a profile, it applies the preferences saved in the
+/// profile on the device by setting the `appCookie` cookie and redirects to the index page.
+///
+/// # Example
+///
+/// ```bash
+/// curl "http://127.0.0.1:8080/profiles/<token>"
+/// ```
+#[get("/profiles/{token}")]
+pub async fn apply_profile(
+    req: HttpRequest,
+    token: web::Path<String>,
+    config: web::Data<&'static SharedConfig>,
+    cache: web::Data<&'static SharedCache>,
+) -> Result<HttpResponse, Box<dyn std::error::Error>> {
+    let config = config.load();
+    if !config.profiles.enabled {
+        return Ok(profile_not_found(&req, &config));
+    }
+    let Ok(preferences) = load_profile(&cache, &token).await else {
+        return Ok(profile_not_found(&req, &config));
+    };
+
+    let mut profile_cookie = build_cookie(PROFILE_COOKIE, token.into_inner(), &config.base_path);
+    profile_cookie.set_http_only(true);
+    Ok(HttpResponse::SeeOther()
+        .cookie(build_cookie("appCookie", preferences, &config.base_path))
+        .cookie(profile_cookie)
+        .insert_header((header::LOCATION, format!("{}/", config.base_path)))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the generated tokens are unique and url safe and whether
+    /// the storage keys do not contain the tokens.
+    fn test_generate_token_and_profile_key() {
+        let (first, second) = (generate_token().unwrap(), generate_token().unwrap());
+        assert_ne!(first, second);
+        assert_eq!(first.len(), 22);
+        assert!(first
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        let key = profile_key(&first);
+        assert!(key.starts_with("profile:"));
+        assert!(!key.contains(&first));
+        assert_eq!(key, profile_key(&first));
+    }
+}
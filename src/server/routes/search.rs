@@ -1,22 +1,34 @@
 //! This module handles the search route of the search engine website.
 
 use crate::{
-    cache::cacher::SharedCache,
-    config::parser::Config,
+    cache::{
+        cacher::{CacheLookup, SharedCache},
+        key::CacheKey,
+    },
+    config::{parser::Config, reload::SharedConfig},
     handler::{file_path, FileType},
     models::{
-        aggregation_models::SearchResults,
+        aggregation_models::{SearchResult, SearchResults},
         engine_models::EngineHandler,
         server_models::{self, SearchParams},
     },
-    results::aggregator::aggregate,
+    results::{
+        aggregator::{engine_results, merge_results, EngineResults},
+        coalescing::SingleFlight,
+    },
+    server::router::request_locale,
 };
 use axum_session::SessionConfig;
 use actix_web::{get, http::header::ContentType, web, HttpRequest, HttpResponse};
-use itertools::Itertools;
+use futures::future::join_all;
 use regex::Regex;
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::{borrow::Cow, time::Duration};
+use std::{
+    borrow::Cow,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use url::form_urlencoded;
 use tokio::{
     fs::File,
     io::{AsyncBufReadExt, BufReader},
@@ -24,7 +36,10 @@ use tokio::{
 };
 
 /// Handles the route of search page of the `websurfx` meta search engine website and it takes
-/// two search url parameters `q` and `page` where `page` parameter is optional.
+/// two search url parameters `q` and `page` where `page` parameter is optional. The preferences
+/// can also be passed as the url parameters `engines` (a comma separated list), `theme`,
+/// `colorscheme`, `animation`, `highlight_query` and `safesearch` which take precedence over the
+/// cookie.
 ///
 /// # Example
 ///
@@ -37,18 +52,25 @@ use tokio::{
 /// ```bash
 /// curl "http://127.0.0.1:8080/search?q=sweden"
 /// ```
+///
+/// Or
+///
+/// ```bash
+/// curl "http://127.0.0.1:8080/search?q=sweden&engines=duckduckgo,brave&theme=simple"
+/// ```
 #[get("/search")]
 pub async fn search(
     req: HttpRequest,
-    config: web::Data<&'static Config>,
+    config: web::Data<&'static SharedConfig>,
     cache: web::Data<&'static SharedCache>,
 ) -> Result<HttpResponse, Box<dyn std::error::Error>> {
     let params = web::Query::<SearchParams>::from_query(req.query_string())?;
+    let config = config.load();
     match &params.q {
         Some(query) => {
             if query.trim().is_empty() {
                 return Ok(HttpResponse::TemporaryRedirect()
-                    .insert_header(("location", "/"))
+                    .insert_header(("location", index_location(&config, &params)))
                     .finish());
             }
 
@@ -72,6 +94,11 @@ pub async fn search(
                     )
                 });
 
+            // The preferences passed as url parameters take precedence over the cookie like the
+            // safe search level.
+            let url_settings =
+                search_settings.apply_url_settings(&params, &config.upstream_search_engines);
+
             search_settings.safe_search_level = get_safesearch_level(
                 params.safesearch,
                 search_settings.safe_search_level,
@@ -93,152 +120,322 @@ pub async fn search(
                 tokio::time::sleep(Duration::from_secs(delay)).await;
             }
 
-            let results: (SearchResults, String, bool);
-            if page != previous_page {
-                let (previous_results, current_results, next_results) = join!(
+            // The results of the neighbouring pages are only fetched so that they are cached
+            // beforehand.
+            let mut results = if page != previous_page {
+                let (_, current_results, _) = join!(
                     get_results(previous_page),
                     get_results(page),
                     get_results(next_page)
                 );
-
-                results = current_results?;
-
-                let (results_list, cache_keys): (Vec<SearchResults>, Vec<String>) =
-                    [previous_results?, results.clone(), next_results?]
-                        .into_iter()
-                        .filter_map(|(result, cache_key, flag)| flag.then_some((result, cache_key)))
-                        .multiunzip();
-
-                tokio::spawn(async move { cache.cache_results(&results_list, &cache_keys).await });
+                current_results?
             } else {
-                let (current_results, next_results) =
-                    join!(get_results(page), get_results(page + 1));
-
-                results = current_results?;
-
-                let (results_list, cache_keys): (Vec<SearchResults>, Vec<String>) =
-                    [results.clone(), next_results?]
-                        .into_iter()
-                        .filter_map(|(result, cache_key, flag)| flag.then_some((result, cache_key)))
-                        .multiunzip();
+                let (current_results, _) = join!(get_results(page), get_results(next_page));
+                current_results?
+            };
 
-                tokio::spawn(async move { cache.cache_results(&results_list, &cache_keys).await });
+            // The results are highlighted after being cached as the option differs between the
+            // users.
+            if search_settings.highlight_query {
+                results.highlight(query);
             }
 
+            let animation = search_settings.animation.as_deref().map(str::to_owned);
+            let locale = request_locale(&req, &config, search_settings.language.as_deref());
             Ok(HttpResponse::Ok().content_type(ContentType::html()).body(
                 crate::templates::views::search::search(
-                    &config.style.colorscheme,
-                    &config.style.theme,
-                    &config.style.animation,
+                    &search_settings.colorscheme,
+                    &search_settings.theme,
+                    &animation,
+                    &config.base_path,
                     query,
                     page,
-                    &results.0,
+                    &results,
+                    &url_settings,
+                    locale,
                 )
                 .0,
             ))
         }
         None => Ok(HttpResponse::TemporaryRedirect()
-            .insert_header(("location", "/"))
+            .insert_header(("location", index_location(&config, &params)))
             .finish()),
     }
 }
 
-/// Fetches the results for a query and page. It First checks the redis cache, if that
-/// fails it gets proper results by requesting from the upstream search engines.
+/// A helper function which builds the location of the index page to redirect to when no query is
+/// provided, the preferences passed as url parameters are carried over to it.
+///
+/// # Arguments
+///
+/// * `config` - It takes the config currently in effect as an argument.
+/// * `params` - It takes the parameters of the search url as an argument.
+///
+/// # Returns
+///
+/// It returns the location of the index page.
+fn index_location(config: &Config, params: &SearchParams) -> String {
+    let mut settings = server_models::Cookie::build(&config.style, Vec::new(), config.safe_search);
+    let url_settings = settings.apply_url_settings(params, &config.upstream_search_engines);
+    match url_settings.is_empty() {
+        true => format!("{}/", config.base_path),
+        false => format!(
+            "{}/?{}",
+            config.base_path,
+            form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(url_settings)
+                .finish()
+        ),
+    }
+}
+
+/// Fetches the results for a query and page. The results of each upstream search engine are
+/// cached separately, so that only the engines missing from the cache are requested and the users
+/// with different engine selections share the cached results of the engines they have in common.
+/// The stale cached results are served as is while they are refreshed in the background.
 ///
 /// # Arguments
 ///
-/// * `url` - It takes the url of the current page that requested the search results for a
-///   particular search query.
 /// * `config` - It takes a parsed config struct.
-/// * `query` - It takes the page number as u32 value.
-/// * `req` - It takes the `HttpRequest` struct as a value.
+/// * `cache` - It takes the cache storing the results of the upstream search engines.
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number as u32 value.
+/// * `search_settings` - It takes the preferences of the user as an argument.
 ///
 /// # Error
 ///
 /// It returns the `SearchResults` struct if the search results could be successfully fetched from
 /// the cache or from the upstream search engines otherwise it returns an appropriate error.
 async fn results(
-    config: &'static Config,
+    config: &Arc<Config>,
     cache: &'static SharedCache,
     query: &str,
     page: u32,
     search_settings: &server_models::Cookie<'_>,
-) -> Result<(SearchResults, String, bool), Box<dyn std::error::Error>> {
+) -> Result<SearchResults, Box<dyn std::error::Error>> {
     // eagerly parse cookie value to evaluate safe search level
     let safe_search_level = search_settings.safe_search_level;
 
-    let cache_key = format!(
-        "http://{}:{}/search?q={}&page={}&safesearch={}&engines={}",
-        config.binding_ip,
-        config.port,
-        query,
-        page,
-        safe_search_level,
-        search_settings.engines.join(",")
+    // Return early when query contains disallowed words,
+    if safe_search_level == 4
+        && !is_match_from_filter_list(file_path(FileType::BlockList)?, query).await?
+    {
+        let mut results = SearchResults::default();
+        results.set_disallowed();
+        results.set_safe_search_level(safe_search_level);
+        return Ok(results);
+    }
+
+    // check if the cookie value is empty or not if it is empty then use the
+    // default selected upstream search engines from the config file otherwise
+    // parse the non-empty cookie and grab the user selected engines from the
+    // UI and use that.
+    let mut results: SearchResults = match search_settings.engines.is_empty() {
+        false => {
+            let language = search_settings
+                .language
+                .as_deref()
+                .unwrap_or(&config.language);
+            let engines = search_settings
+                .engines
+                .iter()
+                .filter_map(|engine| EngineHandler::new(engine).ok())
+                .collect();
+            engines_results(
+                config,
+                cache,
+                engines,
+                query,
+                page,
+                safe_search_level,
+                language,
+            )
+            .await?
+        }
+        true => {
+            let mut search_results = SearchResults::default();
+            search_results.set_no_engines_selected();
+            search_results
+        }
+    };
+    let (engine_errors_info, results_empty_check, no_engines_selected) = (
+        results.engine_errors_info().is_empty(),
+        results.results().is_empty(),
+        results.no_engines_selected(),
     );
+    results.set_filtered(engine_errors_info & results_empty_check & !no_engines_selected);
+    results.set_safe_search_level(safe_search_level);
+    Ok(results)
+}
 
-    // fetch the cached results json.
-    let cached_results = cache.cached_results(&cache_key).await;
-    // check if fetched cache results was indeed fetched or it was an error and if so
-    // handle the data accordingly.
-    match cached_results {
-        Ok(results) => Ok((results, cache_key, false)),
-        Err(_) => {
-            if safe_search_level == 4 {
-                let mut results: SearchResults = SearchResults::default();
-
-                let flag: bool =
-                    !is_match_from_filter_list(file_path(FileType::BlockList)?, query).await?;
-                // Return early when query contains disallowed words,
-                if flag {
-                    results.set_disallowed();
-                    cache
-                        .cache_results(&[results.clone()], &[cache_key.clone()])
-                        .await?;
-                    results.set_safe_search_level(safe_search_level);
-                    return Ok((results, cache_key, true));
-                }
-            }
+/// A helper function which fetches the results of the upstream search engines for a query and
+/// page and merges them, the results of each engine are fetched from the cache so that only the
+/// engines missing from it are requested.
+///
+/// # Arguments
+///
+/// * `config` - It takes a parsed config struct.
+/// * `cache` - It takes the cache storing the results of the upstream search engines.
+/// * `engines` - It takes the handlers of the upstream search engines as an argument.
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number as an argument.
+/// * `safe_search_level` - It takes the safe search level as an argument.
+/// * `language` - It takes the code of the locale of the pages as an argument.
+///
+/// # Error
+///
+/// It returns the merged `SearchResults` struct on success otherwise it returns an appropriate
+/// error.
+async fn engines_results(
+    config: &Arc<Config>,
+    cache: &'static SharedCache,
+    engines: Vec<EngineHandler>,
+    query: &str,
+    page: u32,
+    safe_search_level: u8,
+    language: &str,
+) -> Result<SearchResults, Box<dyn std::error::Error>> {
+    let responses = join_all(engines.into_iter().map(|engine| {
+        cached_engine_results(
+            config,
+            cache,
+            engine,
+            query,
+            page,
+            safe_search_level,
+            language,
+        )
+    }))
+    .await;
+    merge_results(query, safe_search_level, responses).await
+}
 
-            // check if the cookie value is empty or not if it is empty then use the
-            // default selected upstream search engines from the config file otherwise
-            // parse the non-empty cookie and grab the user selected engines from the
-            // UI and use that.
-            let mut results: SearchResults = match search_settings.engines.is_empty() {
-                false => {
-                    aggregate(
-                        query,
-                        page,
-                        config,
-                        &search_settings
-                            .engines
-                            .iter()
-                            .filter_map(|engine| EngineHandler::new(engine).ok())
-                            .collect::<Vec<EngineHandler>>(),
-                        safe_search_level,
-                    )
-                    .await?
-                }
-                true => {
-                    let mut search_results = SearchResults::default();
-                    search_results.set_no_engines_selected();
-                    search_results
-                }
-            };
-            let (engine_errors_info, results_empty_check, no_engines_selected) = (
-                results.engine_errors_info().is_empty(),
-                results.results().is_empty(),
-                results.no_engines_selected(),
-            );
-            results.set_filtered(engine_errors_info & results_empty_check & !no_engines_selected);
-            cache
-                .cache_results(&[results.clone()], &[cache_key.clone()])
-                .await?;
-            results.set_safe_search_level(safe_search_level);
-            Ok((results, cache_key, true))
+/// A helper function which fetches the results of an upstream search engine for a query and page
+/// from the cache, if they are missing they are requested from the upstream search engine and
+/// cached. The stale cached results are served as is while they are refreshed in the background.
+///
+/// # Arguments
+///
+/// * `config` - It takes a parsed config struct.
+/// * `cache` - It takes the cache storing the results of the upstream search engines.
+/// * `engine` - It takes the handler of the upstream search engine as an argument.
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number as an argument.
+/// * `safe_search_level` - It takes the safe search level as an argument.
+/// * `language` - It takes the code of the locale of the pages as an argument.
+///
+/// # Returns
+///
+/// It returns the name of the upstream search engine with either its results keyed by their url
+/// or the info of the error which occurred.
+async fn cached_engine_results(
+    config: &Arc<Config>,
+    cache: &'static SharedCache,
+    engine: EngineHandler,
+    query: &str,
+    page: u32,
+    safe_search_level: u8,
+    language: &str,
+) -> (&'static str, EngineResults) {
+    let name = engine.name();
+    let cache_key = CacheKey::new(query, page, safe_search_level, language, name).to_string();
+
+    match cache.lookup(&cache_key).await {
+        Ok(CacheLookup::Fresh(results)) => return (name, Ok(keyed_results(results))),
+        Ok(CacheLookup::Stale(results)) => {
+            let (config, query, key) = (config.clone(), query.to_owned(), cache_key.clone());
+            cache.revalidate(cache_key, async move {
+                coalesced_engine_results(&config, &key, engine, &query, page, safe_search_level)
+                    .await
+                    .map(cached_results)
+                    .map_err(|error| {
+                        log::error!("Failed to refresh the stale results: {}", error.error)
+                    })
+                    .ok()
+            });
+            return (name, Ok(keyed_results(results)));
         }
+        Err(_) => (),
     }
+
+    let results =
+        coalesced_engine_results(config, &cache_key, engine, query, page, safe_search_level).await;
+    // The errors are not cached so that the upstream search engine is requested again on the
+    // next search.
+    if let Ok(results) = &results {
+        if let Err(error) = cache
+            .cache_results(&[cached_results(results.clone())], &[cache_key])
+            .await
+        {
+            log::error!("Failed to cache the results of {name}: {error:?}");
+        }
+    }
+    (name, results)
+}
+
+/// A static variable which stores the requests to the upstream search engines in flight by their
+/// cache key.
+static UPSTREAM_SEARCHES: OnceLock<SingleFlight<EngineResults>> = OnceLock::new();
+
+/// A helper function which requests the results of an upstream search engine for a query and
+/// page, the identical concurrent requests (with the same cache key) await a single request to
+/// the upstream search engine instead of requesting it once per search.
+///
+/// # Arguments
+///
+/// * `config` - It takes a parsed config struct.
+/// * `cache_key` - It takes the cache key identifying the request as an argument.
+/// * `engine` - It takes the handler of the upstream search engine as an argument.
+/// * `query` - It takes the search query as an argument.
+/// * `page` - It takes the page number as an argument.
+/// * `safe_search_level` - It takes the safe search level as an argument.
+///
+/// # Error
+///
+/// It returns the results of the upstream search engine keyed by their url otherwise it returns
+/// the info of the error which occurred.
+async fn coalesced_engine_results(
+    config: &Config,
+    cache_key: &str,
+    engine: EngineHandler,
+    query: &str,
+    page: u32,
+    safe_search_level: u8,
+) -> EngineResults {
+    UPSTREAM_SEARCHES
+        .get_or_init(SingleFlight::new)
+        .run(cache_key, || {
+            engine_results(query, page, config, engine, safe_search_level)
+        })
+        .await
+}
+
+/// A helper function which converts the results of an upstream search engine into the
+/// `SearchResults` struct stored in the cache.
+///
+/// # Arguments
+///
+/// * `results` - It takes the results keyed by their url as an argument.
+fn cached_results(results: Vec<(String, SearchResult)>) -> SearchResults {
+    SearchResults::new(
+        results.into_iter().map(|(_, result)| result).collect(),
+        Box::default(),
+    )
+}
+
+/// A helper function which converts the `SearchResults` struct stored in the cache back into the
+/// results of an upstream search engine keyed by their url.
+///
+/// # Arguments
+///
+/// * `results` - It takes the cached results as an argument.
+fn keyed_results(results: SearchResults) -> Vec<(String, SearchResult)> {
+    results
+        .results
+        .into_vec()
+        .into_iter()
+        .map(|result| (result.url.clone(), result))
+        .collect()
 }
 
 /// A helper function which checks whether the search query contains any keywords which should be
@@ -300,7 +497,66 @@ fn get_safesearch_level(
 
 #[cfg(test)]
 mod tests {
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use reqwest::Client;
+
+    use super::*;
+    use crate::models::engine_models::{EngineError, SearchEngine};
+    #[cfg(feature = "memory-cache")]
+    use crate::{
+        cache::cacher::{Cacher, InMemoryCache},
+        metrics::registry::registry,
+    };
+    #[cfg(feature = "memory-cache")]
+    use actix_web::{
+        test::{call_and_read_body, init_service, TestRequest},
+        App,
+    };
+
+    /// A mock upstream search engine which counts the number of requests made to it.
+    struct MockEngine {
+        /// It stores the number of requests made to the engine, shared with the test.
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl SearchEngine for MockEngine {
+        async fn results(
+            &self,
+            query: &str,
+            _page: u32,
+            _user_agent: &str,
+            _client: &Client,
+            _safe_search: u8,
+        ) -> error_stack::Result<Vec<(String, SearchResult)>, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let url = format!("https://www.example.com/{query}");
+            let result = SearchResult::new(query, &url, "A mock result", &["mock"]);
+            Ok(vec![(url, result)])
+        }
+    }
+
+    /// A helper function which creates the handler of a mock upstream search engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - It takes the name of the mock engine as an argument.
+    /// * `calls` - It takes the counter of the requests made to the mock engine as an argument.
+    ///
+    /// # Returns
+    ///
+    /// Returns the handler of the mock engine.
+    fn mock_engine(name: &'static str, calls: &Arc<AtomicUsize>) -> EngineHandler {
+        let engine = MockEngine {
+            calls: calls.clone(),
+        };
+        EngineHandler::from_engine(name, Box::new(engine))
+    }
 
     /// A helper function which creates a random mock safe search level value.
     ///
@@ -363,4 +619,146 @@ mod tests {
             safe_search_level_value_from_branchless_code
         );
     }
+
+    #[tokio::test]
+    /// A test function to test whether the identical concurrent searches of an upstream search
+    /// engine make a single request to it and all get its results.
+    async fn test_coalesced_engine_results_request_the_engine_once() {
+        let config = Config::parse(true).unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache_key = CacheKey::new("coalesced", 0, 0, &config.language, "mock").to_string();
+
+        let responses = join_all((0..10).map(|_| {
+            coalesced_engine_results(
+                &config,
+                &cache_key,
+                mock_engine("mock", &calls),
+                "coalesced",
+                0,
+                0,
+            )
+        }))
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(responses
+            .iter()
+            .all(|results| results.as_ref().is_ok_and(|results| results.len() == 1)));
+    }
+
+    #[cfg(feature = "memory-cache")]
+    #[tokio::test]
+    /// A test function to test whether a search sharing some upstream search engines with a
+    /// previous search only requests the engines missing from the cache.
+    async fn test_overlapping_searches_only_request_the_missing_engines() {
+        let config = Arc::new(Config::parse(true).unwrap());
+        let cache: &'static SharedCache = Box::leak(Box::new(SharedCache::new(
+            InMemoryCache::build(&config).await,
+        )));
+        let (a, b, c) = (
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+        );
+
+        let first = engines_results(
+            &config,
+            cache,
+            vec![mock_engine("mock-a", &a), mock_engine("mock-b", &b)],
+            "overlapping",
+            0,
+            0,
+            &config.language,
+        )
+        .await
+        .unwrap();
+        let second = engines_results(
+            &config,
+            cache,
+            vec![mock_engine("mock-b", &b), mock_engine("mock-c", &c)],
+            "overlapping",
+            0,
+            0,
+            &config.language,
+        )
+        .await
+        .unwrap();
+
+        // The results of the engine in common are served from the cache to the second search.
+        for calls in [a, b, c] {
+            assert_eq!(calls.load(Ordering::SeqCst), 1);
+        }
+        for mut results in [first, second] {
+            assert_eq!(results.results().len(), 1);
+            assert!(results.engine_errors_info().is_empty());
+        }
+    }
+
+    #[cfg(feature = "memory-cache")]
+    #[actix_web::test]
+    /// A test function to test whether the search route serves the stale cached results of a
+    /// page while a single refresh per stale page requests the upstream search engine, even when
+    /// the page is searched twice at the same time.
+    async fn test_search_serves_stale_results_while_refreshing() {
+        let mut config = Config::parse(true).unwrap();
+        config.aggregator.random_delay = false;
+        config.debug = false;
+        config.safe_search = 0;
+        // The refreshes give up quickly when the upstream search engine is unreachable.
+        config.request_timeout = 1;
+        let cache: &'static SharedCache = Box::leak(Box::new(
+            SharedCache::new(InMemoryCache::build(&config).await)
+                .with_stale_while_revalidate(Duration::ZERO),
+        ));
+
+        // The search fetches the current and the next page, both are cached and already stale.
+        let url = "https://www.example.com/cached";
+        let result = SearchResult::new("A cached result", url, "Cached before", &["duckduckgo"]);
+        for page in [0, 1] {
+            let key = CacheKey::new("stale pages", page, 0, &config.language, "duckduckgo");
+            cache
+                .cache_results(
+                    &[cached_results(vec![(url.to_owned(), result.clone())])],
+                    &[key.to_string()],
+                )
+                .await
+                .unwrap();
+        }
+
+        let config: &'static SharedConfig = Box::leak(Box::new(SharedConfig::new(config)));
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(config))
+                .app_data(web::Data::new(cache))
+                .service(search),
+        )
+        .await;
+        let upstream_requests = || {
+            registry()
+                .engine_summary()
+                .get("duckduckgo")
+                .map_or(0, |(requests, _)| *requests)
+        };
+        let requests_before = upstream_requests();
+
+        let request = || {
+            TestRequest::get()
+                .uri("/search?q=stale+pages&engines=duckduckgo")
+                .to_request()
+        };
+        let (first, second) = join!(
+            call_and_read_body(&app, request()),
+            call_and_read_body(&app, request())
+        );
+        for body in [first, second] {
+            assert!(String::from_utf8_lossy(&body).contains("A cached result"));
+        }
+
+        // The refreshes run in the background after the stale pages have been served.
+        while upstream_requests() < requests_before + 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(upstream_requests(), requests_before + 2);
+    }
 }
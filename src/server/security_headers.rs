@@ -0,0 +1,152 @@
+//! This module provides the middleware which sets the security related headers (like the
+//! `Content-Security-Policy`) configured in the config file on every response.
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{self, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    web,
+};
+
+use crate::{config::reload::SharedConfig, models::parser_models::SecurityHeaders};
+
+/// A static variable holding the name of the `Permissions-Policy` header.
+static PERMISSIONS_POLICY: HeaderName = HeaderName::from_static("permissions-policy");
+
+/// A helper function which builds the content security policy with the `frame-ancestors`
+/// directive appended to it.
+///
+/// # Arguments
+///
+/// * `options` - It takes the security headers config options as an argument.
+///
+/// # Returns
+///
+/// It returns the content security policy or an empty string if none should be sent.
+fn content_security_policy(options: &SecurityHeaders) -> String {
+    let policy = options.content_security_policy.trim().trim_end_matches(';');
+    match (policy.is_empty(), options.frame_ancestors.trim()) {
+        (_, "") => policy.to_owned(),
+        (true, frame_ancestors) => format!("frame-ancestors {frame_ancestors}"),
+        (false, frame_ancestors) => format!("{policy}; frame-ancestors {frame_ancestors}"),
+    }
+}
+
+/// A function which inserts the configured security headers into the provided headers, the
+/// headers already set by the route handlers are left untouched. The `X-Frame-Options` header is
+/// also set for the browsers which do not support the `frame-ancestors` directive when the
+/// frames are denied or only allowed from the same origin.
+///
+/// # Arguments
+///
+/// * `options` - It takes the security headers config options as an argument.
+/// * `headers` - It takes the headers of the response as an argument.
+pub fn insert_headers(options: &SecurityHeaders, headers: &mut HeaderMap) {
+    let frame_options = match options.frame_ancestors.trim() {
+        "'none'" => "DENY",
+        "'self'" => "SAMEORIGIN",
+        _ => "",
+    };
+    let hsts = options
+        .hsts_max_age
+        .map(|max_age| format!("max-age={max_age}"))
+        .unwrap_or_default();
+    let nosniff = match options.content_type_nosniff {
+        true => "nosniff",
+        false => "",
+    };
+
+    let values = [
+        (
+            header::CONTENT_SECURITY_POLICY,
+            content_security_policy(options),
+        ),
+        (header::X_FRAME_OPTIONS, frame_options.to_owned()),
+        (header::REFERRER_POLICY, options.referrer_policy.clone()),
+        (
+            PERMISSIONS_POLICY.clone(),
+            options.permissions_policy.clone(),
+        ),
+        (header::X_CONTENT_TYPE_OPTIONS, nosniff.to_owned()),
+        (header::STRICT_TRANSPORT_SECURITY, hsts),
+    ];
+    for (name, value) in values {
+        if value.is_empty() || headers.contains_key(&name) {
+            continue;
+        }
+        // The values are validated when the config is parsed.
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// A middleware function which sets the security headers configured in the config currently in
+/// effect on every response.
+///
+/// # Arguments
+///
+/// * `req` - It takes the incoming request as an argument.
+/// * `next` - It takes the rest of the middleware chain as an argument.
+///
+/// # Error
+///
+/// Returns the response of the inner service on success otherwise returns the error of the
+/// inner service.
+pub async fn set_security_headers(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let config = req
+        .app_data::<web::Data<&'static SharedConfig>>()
+        .map(|config| config.load());
+
+    let mut res = next.call(req).await?;
+    if let Some(config) = config {
+        insert_headers(&config.security_headers, res.headers_mut());
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the default security headers are set and whether the
+    /// `frame-ancestors` directive is appended to the content security policy.
+    fn test_insert_headers() {
+        let mut options = SecurityHeaders::default();
+        let mut headers = HeaderMap::new();
+        insert_headers(&options, &mut headers);
+
+        let policy = headers[header::CONTENT_SECURITY_POLICY].to_str().unwrap();
+        assert!(policy.starts_with("default-src 'self';"));
+        assert!(policy.ends_with("; frame-ancestors 'none'"));
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert!(headers.contains_key(&PERMISSIONS_POLICY));
+        assert!(!headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+        // The empty options disable their header and HSTS is only sent when configured.
+        options.content_security_policy = String::new();
+        options.frame_ancestors = "https://example.com".to_owned();
+        options.referrer_policy = String::new();
+        options.hsts_max_age = Some(31536000);
+        let mut headers = HeaderMap::new();
+        insert_headers(&options, &mut headers);
+
+        assert_eq!(
+            headers[header::CONTENT_SECURITY_POLICY],
+            "frame-ancestors https://example.com"
+        );
+        assert!(!headers.contains_key(header::X_FRAME_OPTIONS));
+        assert!(!headers.contains_key(header::REFERRER_POLICY));
+        assert_eq!(
+            headers[header::STRICT_TRANSPORT_SECURITY],
+            "max-age=31536000"
+        );
+    }
+}
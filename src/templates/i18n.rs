@@ -0,0 +1,268 @@
+//! This module provides the internationalization of the `websurfx` frontend. The translation
+//! catalogs are embedded in the app and the locale of each page is selected from the language
+//! chosen by the user, the `Accept-Language` header of the browser or the config.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+/// A constant holding the codes of the shipped locales, the first one is used for the messages
+/// missing from the other catalogs.
+pub const LOCALE_CODES: [&str; 2] = ["en", "ar"];
+
+/// A constant holding the translation catalogs of the shipped locales in the same order as
+/// `LOCALE_CODES`.
+const CATALOGS: [&str; 2] = [
+    include_str!("locales/en.toml"),
+    include_str!("locales/ar.toml"),
+];
+
+/// A static variable holding the parsed translation catalogs.
+static LOCALES: OnceLock<Vec<Locale>> = OnceLock::new();
+
+/// A named struct which stores a parsed translation catalog.
+pub struct Locale {
+    /// It stores the code of the locale (like `en`).
+    code: &'static str,
+    /// It stores the name of the language in the language itself.
+    name: String,
+    /// It stores whether the language is written from right to left.
+    rtl: bool,
+    /// It stores the translated messages by their dotted key (like `search.not_found`).
+    messages: HashMap<String, String>,
+}
+
+/// A helper function which flattens the nested tables of a translation catalog into dotted keys.
+///
+/// # Arguments
+///
+/// * `prefix` - It takes the dotted key of the table as an argument.
+/// * `table` - It takes the table to flatten as an argument.
+/// * `messages` - It takes the flattened messages as an argument.
+fn flatten(prefix: &str, table: toml::Table, messages: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = match prefix.is_empty() {
+            true => key,
+            false => format!("{prefix}.{key}"),
+        };
+        match value {
+            toml::Value::String(message) => {
+                messages.insert(key, message);
+            }
+            toml::Value::Table(table) => flatten(&key, table, messages),
+            _ => (),
+        }
+    }
+}
+
+impl Locale {
+    /// A function which parses a translation catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - It takes the code of the locale as an argument.
+    /// * `catalog` - It takes the toml source of the catalog as an argument.
+    ///
+    /// # Error
+    ///
+    /// Returns the parsed locale on success otherwise returns the toml parsing error.
+    fn parse(code: &'static str, catalog: &str) -> Result<Self, toml::de::Error> {
+        let mut messages = HashMap::new();
+        flatten("", toml::from_str(catalog)?, &mut messages);
+        Ok(Self {
+            code,
+            name: messages
+                .remove("locale.name")
+                .unwrap_or_else(|| code.to_owned()),
+            rtl: messages.remove("locale.direction").as_deref() == Some("rtl"),
+            messages,
+        })
+    }
+
+    /// A getter function which provides the code of the locale (like `en`).
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// A getter function which provides the name of the language in the language itself.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A getter function which provides the value of the `dir` html attribute for the language.
+    pub fn direction(&self) -> &'static str {
+        match self.rtl {
+            true => "rtl",
+            false => "ltr",
+        }
+    }
+
+    /// A function which provides the translated message for the provided key, the English
+    /// message is used when it is missing from the catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the dotted key of the message as an argument.
+    ///
+    /// # Returns
+    ///
+    /// It returns the translated message or the key itself if no catalog provides it.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages
+            .get(key)
+            .or_else(|| locales()[0].messages.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// A function which splits the translated message around a placeholder, so that the
+    /// placeholder can be replaced by some markup (like a link) in the templates.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - It takes the dotted key of the message as an argument.
+    /// * `placeholder` - It takes the placeholder (like `{query}`) as an argument.
+    ///
+    /// # Returns
+    ///
+    /// It returns the text before and after the placeholder.
+    pub fn split<'a>(&'a self, key: &'a str, placeholder: &str) -> (&'a str, &'a str) {
+        let message = self.text(key);
+        message.split_once(placeholder).unwrap_or((message, ""))
+    }
+}
+
+/// A function which provides the parsed translation catalogs of the shipped locales, in the same
+/// order as `LOCALE_CODES`. A catalog which fails to parse is replaced by an empty one so that
+/// the English messages are used instead.
+pub fn locales() -> &'static [Locale] {
+    LOCALES.get_or_init(|| {
+        LOCALE_CODES
+            .into_iter()
+            .zip(CATALOGS)
+            .map(|(code, catalog)| {
+                Locale::parse(code, catalog).unwrap_or_else(|error| {
+                    log::error!("Failed to parse the `{code}` translation catalog: {error}");
+                    Locale {
+                        code,
+                        name: code.to_owned(),
+                        rtl: false,
+                        messages: HashMap::new(),
+                    }
+                })
+            })
+            .collect()
+    })
+}
+
+/// A function which provides the locale matching a language tag, only the primary language
+/// subtag is compared (so `ar-EG` matches `ar`).
+///
+/// # Arguments
+///
+/// * `tag` - It takes the language tag as an argument.
+///
+/// # Returns
+///
+/// It returns the matching locale or `None` if it is not shipped.
+pub fn locale(tag: &str) -> Option<&'static Locale> {
+    let primary = tag.trim().split(['-', '_']).next()?;
+    locales()
+        .iter()
+        .find(|locale| locale.code.eq_ignore_ascii_case(primary))
+}
+
+/// A function which selects the locale of a page. The language chosen by the user takes
+/// precedence over the languages accepted by the browser (in the order of their quality) which
+/// take precedence over the language set in the config.
+///
+/// # Arguments
+///
+/// * `preferred` - It takes the language chosen by the user (from the cookie or the url) as an
+///   argument.
+/// * `accept_language` - It takes the value of the `Accept-Language` header as an argument.
+/// * `default` - It takes the language set in the config as an argument.
+///
+/// # Returns
+///
+/// It returns the selected locale, the English one is used when none of them is shipped.
+pub fn negotiate(
+    preferred: Option<&str>,
+    accept_language: Option<&str>,
+    default: &str,
+) -> &'static Locale {
+    let mut accepted: Vec<(f32, &str)> = accept_language
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|range| {
+            let mut parameters = range.split(';');
+            let tag = parameters.next()?.trim();
+            let quality = parameters.find_map(|parameter| parameter.trim().strip_prefix("q="));
+            let quality = match quality {
+                Some(quality) => quality.trim().parse::<f32>().ok()?,
+                None => 1.0,
+            };
+            (!tag.is_empty() && quality > 0.0).then_some((quality, tag))
+        })
+        .collect();
+    // The sort is stable so the languages with the same quality keep their order.
+    accepted.sort_by(|first, second| second.0.total_cmp(&first.0));
+
+    preferred
+        .into_iter()
+        .chain(accepted.into_iter().map(|(_, tag)| tag))
+        .chain([default])
+        .find_map(locale)
+        .unwrap_or(&locales()[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// A test function to test whether the shipped catalogs parse and whether their messages use
+    /// the same placeholders as the English ones.
+    fn test_catalogs_placeholders() {
+        let placeholders = |message: &str| -> Vec<String> {
+            message
+                .split('{')
+                .skip(1)
+                .filter_map(|part| Some(format!("{{{}}}", part.split_once('}')?.0)))
+                .collect()
+        };
+
+        let english = &locales()[0];
+        assert!(!english.messages.is_empty());
+        for locale in locales() {
+            assert!(!locale.messages.is_empty(), "`{}` is empty", locale.code);
+            for (key, message) in &locale.messages {
+                let english_message = english.messages.get(key);
+                assert!(english_message.is_some(), "`{key}` is not in English");
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(english_message.unwrap()),
+                    "the placeholders of `{key}` in `{}`",
+                    locale.code
+                );
+            }
+        }
+        assert_eq!(locale("ar").unwrap().direction(), "rtl");
+    }
+
+    #[test]
+    /// A test function to test whether the locale is selected from the language chosen by the
+    /// user, the `Accept-Language` header and the config in this order.
+    fn test_negotiate() {
+        assert_eq!(negotiate(Some("ar"), Some("en-US"), "en").code(), "ar");
+        assert_eq!(negotiate(Some("xx"), Some("ar-EG"), "en").code(), "ar");
+        assert_eq!(
+            negotiate(None, Some("fr;q=0.9, en;q=0.5, ar-SA;q=0.8"), "en").code(),
+            "ar"
+        );
+        assert_eq!(negotiate(None, Some("ar;q=0, fr"), "en").code(), "en");
+        assert_eq!(negotiate(None, Some("*"), "ar").code(), "ar");
+        assert_eq!(negotiate(None, None, "xx").code(), "en");
+
+        let (before, after) = locale("en").unwrap().split("search.not_found", "{query}");
+        assert_eq!(before, "Your search - ");
+        assert_eq!(after, " - did not match any documents.");
+    }
+}
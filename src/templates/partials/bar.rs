@@ -2,26 +2,34 @@
 
 use maud::{html, Markup, PreEscaped};
 
+use crate::templates::i18n::Locale;
+
 /// A functions that handles the html code for the bar for the `search_bar` partial and the
 /// home/index/main page in the search engine frontend.
 ///
 /// # Arguments
 ///
 /// * `query` - It takes the current search query provided by user as an argument.
+/// * `url_settings` - It takes the preferences passed as url parameters as an argument, they are
+///   carried over to the next search.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
 /// It returns the compiled html code for the search bar as a result.
-pub fn bar(query: &str) -> Markup {
+pub fn bar(query: &str, url_settings: &[(&str, String)], locale: &Locale) -> Markup {
     html!(
-        (PreEscaped("<form action=\"/search\">"))
+        (PreEscaped("<form action=\"search\">"))
+        @for (name, value) in url_settings {
+            input type="hidden" name=(name) value=(value);
+        }
         (PreEscaped("<div class=\"search_bar\">"))
-            input type="search" name="q" value=(query) placeholder="Type to search";
-            button type="button" onclick="clearSearchText()" {
-                img src="./images/close.svg" alt="Clear button icon for clearing search input text";
+            input type="search" name="q" value=(query) placeholder=(locale.text("bar.placeholder"));
+            button type="button" class="clear_button" {
+                img src="./images/close.svg" alt=(locale.text("bar.clear_alt"));
             }
             button type="submit" {
-                img src="./images/magnifying_glass.svg" alt="Info icon for error box";
+                img src="./images/magnifying_glass.svg" alt=(locale.text("bar.search_alt"));
             }
     )
 }
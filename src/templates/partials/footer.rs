@@ -2,23 +2,28 @@
 
 use maud::{html, Markup, PreEscaped};
 
+use crate::templates::i18n::Locale;
+
 /// A functions that handles the html code for the footer for all the pages in the search engine
 /// frontend.
 ///
+/// # Arguments
+///
+/// * `locale` - It takes the locale of the page as an argument.
+///
 /// # Returns
 ///
 /// It returns the compiled html code for the footer as a result.
-pub fn footer() -> Markup {
+pub fn footer(locale: &Locale) -> Markup {
     html!(
         footer{
            div{
-              span{"Powered By "b{"Websurfx"}}span{"-"}span{"a lightning-fast, privacy respecting, secure meta
-                  search engine"}
+              span{(locale.text("footer.powered_by"))" "b{"Websurfx"}}span{"-"}span{(locale.text("footer.tagline"))}
            }
            div{
               ul{
-                  li{a href="https://github.com/neon-mmd/websurfx"{"Source Code"}}
-                  li{a href="https://github.com/neon-mmd/websurfx/issues"{"Issues/Bugs"}}
+                  li{a href="https://github.com/neon-mmd/websurfx"{(locale.text("footer.source_code"))}}
+                  li{a href="https://github.com/neon-mmd/websurfx/issues"{(locale.text("footer.issues"))}}
               }
            }
         }
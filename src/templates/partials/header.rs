@@ -1,6 +1,6 @@
 //! A module that handles the header for all the pages in the `websurfx` frontend.
 
-use crate::templates::partials::navbar::navbar;
+use crate::templates::{i18n::Locale, partials::navbar::navbar};
 use maud::{html, Markup, PreEscaped, DOCTYPE};
 
 /// A function that handles the html code for the header for all the pages in the search engine frontend.
@@ -9,19 +9,29 @@ use maud::{html, Markup, PreEscaped, DOCTYPE};
 ///
 /// * `colorscheme` - It takes the colorscheme name as an argument.
 /// * `theme` - It takes the theme name as an argument.
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
 /// It returns the compiled html markup code for the header as a result.
-pub fn header(colorscheme: &str, theme: &str, animation: &Option<String>) -> Markup {
+pub fn header(
+    colorscheme: &str,
+    theme: &str,
+    animation: &Option<String>,
+    base_path: &str,
+    locale: &Locale,
+) -> Markup {
     html!(
         (DOCTYPE)
-        html lang="en"
+        (PreEscaped(format!("<html lang=\"{}\" dir=\"{}\">", locale.code(), locale.direction())))
 
         head{
             title{"Websurfx"}
             meta charset="UTF-8";
             meta name="viewport" content="width=device-width, initial-scale=1";
+            // All the links are relative to the path prefix under which the website is served.
+            base href=(format!("{base_path}/"));
             link href=(format!("static/colorschemes/{colorscheme}.css")) rel="stylesheet" type="text/css";
             link href=(format!("static/themes/{theme}.css")) rel="stylesheet" type="text/css";
             @if animation.is_some() {
@@ -29,10 +39,10 @@ pub fn header(colorscheme: &str, theme: &str, animation: &Option<String>) -> Mar
             }
         }
 
-        (PreEscaped("<body onload=\"getClientSettings()\">"))
+        (PreEscaped("<body>"))
             header{
-                h1{a href="/"{"Websurfx"}}
-                (navbar())
+                h1{a href="./"{"Websurfx"}}
+                (navbar(locale))
             }
     )
 }
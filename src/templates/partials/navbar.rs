@@ -2,17 +2,23 @@
 
 use maud::{html, Markup};
 
+use crate::templates::i18n::Locale;
+
 /// A functions that handles the html code for the header partial.
 ///
+/// # Arguments
+///
+/// * `locale` - It takes the locale of the page as an argument.
+///
 /// # Returns
 ///
 /// It returns the compiled html code for the navbar as a result.
-pub fn navbar() -> Markup {
+pub fn navbar(locale: &Locale) -> Markup {
     html!(
         nav{
             ul{
-               li{a href="about"{"about"}}
-               li{a href="settings"{"settings"}}
+               li{a href="about"{(locale.text("navbar.about"))}}
+               li{a href="settings"{(locale.text("navbar.settings"))}}
             }
         }
     )
@@ -2,10 +2,18 @@
 
 use maud::{html, Markup, PreEscaped};
 
-use crate::{models::aggregation_models::EngineErrorInfo, templates::partials::bar::bar};
+use crate::{
+    models::aggregation_models::EngineErrorInfo,
+    templates::{i18n::Locale, partials::bar::bar},
+};
 
-/// A constant holding the named safe search level options for the corresponding values 0, 1 and 2.
-const SAFE_SEARCH_LEVELS_NAME: [&str; 3] = ["None", "Low", "Moderate"];
+/// A constant holding the message keys of the named safe search level options for the
+/// corresponding values 0, 1 and 2.
+pub const SAFE_SEARCH_LEVELS_NAME: [&str; 3] = [
+    "safe_search.none",
+    "safe_search.low",
+    "safe_search.moderate",
+];
 
 /// A functions that handles the html code for the search bar for the search page.
 ///
@@ -15,6 +23,8 @@ const SAFE_SEARCH_LEVELS_NAME: [&str; 3] = ["None", "Low", "Moderate"];
 ///   search engine which failed to provide results as an argument.
 /// * `safe_search_level` - It takes the safe search level with values from 0-2 as an argument.
 /// * `query` - It takes the current search query provided by user as an argument.
+/// * `url_settings` - It takes the preferences passed as url parameters as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
@@ -23,32 +33,34 @@ pub fn search_bar(
     engine_errors_info: &[EngineErrorInfo],
     safe_search_level: u8,
     query: &str,
+    url_settings: &[(&str, String)],
+    locale: &Locale,
 ) -> Markup {
     html!(
         .search_area{
-            (bar(query))
+            (bar(query, url_settings, locale))
                 .error_box {
                    @if !engine_errors_info.is_empty(){
-                      button type="button" onclick="toggleErrorBox()" class="error_box_toggle_button"{
-                         img src="./images/warning.svg" alt="Info icon for error box";
+                      button type="button" class="error_box_toggle_button"{
+                         img src="./images/warning.svg" alt=(locale.text("search_bar.warning_alt"));
                       }
                       .dropdown_error_box{
                          @for errors in engine_errors_info{
                             .error_item{
                                span class="engine_name"{(errors.engine)}
                                span class="engine_name"{(errors.error)}
-                               span class="severity_color" style="background: {{{this.severity_color}}};"{}
+                               span class=(format!("severity_color {}", errors.severity_color)){}
                             }
                          }
                       }
                    }
                    @else {
-                      button type="button" onclick="toggleErrorBox()" class="error_box_toggle_button"{
-                         img src="./images/info.svg" alt="Warning icon for error box";
+                      button type="button" class="error_box_toggle_button"{
+                         img src="./images/info.svg" alt=(locale.text("search_bar.info_alt"));
                       }
                       .dropdown_error_box {
                          .no_errors{
-                            "Everything looks good 🙂!!"
+                            (locale.text("search_bar.no_errors"))
                          }
                       }
                   }
@@ -62,11 +74,12 @@ pub fn search_bar(
                    (PreEscaped(format!("<select name=\"safesearch\" value=\"{}\">", safe_search_level)))
                }
                @for (idx, name) in SAFE_SEARCH_LEVELS_NAME.iter().enumerate() {
+                   @let name = locale.text("safe_search.option").replace("{level}", locale.text(name));
                    @if (safe_search_level as usize) == idx {
-                       option value=(idx) selected {(format!("SafeSearch: {name}"))}
+                       option value=(idx) selected {(name)}
                    }
                    @else{
-                       option value=(idx) {(format!("SafeSearch: {name}"))}
+                       option value=(idx) {(name)}
                    }
                }
                (PreEscaped("</select>"))
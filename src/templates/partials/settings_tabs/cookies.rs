@@ -2,23 +2,54 @@
 
 use maud::{html, Markup};
 
+use crate::templates::i18n::Locale;
+
 /// A functions that handles the html code for the cookies tab for the settings page for the search page.
 ///
+/// # Arguments
+///
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
+/// * `profiles_enabled` - It takes whether the preference profiles are enabled on the server as
+/// an argument.
+/// * `profile_token` - It takes the token of the profile in use on this device as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
+///
 /// # Returns
 ///
 /// It returns the compiled html markup code for the cookies tab.
-pub fn cookies() -> Markup {
+pub fn cookies(
+    base_path: &str,
+    profiles_enabled: bool,
+    profile_token: Option<&str>,
+    locale: &Locale,
+) -> Markup {
     html!(
         div class="cookies tab"{
-           h1{"Cookies"}
+           h1{(locale.text("settings.cookies.title"))}
            p class="description"{
-               "This is the cookies are saved on your system and it contains the preferences
-               you chose in the settings page"
+               (locale.text("settings.cookies.description"))
            }
-           input type="text" name="cookie_field" value="" readonly;
+           // The messages are shown by the `cookies.js` script when no cookie could be read.
+           input type="text" name="cookie_field" value="" readonly
+               data-no-cookies=(locale.text("settings.cookies.no_cookies"))
+               data-cookie-error=(locale.text("settings.cookies.cookie_error"));
            p class="description"{
-               "The cookies stored are not used by us for any malicious intend or for
-               tracking you in any way."
+               (locale.text("settings.cookies.tracking"))
+           }
+           @if profiles_enabled {
+               h3{(locale.text("settings.cookies.profile_title"))}
+               p class="description"{
+                   (locale.text("settings.cookies.profile_description"))
+               }
+               @if let Some(token) = profile_token {
+                   input type="text" class="profile_link" value=(format!("{base_path}/profiles/{token}")) readonly;
+                   form method="post" action=(format!("profiles/{token}")) target="_self"{
+                        button type="submit" {(locale.text("settings.cookies.update_profile"))}
+                   }
+               }
+               form method="post" action="profiles" target="_self"{
+                    button type="submit" {(locale.text("settings.cookies.create_profile"))}
+               }
            }
         }
     )
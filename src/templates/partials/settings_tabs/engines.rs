@@ -4,23 +4,26 @@ use std::collections::HashMap;
 
 use maud::{html, Markup};
 
+use crate::templates::i18n::Locale;
+
 /// A functions that handles the html code for the engines tab for the settings page for the search page.
 ///
 /// # Arguments
 ///
 /// * `engine_names` - It takes the key value pair list of all available engine names and there corresponding
 ///   selected (enabled/disabled) value as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
 /// It returns the compiled html markup code for the engines tab.
-pub fn engines(engine_names: &HashMap<String, bool>) -> Markup {
+pub fn engines(engine_names: &HashMap<String, bool>, locale: &Locale) -> Markup {
     html!(
         div class="engines tab"{
-           h1{"Engines"}
-           h3{"select search engines"}
+           h1{(locale.text("settings.engines.title"))}
+           h3{(locale.text("settings.engines.select_title"))}
            p class="description"{
-              "Select the search engines from the list of engines that you want results from"
+              (locale.text("settings.engines.select_description"))
            }
            .engine_selection{
                // Checks whether all the engines are selected or not if they are then the
@@ -29,19 +32,19 @@ pub fn engines(engine_names: &HashMap<String, bool>) -> Markup {
                @if engine_names.values().all(|selected| *selected){
                    .toggle_btn{
                       label class="switch"{
-                         input type="checkbox" class="select_all" onchange="toggleAllSelection()" checked;
+                         input type="checkbox" class="select_all" checked;
                          span class="slider round"{}
                       }
-                      "Select All"
+                      (locale.text("settings.engines.select_all"))
                    }
                }
                @else{
                    .toggle_btn {
                       label class="switch"{
-                         input type="checkbox" class="select_all" onchange="toggleAllSelection()";
+                         input type="checkbox" class="select_all";
                          span class="slider round"{}
                       }
-                      "Select All"
+                      (locale.text("settings.engines.select_all"))
                    }
                }
                hr;
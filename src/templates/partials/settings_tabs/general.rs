@@ -2,55 +2,63 @@
 
 use maud::{html, Markup};
 
-/// A constant holding the named safe search level options for the corresponding values 0, 1 and 2.
-const SAFE_SEARCH_LEVELS: [(u8, &str); 3] = [(0, "None"), (1, "Low"), (2, "Moderate")];
+use crate::templates::i18n::Locale;
+
+/// A constant holding the message keys of the named safe search level options for the
+/// corresponding values 0, 1 and 2.
+const SAFE_SEARCH_LEVELS: [(u8, &str); 3] = [
+    (0, "safe_search.none"),
+    (1, "safe_search.low"),
+    (2, "safe_search.moderate"),
+];
 
 /// A functions that handles the html code for the general tab for the settings page for the search page.
 ///
 /// # Arguments
 ///
 /// * `safe_search_level` - It takes the safe search level as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
 /// It returns the compiled html markup code for the general tab.
-pub fn general(safe_search_level: u8) -> Markup {
+pub fn general(safe_search_level: u8, locale: &Locale) -> Markup {
     html!(
         div class="general tab active"{
-           h1{"General"}
-           h3{"Select a safe search level"}
+           h1{(locale.text("settings.general.title"))}
+           h3{(locale.text("settings.general.safe_search_title"))}
            p class="description"{
-               "Select a safe search level from the menu below to filter content based on the level."
+               (locale.text("settings.general.safe_search_description"))
            }
            @if safe_search_level < 3 {
                select name="safe_search_levels" {
                    // Sets the user selected safe_search_level name from the config file as the first option in the selection list.
-                   option value=(safe_search_level){(SAFE_SEARCH_LEVELS.iter().find(|level| level.0 == safe_search_level).unwrap().1)}
+                   option value=(safe_search_level){(locale.text(SAFE_SEARCH_LEVELS.iter().find(|level| level.0 == safe_search_level).unwrap().1))}
                    @for (k,v) in SAFE_SEARCH_LEVELS.iter().filter(|level| level.0 != safe_search_level){
-                     option value=(k){(v)}
+                     option value=(k){(locale.text(v))}
                    }
                }
            }
            @else {
-               p class="admin_warning" {"⚠️  This setting is being managed by the server administrator."}
+               p class="admin_warning" {(locale.text("settings.general.admin_warning"))}
                select name="safe_search_levels" disabled {
-                     option value=(SAFE_SEARCH_LEVELS[2].0){(SAFE_SEARCH_LEVELS[2].1)}
+                     option value=(SAFE_SEARCH_LEVELS[2].0){(locale.text(SAFE_SEARCH_LEVELS[2].1))}
                }
            }
-           h3{"Restore preferences from file"}
+           h3{(locale.text("settings.general.restore_title"))}
            p class="description"{
-               "Select a json file to restore preferences for the search engine."
+               (locale.text("settings.general.restore_description"))
            }
-           form method="post" enctype="multipart/form-data" onsubmit="setTimeout(function () { window.location.reload(); }, 10)" action="/settings" target="_self"{
+           form class="restore_form" method="post" enctype="multipart/form-data" action="settings" target="_self"{
                 input type="file" name="file" accept="application/json";
-                button type="submit" {"Submit"}
+                button type="submit" {(locale.text("settings.general.submit"))}
            }
-           h3{"Export preferences to a file"}
+           h3{(locale.text("settings.general.export_title"))}
            p class="description"{
-               "Export all the settings saved as a cookie as a json file."
+               (locale.text("settings.general.export_description"))
            }
            a class="export_btn" href="./download" download="settings.json" {
-                button type="submit" {"Export"}
+                button type="submit" {(locale.text("settings.general.export"))}
            }
         }
     )
@@ -1,6 +1,9 @@
 //! A module that handles the user interface tab for setting page view in the `websurfx` frontend.
 
-use crate::handler::{file_path, FileType};
+use crate::{
+    handler::{file_path, FileType},
+    templates::i18n::{locales, Locale},
+};
 use maud::{html, Markup};
 use std::fs::read_dir;
 
@@ -14,6 +17,7 @@ use std::fs::read_dir;
 ///   argument.
 /// * `selected_style` - It takes the currently selected style value provided via the config file
 ///   as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Error
 ///
@@ -22,6 +26,7 @@ use std::fs::read_dir;
 fn style_option_list(
     style_type: &str,
     selected_style: &str,
+    locale: &Locale,
 ) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
     let mut style_option_names: Vec<(String, String)> = Vec::new();
     for file in read_dir(format!(
@@ -36,7 +41,10 @@ fn style_option_list(
     }
 
     if style_type == "animations" {
-        style_option_names.push((String::default(), "none".to_owned()))
+        style_option_names.push((
+            String::default(),
+            locale.text("settings.user_interface.no_animation").to_owned(),
+        ))
     }
 
     Ok(style_option_names)
@@ -44,6 +52,13 @@ fn style_option_list(
 
 /// A functions that handles the html code for the user interface tab for the settings page for the search page.
 ///
+/// # Arguments
+///
+/// * `theme` - It takes the theme name as an argument.
+/// * `colorscheme` - It takes the colorscheme name as an argument.
+/// * `animation` - It takes the animation name as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
+///
 /// # Error
 ///
 /// It returns the compiled html markup code for the user interface tab on success otherwise
@@ -52,45 +67,68 @@ pub fn user_interface(
     theme: &str,
     colorscheme: &str,
     animation: &Option<String>,
+    locale: &Locale,
 ) -> Result<Markup, Box<dyn std::error::Error>> {
     Ok(html!(
         div class="user_interface tab"{
-           h1{"User Interface"}
-           h3{"select theme"}
+           h1{(locale.text("settings.user_interface.title"))}
+           h3{(locale.text("settings.user_interface.theme_title"))}
            p class="description"{
-               "Select the theme from the available themes to be used in user interface"
+               (locale.text("settings.user_interface.theme_description"))
            }
            select name="themes"{
                // Sets the user selected theme name from the config file as the first option in the selection list.
                option value=(theme){(theme.replace('-', " "))}
-               @for (k,v) in style_option_list("themes", theme)?{
+               @for (k,v) in style_option_list("themes", theme, locale)?{
                    option value=(k){(v)}
                }
            }
-           h3{"select color scheme"}
+           h3{(locale.text("settings.user_interface.colorscheme_title"))}
            p class="description"{
-               "Select the color scheme for your theme to be used in user interface"
+               (locale.text("settings.user_interface.colorscheme_description"))
            }
            select name="colorschemes"{
                // Sets the user selected colorscheme name from the config file as the first option in the selection list.
                option value=(colorscheme){(colorscheme.replace('-', " "))}
-               @for (k,v) in style_option_list("colorschemes", colorscheme)?{
+               @for (k,v) in style_option_list("colorschemes", colorscheme, locale)?{
                    option value=(k){(v)}
                }
            }
-           h3{"select animation"}
+           h3{(locale.text("settings.user_interface.animation_title"))}
            p class="description"{
-               "Select the animation for your theme to be used in user interface"
+               (locale.text("settings.user_interface.animation_description"))
            }
            select name="animations"{
                @let default_animation = &String::default();
                @let animation = animation.as_ref().unwrap_or(default_animation);
                // Sets the user selected animation name from the config file as the first option in the selection list.
                option value=(animation){(animation.replace('-'," "))}
-               @for (k,v) in style_option_list("animations", animation)?{
+               @for (k,v) in style_option_list("animations", animation, locale)?{
                    option value=(k){(v)}
                }
            }
+           h3{(locale.text("settings.user_interface.language_title"))}
+           p class="description"{
+               (locale.text("settings.user_interface.language_description"))
+           }
+           select name="languages"{
+               // The language of the browser is used when no language is selected.
+               option value=""{(locale.text("settings.user_interface.automatic_language"))}
+               @for language in locales(){
+                   option value=(language.code()) lang=(language.code()){(language.name())}
+               }
+           }
+           h3{(locale.text("settings.user_interface.highlight_title"))}
+           p class="description"{
+               (locale.text("settings.user_interface.highlight_description"))
+           }
+           .toggle_btn{
+              label class="switch"{
+                 input type="checkbox" class="highlight_query" checked;
+                 span class="slider round"{}
+              }
+              (locale.text("settings.user_interface.highlight"))
+           }
         }
     ))
 }
@@ -1,6 +1,9 @@
 //! A module that handles the view for the 404 page in the `websurfx` frontend.
 
-use crate::templates::partials::{footer::footer, header::header};
+use crate::templates::{
+    i18n::Locale,
+    partials::{footer::footer, header::header},
+};
 use maud::{html, Markup};
 
 /// A function that handles the html code for the 404 page view in the search engine frontend.
@@ -9,21 +12,30 @@ use maud::{html, Markup};
 ///
 /// * `colorscheme` - It takes the colorscheme name as an argument.
 /// * `theme` - It takes the theme name as an argument.
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
 /// It returns the compiled html markup code as a result.
-pub fn not_found(colorscheme: &str, theme: &str, animation: &Option<String>) -> Markup {
+pub fn not_found(
+    colorscheme: &str,
+    theme: &str,
+    animation: &Option<String>,
+    base_path: &str,
+    locale: &Locale,
+) -> Markup {
     html!(
-        (header(colorscheme, theme, animation))
+        (header(colorscheme, theme, animation, base_path, locale))
         main class="error_container"{
-         img src="images/robot-404.svg" alt="Image of broken robot.";
+         img src="images/robot-404.svg" alt=(locale.text("not_found.image_alt"));
          .error_content{
-          h1{"Aw! snap"}
-          h2{"404 Page Not Found!"}
-          p{"Go to "{a href="/"{"search page"}}}
+          h1{(locale.text("not_found.title"))}
+          h2{(locale.text("not_found.heading"))}
+          @let (before, after) = locale.split("not_found.go_to", "{link}");
+          p{(before){a href="./"{(locale.text("not_found.search_page"))}}(after)}
          }
         }
-        (footer())
+        (footer(locale))
     )
 }
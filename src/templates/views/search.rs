@@ -1,10 +1,14 @@
 //! A module that handles the view for the search page in the `websurfx` frontend.
 
 use maud::{html, Markup, PreEscaped};
+use url::form_urlencoded;
 
 use crate::{
     models::aggregation_models::SearchResults,
-    templates::partials::{footer::footer, header::header, search_bar::search_bar},
+    templates::{
+        i18n::Locale,
+        partials::{footer::footer, header::header, search_bar::search_bar},
+    },
 };
 
 /// A function that handles the html code for the search page view in the search engine frontend.
@@ -13,8 +17,12 @@ use crate::{
 ///
 /// * `colorscheme` - It takes the colorscheme name as an argument.
 /// * `theme` - It takes the theme name as an argument.
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
 /// * `query` - It takes the current search query provided by the user as an argument.
 /// * `search_results` - It takes the aggregated search results as an argument.
+/// * `url_settings` - It takes the preferences passed as url parameters as an argument, they are
+///   carried over to the links of the other pages.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Returns
 ///
@@ -23,16 +31,29 @@ pub fn search(
     colorscheme: &str,
     theme: &str,
     animation: &Option<String>,
+    base_path: &str,
     query: &str,
     page: u32,
     search_results: &SearchResults,
+    url_settings: &[(&str, String)],
+    locale: &Locale,
 ) -> Markup {
+    let settings_query: String = url_settings
+        .iter()
+        .map(|(name, value)| {
+            form_urlencoded::Serializer::new(String::from("&"))
+                .append_pair(name, value)
+                .finish()
+        })
+        .collect();
     html!(
-        (header(colorscheme, theme, animation))
+        (header(colorscheme, theme, animation, base_path, locale))
         main class="results"{
-           (search_bar(&search_results.engine_errors_info, search_results.safe_search_level, query))
+           (search_bar(&search_results.engine_errors_info, search_results.safe_search_level, query, url_settings, locale))
            .results_aggregated{
               @if !search_results.results.is_empty() {
+                  // The titles and descriptions have been sanitized by the aggregator so only the
+                  // allowed formatting tags are left in them.
                   @for result in search_results.results.iter(){
                       .result {
                          h1{a href=(result.url){(PreEscaped(&result.title))}}
@@ -49,75 +70,66 @@ pub fn search(
               @else if search_results.disallowed{
                  .result_disallowed{
                     .description{
-                       p{
-                          "Your search - "{span class="user_query"{(query)}}" -
-                          has been disallowed."
-                       }
-                       p class="description_paragraph"{"Dear user,"}
-                       p class="description_paragraph"{
-                          "The query - "{span class="user_query"{(query)}}" - has
-                          been blacklisted via server configuration and hence disallowed by the
-                          server. Henceforth no results could be displayed for your query."
-                       }
+                       @let (before, after) = locale.split("search.disallowed", "{query}");
+                       p{(before){span class="user_query"{(query)}}(after)}
+                       p class="description_paragraph"{(locale.text("search.dear_user"))}
+                       @let (before, after) = locale.split("search.disallowed_description", "{query}");
+                       p class="description_paragraph"{(before){span class="user_query"{(query)}}(after)}
                     }
-                    img src="./images/barricade.png" alt="Image of a Barricade";
+                    img src="./images/barricade.png" alt=(locale.text("search.disallowed_alt"));
                  }
               }
               @else if search_results.filtered {
                  .result_filtered{
                     .description{
-                       p{
-                          "Your search - "{span class="user_query"{(query)}}" -
-                          has been filtered."
-                       }
-                       p class="description_paragraph"{"Dear user,"}
-                       p class="description_paragraph"{
-                          "All the search results contain results that has been configured to be
-                          filtered out via server configuration and henceforth has been
-                          completely filtered out."
-                       }
+                       @let (before, after) = locale.split("search.filtered", "{query}");
+                       p{(before){span class="user_query"{(query)}}(after)}
+                       p class="description_paragraph"{(locale.text("search.dear_user"))}
+                       p class="description_paragraph"{(locale.text("search.filtered_description"))}
                     }
-                    img src="./images/filter.png" alt="Image of a paper inside a funnel";
+                    img src="./images/filter.png" alt=(locale.text("search.filtered_alt"));
                  }
               }
               @else if search_results.no_engines_selected {
                  .result_engine_not_selected{
                     .description{
-                       p{
-                          "No results could be fetched for your search '{span class="user_query"{(query)}}'."
-                       }
-                       p class="description_paragraph"{"Dear user,"}
-                       p class="description_paragraph"{
-                          "No results could be retrieved from the upstream search engines as no
-                          upstream search engines were selected from the settings page."
-                       }
+                       @let (before, after) = locale.split("search.no_engines", "{query}");
+                       p{(before){span class="user_query"{(query)}}(after)}
+                       p class="description_paragraph"{(locale.text("search.dear_user"))}
+                       p class="description_paragraph"{(locale.text("search.no_engines_description"))}
                     }
-                    img src="./images/no_selection.png" alt="Image of a white cross inside a red circle";
+                    img src="./images/no_selection.png" alt=(locale.text("search.no_engines_alt"));
                  }
               }
               @else {
                  .result_not_found {
-                    p{"Your search - "{(query)}" - did not match any documents."}
-                    p class="suggestions"{"Suggestions:"}
+                    @let (before, after) = locale.split("search.not_found", "{query}");
+                    p{(before)(query)(after)}
+                    p class="suggestions"{(locale.text("search.suggestions"))}
                     ul{
-                       li{"Make sure that all words are spelled correctly."}
-                       li{"Try different keywords."}
-                       li{"Try more general keywords."}
+                       li{(locale.text("search.suggestion_spelling"))}
+                       li{(locale.text("search.suggestion_keywords"))}
+                       li{(locale.text("search.suggestion_general"))}
                     }
-                    img src="./images/no_results.gif" alt="Man fishing gif";
+                    img src="./images/no_results.gif" alt=(locale.text("search.not_found_alt"));
                  }
               }
             }
             .page_navigation {
-               a href=(format!("/search?q={}&safesearch={}&page={}", query, search_results.safe_search_level, if page > 1 {page-1} else {1})) {
-                   (PreEscaped("&#8592;")) "previous"
+               // The arrows point in the reading direction of the language.
+               @let (previous_arrow, next_arrow) = match locale.direction() {
+                   "rtl" => ("&#8594;", "&#8592;"),
+                   _ => ("&#8592;", "&#8594;"),
+               };
+               a href=(format!("search?q={}&safesearch={}&page={}{}", query, search_results.safe_search_level, if page > 1 {page-1} else {1}, settings_query)) {
+                   (PreEscaped(previous_arrow)) (locale.text("search.previous"))
                }
-               a href=(format!("/search?q={}&safesearch={}&page={}", query, search_results.safe_search_level, page+2)) {
-                  "next" (PreEscaped("&#8594;"))}
+               a href=(format!("search?q={}&safesearch={}&page={}{}", query, search_results.safe_search_level, page+2, settings_query)) {
+                  (locale.text("search.next")) (PreEscaped(next_arrow))}
             }
         }
         script src="static/index.js"{}
         script src="static/error_box.js"{}
-        (footer())
+        (footer(locale))
     )
 }
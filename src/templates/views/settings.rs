@@ -4,11 +4,14 @@ use std::collections::HashMap;
 
 use maud::{html, Markup};
 
-use crate::templates::partials::{
-    footer::footer,
-    header::header,
-    settings_tabs::{
-        cookies::cookies, engines::engines, general::general, user_interface::user_interface,
+use crate::templates::{
+    i18n::Locale,
+    partials::{
+        footer::footer,
+        header::header,
+        settings_tabs::{
+            cookies::cookies, engines::engines, general::general, user_interface::user_interface,
+        },
     },
 };
 
@@ -20,7 +23,12 @@ use crate::templates::partials::{
 /// * `colorscheme` - It takes the colorscheme name as an argument.
 /// * `theme` - It takes the theme name as an argument.
 /// * `animation` - It takes the animation name as an argument.
+/// * `base_path` - It takes the path prefix under which the website is served as an argument.
 /// * `engine_names` - It takes a list of engine names as an argument.
+/// * `profiles_enabled` - It takes whether the preference profiles are enabled on the server as
+/// an argument.
+/// * `profile_token` - It takes the token of the profile in use on this device as an argument.
+/// * `locale` - It takes the locale of the page as an argument.
 ///
 /// # Error
 ///
@@ -31,32 +39,39 @@ pub fn settings(
     colorscheme: &str,
     theme: &str,
     animation: &Option<String>,
+    base_path: &str,
     engine_names: &HashMap<String, bool>,
+    profiles_enabled: bool,
+    profile_token: Option<&str>,
+    locale: &Locale,
 ) -> Result<Markup, Box<dyn std::error::Error>> {
     Ok(html!(
-        (header(colorscheme, theme, animation))
+        (header(colorscheme, theme, animation, base_path, locale))
         main class="settings"{
-           h1{"Settings"}
+           h1{(locale.text("settings.title"))}
            hr;
            .settings_container{
+              // The tabs are matched by the `data-tab` attribute as their text is translated.
               .sidebar{
-                  div class="btn active" onclick="setActiveTab(this)"{"general"}
-                  .btn onclick="setActiveTab(this)"{"user interface"}
-                  .btn onclick="setActiveTab(this)"{"engines"}
-                  .btn onclick="setActiveTab(this)"{"cookies"}
+                  div class="btn active" data-tab="general"{(locale.text("settings.general.tab"))}
+                  .btn data-tab="user_interface"{(locale.text("settings.user_interface.tab"))}
+                  .btn data-tab="engines"{(locale.text("settings.engines.tab"))}
+                  .btn data-tab="cookies"{(locale.text("settings.cookies.tab"))}
               }
               .main_container{
-                  (general(safe_search_level))
-                  (user_interface(theme, colorscheme, animation)?)
-                  (engines(engine_names))
-                  (cookies())
-                  p class="message"{}
-                  button type="submit" onclick="setClientSettings()"{"Save"}
+                  (general(safe_search_level, locale))
+                  (user_interface(theme, colorscheme, animation, locale)?)
+                  (engines(engine_names, locale))
+                  (cookies(base_path, profiles_enabled, profile_token, locale))
+                  // The messages are shown by the `settings.js` script.
+                  p class="message" data-saved=(locale.text("settings.saved")) data-copied-url=(locale.text("settings.copied_url")){}
+                  button type="submit" class="save_button"{(locale.text("settings.save"))}
+                  button type="button" class="copy_settings_url_button"{(locale.text("settings.copy_url"))}
               }
            }
         }
-        script src="static/settings.js"{}
+        // The `settings.js` script is loaded by the footer on every page.
         script src="static/cookies.js"{}
-        (footer())
+        (footer(locale))
     ))
 }
@@ -1,17 +1,32 @@
 use std::{net::TcpListener, sync::OnceLock};
 
-use websurfx::{config::parser::Config, run, templates::views};
+use websurfx::{
+    config::{parser::Config, reload::SharedConfig},
+    models::parser_models::CacheAdminConfig,
+    run,
+    templates::{i18n, views},
+};
 
 /// A static constant for holding the parsed config.
-static CONFIG: OnceLock<Config> = OnceLock::new();
+static CONFIG: OnceLock<SharedConfig> = OnceLock::new();
+
+/// The token of the cache administration routes enabled for the tests.
+const ADMIN_TOKEN: &str = "test-admin-token";
 
 // Starts a new instance of the HTTP server, bound to a random available port
 async fn spawn_app() -> String {
     // Binding to port 0 will trigger the OS to assign a port for us.
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
     let port = listener.local_addr().unwrap().port();
-    let config = CONFIG.get_or_init(|| Config::parse(false).unwrap());
-    let cache = websurfx::cache::cacher::create_cache(config).await;
+    let config = CONFIG.get_or_init(|| {
+        let mut config = Config::parse(false).unwrap();
+        config.cache_admin = CacheAdminConfig {
+            enabled: true,
+            token: Some(ADMIN_TOKEN.to_owned()),
+        };
+        SharedConfig::new(config)
+    });
+    let cache = websurfx::cache::cacher::create_cache(&config.load()).await;
     let server = run(listener, config, cache).expect("Failed to bind address");
 
     tokio::spawn(server);
@@ -27,14 +42,68 @@ async fn test_index() {
     assert_eq!(res.status(), 200);
 
     let config = Config::parse(true).unwrap();
+    // The request has neither a cookie nor an `Accept-Language` header so the server picks the
+    // locale of the config.
+    let locale = i18n::negotiate(None, None, &config.language);
     let template = views::index::index(
         &config.style.colorscheme,
         &config.style.theme,
         &config.style.animation,
+        &config.base_path,
+        &[],
+        locale,
     )
     .0;
     assert_eq!(res.text().await.unwrap(), template);
 }
 
+#[tokio::test]
+async fn test_search_with_url_preferences() {
+    let address = spawn_app().await;
+
+    let client = reqwest::Client::new();
+    let res = client
+        .get(format!(
+            "{address}search?q=rust&theme=simple&colorscheme=nord&engines=duckduckgo"
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+
+    // The preferences passed as url parameters are applied to the page and carried over to the
+    // links of the next pages.
+    let body = res.text().await.unwrap();
+    assert!(body.contains("static/colorschemes/nord.css"));
+    assert!(body.contains("static/themes/simple.css"));
+    assert!(body.contains("engines=duckduckgo"));
+}
+
+#[tokio::test]
+async fn test_cache_admin_token_passes_rate_limiter() {
+    let address = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    // The bearer token is checked by the route itself and not taken for an API key by the rate
+    // limiter.
+    let res = client
+        .get(format!("{address}admin/cache/stats"))
+        .bearer_auth(ADMIN_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    assert!(res.headers().contains_key("x-ratelimit-limit"));
+
+    let res = client
+        .get(format!("{address}admin/cache/stats"))
+        .bearer_auth("wrong-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 401);
+    assert_eq!(res.headers()["www-authenticate"], "Bearer");
+}
+
 // TODO: Write tests for testing parameters for search function that if provided with something
 // other than u32 like alphabets and special characters than it should panic
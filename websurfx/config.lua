@@ -2,10 +2,15 @@
 logging = true -- an option to enable or disable logs.
 debug = false -- an option to enable or disable debug mode.
 threads = 10 -- the amount of threads that the app will use to run (the value should be greater than 0).
+log_format = "text" -- the format of the logs, either `text` for human readable lines or `json` for structured lines carrying the request id of each search.
+otlp_endpoint = nil -- url of an OpenTelemetry collector to which the traces should be exported (e.g. "http://127.0.0.1:4318/v1/traces"), requires the `otlp-tracing` feature. Set to nil to disable.
+strict_config = false -- whether to refuse to start (or reload) when the config has any issues (unknown, missing or out of range options) instead of falling back to default values.
 
 -- ### Server ###
 port = "8080" -- port on which server should be launched
 binding_ip = "127.0.0.1" --ip address on the which server should be launched.
+base_url = nil -- the public url (e.g. "https://example.com/search-engine/") or path prefix (e.g. "/search-engine") under which the website is served behind a reverse proxy. Set to nil to serve it at the root.
+trusted_proxies = {} -- the addresses or ranges (e.g. { "127.0.0.1", "10.0.0.0/8" }) of the reverse proxies trusted to provide the real IP address of the clients in the `Forwarded` or `X-Forwarded-For` headers.
 production_use = false -- whether to use production mode or not (in other words this option should be used if it is to be used to host it on the server to provide a service to a large number of users (more than one))
 -- if production_use is set to true
 -- There will be a random delay before sending the request to the search engines, this is to prevent DDoSing the upstream search engines from a large number of simultaneous requests.
@@ -15,6 +20,9 @@ pool_idle_connection_timeout = 30 -- timeout for the idle connections in the req
 rate_limiter = {
 	number_of_requests = 20, -- The number of request that are allowed within a provided time limit.
 	time_limit = 3, -- The time limit in which the quantity of requests that should be accepted.
+	-- search = { number_of_requests = 10, time_limit = 3 }, -- The quota of the search route (defaults to the one above).
+	-- static_files = { number_of_requests = 50, time_limit = 1 }, -- The quota of the static files (defaults to the one above).
+	-- api_keys = { { key = "change-me", number_of_requests = 100, time_limit = 1 } }, -- The keys of the JSON API clients, each with its own quota.
 }
 -- Set whether the server will use an adaptive/dynamic HTTPS window size, see https://httpwg.org/specs/rfc9113.html#fc-principles
 https_adaptive_window_size = false
@@ -25,6 +33,23 @@ number_of_https_connections = 10 -- the number of https connections that should
 -- Set keep-alive timer in seconds; keeps clients connected to the HTTP server, different from the connection to upstream search engines
 client_connection_keep_alive = 120
 
+-- ### Metrics ###
+metrics = {
+	enabled = false, -- whether to expose the prometheus metrics on the `/metrics` route or not.
+	port = nil, -- port of a separate admin server on which the metrics should be served (set to nil to serve them on the main server).
+}
+
+-- ### Security Headers ###
+-- The headers sent with every response (set an option to an empty string to not send its header).
+security_headers = {
+	content_security_policy = "default-src 'self'; img-src 'self' data:; object-src 'none'; base-uri 'self'; form-action 'self'",
+	frame_ancestors = "'none'", -- the sources allowed to embed the pages in a frame.
+	referrer_policy = "no-referrer",
+	permissions_policy = "camera=(), geolocation=(), microphone=(), payment=(), usb=()",
+	content_type_nosniff = true, -- whether to send the `X-Content-Type-Options: nosniff` header or not.
+	hsts_max_age = nil, -- the max age (in seconds) of the `Strict-Transport-Security` header, only set it when the website is served over https.
+}
+
 -- ### Search ###
 -- Filter results based on different levels. The levels provided are:
 -- {{
@@ -63,10 +88,50 @@ theme = "simple" -- the theme name which should be used for the website
 -- simple-frosted-glow
 -- }}
 animation = "simple-frosted-glow" -- the animation name which should be used with the theme or `nil` if you don't want any animations.
+-- The different languages provided are:
+-- {{
+-- en (English)
+-- ar (Arabic)
+-- }}
+language = "en" -- the language of the website used when the browser of the user does not accept any of the provided languages.
 
 -- ### Caching ###
-redis_url = "redis://127.0.0.1:8082" -- redis connection url address on which the client should connect on.
+redis_url = "redis://127.0.0.1:8082" -- redis connection url address on which the client should connect on (`rediss://` enables TLS).
+-- The deployment and the credentials of the redis servers, it is only used when the app is compiled with the `redis-cache` feature.
+redis = {
+	mode = "standalone", -- "standalone", "sentinel" or "cluster".
+	nodes = nil, -- the urls of the sentinels or of the cluster nodes (like `{ "redis://10.0.0.1:26379", "redis://10.0.0.2:26379" }`), `redis_url` is used when set to `nil`.
+	service_name = "mymaster", -- the name of the master monitored by the sentinels.
+	username = nil, -- the ACL username.
+	password = nil, -- the password (it can be set with the `WEBSURFX_REDIS__PASSWORD` environment variable).
+	pool_size = 5, -- the number of connections kept open.
+	key_prefix = "default:", -- the prefix added to the keys, so that several instances can share the same redis servers (the cached results are stored under `<key_prefix>websurfx:results:`).
+}
 cache_expiry_time = 600 -- This option takes the expiry time of the search results (value in seconds and the value should be greater than or equal to 60 seconds).
+cache_stale_time = 0 -- The time in seconds during which the expired search results are still served while they are refreshed in the background (`0` disables it).
+-- The cache stored in a SQLite file, it is only used when the app is compiled with the `disk-cache` feature.
+disk_cache = {
+	path = "websurfx-cache.sqlite", -- the path to the SQLite file.
+	max_size = 512, -- the maximum size of the cached results in megabytes, the least recently used results are evicted above it.
+	compaction_interval = 300, -- the time in seconds between two compactions of the SQLite file.
+}
+-- The keys encrypting the cached results, they are only used when the app is compiled with the `encrypt-cache-results` or `cec-cache-results` feature.
+cache_encryption = {
+	keys = nil, -- the keys made of 64 hexadecimal characters (like the output of `openssl rand -hex 32`) from the newest to the oldest, the newest key encrypts the results while all of them decrypt them (they can be set with the `WEBSURFX_CACHE_ENCRYPTION__KEYS` environment variable).
+	key_file = nil, -- the path to a file holding one key per line, its keys are used after the ones of `keys`.
+}
+-- The routes under `/admin/cache` to inspect and remove the cached results.
+cache_admin = {
+	enabled = false,
+	token = nil, -- the token to provide as a bearer token in the `Authorization` header (it can be set with the `WEBSURFX_CACHE_ADMIN__TOKEN` environment variable).
+}
+
+-- ### Profiles ###
+-- The preference profiles saved on the server which can be applied on any device with their link.
+profiles = {
+	enabled = false,
+	sqlite_path = nil, -- the path to a SQLite file storing the profiles (requires the `sqlite-profiles` feature), they are stored in the cache when set to `nil`.
+}
 -- ### Search Engines ###
 upstream_search_engines = {
     DuckDuckGo = true,